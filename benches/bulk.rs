@@ -0,0 +1,40 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ringbuffer_spsc::RingBuffer;
+use std::hint::black_box;
+
+const CAPACITY: usize = 4096;
+
+fn bulk(c: &mut Criterion) {
+    let mut group = c.benchmark_group("u8");
+    for len in [16, 256, 4096] {
+        let src = vec![42u8; len];
+        let mut dst = vec![0u8; len];
+        group.throughput(Throughput::Bytes(len as u64));
+
+        let (mut tx, mut rx) = RingBuffer::<u8, CAPACITY>::init();
+        group.bench_with_input(BenchmarkId::new("push_pull", len), &len, |b, _| {
+            b.iter(|| {
+                for &t in black_box(&src) {
+                    tx.push(t);
+                }
+                for d in dst.iter_mut() {
+                    *d = rx.pull().unwrap();
+                }
+                black_box(&dst);
+            })
+        });
+
+        let (mut tx, mut rx) = RingBuffer::<u8, CAPACITY>::init();
+        group.bench_with_input(BenchmarkId::new("push_pull_slice", len), &len, |b, _| {
+            b.iter(|| {
+                tx.push_slice(black_box(&src));
+                rx.pull_slice(&mut dst);
+                black_box(&dst);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bulk);
+criterion_main!(benches);
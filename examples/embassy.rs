@@ -0,0 +1,49 @@
+//! An embassy task awaiting bytes pushed by an interrupt handler.
+//!
+//! On a real target the writer would be owned by the UART RX interrupt
+//! handler, which pushes every received byte and never blocks. Here a thread
+//! plays the role of the interrupt so that the example runs on the host with
+//! embassy's `arch-std` executor.
+use embassy_executor::Spawner;
+use ringbuffer_spsc::{RingBuffer, RingBufferReader, RingBufferWriter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+const N: usize = 1_000;
+
+static OVERRUNS: AtomicUsize = AtomicUsize::new(0);
+
+fn uart_rx_interrupt(tx: &mut RingBufferWriter<u8, 64>, byte: u8) {
+    // An interrupt handler cannot wait for the consumer: drop the byte if full.
+    if tx.push(byte).is_some() {
+        OVERRUNS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[embassy_executor::task]
+async fn consumer(mut rx: RingBufferReader<u8, 64>) {
+    let mut received: usize = 0;
+    // The waker is stored in the ring buffer itself: waiting does not allocate.
+    while let Some(_byte) = rx.pull_async().await {
+        received += 1;
+    }
+    println!(
+        "{received} bytes received, {} overruns",
+        OVERRUNS.load(Ordering::Relaxed)
+    );
+    std::process::exit(0);
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let (mut tx, rx) = RingBuffer::<u8, 64>::init();
+
+    std::thread::spawn(move || {
+        for byte in 0..N {
+            uart_rx_interrupt(&mut tx, byte as u8);
+            std::thread::sleep(Duration::from_micros(10));
+        }
+    });
+
+    spawner.spawn(consumer(rx)).unwrap();
+}
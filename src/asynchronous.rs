@@ -0,0 +1,473 @@
+//! Async support, available with the `async` feature.
+//!
+//! The wakers of the pending writer and reader are stored in the shared ring
+//! buffer state and no allocation happens while waiting, so the futures can
+//! be driven by `no_std` executors like embassy as well as by std runtimes.
+//!
+//! Notifications are coalesced: the reader is only woken when a write makes
+//! the ring buffer non-empty, and the writer when a read takes it below the
+//! level it is waiting for, i.e. from full to non-full with respect to the
+//! [soft limit](RingBufferWriter::set_soft_limit), or past the
+//! [watermark](RingBufferWriter::set_watermark). Other reads and writes do
+//! not touch the wakers at all, which keeps streaming at full speed cheap.
+use crate::{
+    atomic::fence,
+    index,
+    storage::{Inline, Storage},
+    DefaultRef, Index, RingBuffer, RingBufferReader, RingBufferWriter,
+};
+use core::{
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::atomic::Ordering,
+    task::{ready, Context, Poll, Waker},
+};
+
+impl<T, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Wake the pending reader if the write index just moved past `old_idx_w`
+    /// while the ring buffer was empty.
+    #[inline]
+    pub(crate) fn notify_reader(&self, old_idx_w: Index) {
+        // Pairs with the fence in `register_reader`: either the reader sees the
+        // new write index when checking again, or we see that it had caught up.
+        fence(Ordering::SeqCst);
+        if self.idx_r.load(Ordering::Relaxed) == old_idx_w {
+            self.rx_waker.wake();
+        }
+    }
+
+    /// Wake the pending writer if the read index just moved from `old_idx_r`
+    /// to `new_idx_r` past the one it waits for.
+    #[inline]
+    pub(crate) fn notify_writer(&self, old_idx_r: Index, new_idx_r: Index) {
+        // Pairs with the fence in `register_writer`
+        fence(Ordering::SeqCst);
+        let at = self.tx_wake_at.load(Ordering::Relaxed);
+        if at.wrapping_sub(old_idx_r).wrapping_sub(1) < new_idx_r.wrapping_sub(old_idx_r) {
+            self.tx_waker.wake();
+        }
+    }
+
+    /// Register the waker of the reader, to be woken once the ring buffer is not empty.
+    fn register_reader(&self, waker: &Waker) {
+        self.rx_waker.register(waker);
+        fence(Ordering::SeqCst);
+    }
+
+    /// Register the waker of the writer, to be woken once the read index reaches `at`.
+    fn register_writer(&self, waker: &Waker, at: Index) {
+        self.tx_waker.register(waker);
+        self.tx_wake_at.store(at, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the number of elements ready to be read, refreshing the
+    /// cached write index only if the ring buffer looks empty.
+    #[inline]
+    pub(crate) fn available(&mut self) -> usize {
+        if self.local_idx_r == self.cached_idx_w {
+            self.refresh_idx_w();
+        }
+        index::len(self.local_idx_r, self.cached_idx_w)
+    }
+
+    /// Poll for elements ready to be read, registering the current task
+    /// for a wakeup if there are none.
+    ///
+    /// Returns `Poll::Ready(0)` once the writer has been dropped and
+    /// the ring buffer has been completely drained.
+    pub(crate) fn poll_available(&mut self, cx: &mut Context<'_>) -> Poll<usize> {
+        let n = self.available();
+        if n > 0 {
+            return Poll::Ready(n);
+        }
+        // Retry before paying for the waker registration
+        for _ in 0..self.spins {
+            core::hint::spin_loop();
+            let n = self.available();
+            if n > 0 {
+                return Poll::Ready(n);
+            }
+        }
+
+        self.inner.register_reader(cx.waker());
+        // Check again after registering the waker: the writer might have pushed
+        // an element or have been dropped in the meantime without noticing us.
+        let dropped = self.is_writer_dropped();
+        let n = self.available();
+        if n > 0 || dropped {
+            Poll::Ready(n)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Register `waker` to be woken the next time the writer pushes into the
+    /// empty ring buffer or is dropped.
+    ///
+    /// This is the hook [`Self::pull_async`] is built on, for executors and
+    /// RTOSes integrating their own notification mechanism: a raw callback
+    /// can be wrapped with [`Waker::from_raw`]. Only the last registered waker
+    /// is woken, and the ring buffer must be checked again after registering
+    /// since an element might have been pushed in the meantime.
+    pub fn register_data_waker(&mut self, waker: &Waker) {
+        self.inner.register_reader(waker);
+    }
+
+    /// Set how many times the async operations check the ring buffer again
+    /// before registering a waker when it is empty.
+    ///
+    /// Registering a waker and being woken up costs much more than a check,
+    /// so spinning a little pays off when the writer is expected to push
+    /// shortly, at the expense of keeping the executor busy meanwhile.
+    /// Nothing is retried by default.
+    pub fn set_async_spins(&mut self, spins: u32) {
+        self.spins = spins;
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Register `waker` to be woken the next time the reader pulls from the
+    /// full ring buffer, with respect to the soft limit, or is dropped.
+    ///
+    /// See [`RingBufferReader::register_data_waker`].
+    pub fn register_space_waker(&mut self, waker: &Waker) {
+        let at = self.vacant_idx_r(1);
+        self.inner.register_writer(waker, at);
+    }
+
+    /// Return the read index at which `k` slots below the soft limit are free.
+    #[inline]
+    fn vacant_idx_r(&self, k: usize) -> Index {
+        index::add(index::sub(self.local_idx_w, self.limit), k)
+    }
+
+    /// Set how many times the async operations check the ring buffer again
+    /// before registering a waker when it is full.
+    ///
+    /// See [`RingBufferReader::set_async_spins`].
+    pub fn set_async_spins(&mut self, spins: u32) {
+        self.spins = spins;
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the number of free slots below the soft limit, refreshing
+    /// the cached read index only if there look to be fewer than `k`.
+    #[inline]
+    pub(crate) fn vacant(&mut self, k: usize) -> usize {
+        if self
+            .limit
+            .saturating_sub(index::len(self.cached_idx_r, self.local_idx_w))
+            < k
+        {
+            self.refresh_idx_r();
+        }
+        self.limit
+            .saturating_sub(index::len(self.cached_idx_r, self.local_idx_w))
+    }
+
+    /// Poll for free slots, registering the current task for a wakeup
+    /// if there are none.
+    ///
+    /// Returns `Poll::Ready(0)` if the ring buffer is full and the reader
+    /// has been dropped, since no slot is going to be released anymore.
+    #[inline]
+    pub(crate) fn poll_vacant(&mut self, cx: &mut Context<'_>) -> Poll<usize> {
+        self.poll_vacancy(cx, 1)
+    }
+
+    /// Poll for at least `k` free slots, registering the current task for
+    /// a wakeup if there are fewer.
+    ///
+    /// Returns `Poll::Ready` with fewer slots if the reader has been dropped,
+    /// since no slot is going to be released anymore.
+    pub(crate) fn poll_vacancy(&mut self, cx: &mut Context<'_>, k: usize) -> Poll<usize> {
+        let n = self.vacant(k);
+        if n >= k {
+            return Poll::Ready(n);
+        }
+        // Retry before paying for the waker registration
+        for _ in 0..self.spins {
+            core::hint::spin_loop();
+            let n = self.vacant(k);
+            if n >= k {
+                return Poll::Ready(n);
+            }
+        }
+
+        self.inner.register_writer(cx.waker(), self.vacant_idx_r(k));
+        // Check again after registering the waker: the reader might have pulled
+        // elements or have been dropped in the meantime without noticing us.
+        let dropped = self.is_reader_dropped();
+        let n = self.vacant(k);
+        if n >= k || dropped {
+            Poll::Ready(n)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Poll for pushing the element in `item`, registering the current task
+    /// for a wakeup if the ring buffer is full.
+    ///
+    /// The element is taken out of `item` once the poll is ready, and stays
+    /// there while it is pending, so that manual futures can keep it in their
+    /// own state. It is handed back if the reader has been dropped.
+    ///
+    /// # Panics
+    /// This function panics if `item` is `None`.
+    pub fn poll_push(&mut self, cx: &mut Context<'_>, item: &mut Option<T>) -> Poll<Result<(), T>> {
+        assert!(item.is_some(), "poll_push called without an element");
+        let n = if self.is_reader_dropped() {
+            0
+        } else {
+            ready!(self.poll_vacant(cx))
+        };
+        let t = item.take().unwrap();
+        if n == 0 {
+            return Poll::Ready(Err(t));
+        }
+        let res = self.push(t);
+        debug_assert!(res.is_none());
+        Poll::Ready(Ok(()))
+    }
+
+    /// Push an element, waiting for a free slot if the ring buffer is full.
+    ///
+    /// The element is handed back if the reader has been dropped.
+    pub fn push_async(&mut self, t: T) -> PushFuture<'_, T, N, R, S> {
+        PushFuture {
+            writer: self,
+            item: Some(t),
+        }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Poll for pulling an element, registering the current task for a wakeup
+    /// if the ring buffer is empty.
+    ///
+    /// Returns `Poll::Ready(None)` once the writer has been dropped and the
+    /// ring buffer is drained.
+    pub fn poll_pull(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match ready!(self.poll_available(cx)) {
+            0 => Poll::Ready(None),
+            _ => Poll::Ready(self.pull()),
+        }
+    }
+
+    /// Poll for the next element without removing it, registering the current
+    /// task for a wakeup if the ring buffer is empty.
+    ///
+    /// Returns `Poll::Ready(None)` once the writer has been dropped and the
+    /// ring buffer is drained.
+    pub fn poll_peek(&mut self, cx: &mut Context<'_>) -> Poll<Option<&T>> {
+        match ready!(self.poll_available(cx)) {
+            0 => Poll::Ready(None),
+            _ => Poll::Ready(self.peek()),
+        }
+    }
+
+    /// Pull an element, waiting for one to be pushed if the ring buffer is empty.
+    ///
+    /// Resolves to `None` once the writer has been dropped and the ring buffer is drained.
+    pub fn pull_async(&mut self) -> PullFuture<'_, T, N, R, S> {
+        PullFuture { reader: self }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Wait for the reader to reach the [watermark](Self::set_watermark), disarming it.
+    ///
+    /// Resolves to `false` right away if no watermark is armed, or once the reader
+    /// has been dropped without reaching it.
+    pub fn wait_watermark(&mut self) -> WatermarkFuture<'_, T, N, R, S> {
+        WatermarkFuture { writer: self }
+    }
+}
+
+/// Future returned by [`RingBufferWriter::wait_watermark`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WatermarkFuture<'a, T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    writer: &'a mut RingBufferWriter<T, N, R, S>,
+}
+
+impl<T, const N: usize, R, S> Future for WatermarkFuture<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let Some(target) = this.writer.watermark else {
+            return Poll::Ready(false);
+        };
+        if this.writer.watermark_reached() {
+            return Poll::Ready(true);
+        }
+
+        this.writer.inner.register_writer(cx.waker(), target);
+        // Check again after registering the waker: the reader might have pulled
+        // elements or have been dropped in the meantime without noticing us.
+        let dropped = this.writer.is_reader_dropped();
+        if this.writer.watermark_reached() {
+            Poll::Ready(true)
+        } else if dropped {
+            Poll::Ready(false)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Wait for the reader to pull every element pushed so far.
+    ///
+    /// The element kept back by [`Self::push_or_merge`] is published first.
+    /// Resolves to `false` if the reader is dropped before catching up.
+    pub fn wait_drained_async(&mut self) -> DrainedFuture<'_, T, N, R, S> {
+        DrainedFuture { writer: self }
+    }
+}
+
+/// Future returned by [`RingBufferWriter::wait_drained_async`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct DrainedFuture<'a, T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    writer: &'a mut RingBufferWriter<T, N, R, S>,
+}
+
+impl<T, const N: usize, R, S> Future for DrainedFuture<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if this.writer.is_drained() {
+            return Poll::Ready(true);
+        }
+
+        let target = this.writer.local_idx_w;
+        this.writer.inner.register_writer(cx.waker(), target);
+        // Check again after registering the waker: the reader might have pulled
+        // elements or have been dropped in the meantime without noticing us.
+        let dropped = this.writer.is_reader_dropped();
+        if this.writer.is_drained() {
+            Poll::Ready(true)
+        } else if dropped {
+            Poll::Ready(false)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Future returned by [`RingBufferWriter::push_async`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct PushFuture<'a, T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    writer: &'a mut RingBufferWriter<T, N, R, S>,
+    item: Option<T>,
+}
+
+// The element is never pinned
+impl<T, const N: usize, R, S> Unpin for PushFuture<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+}
+
+impl<T, const N: usize, R, S> Future for PushFuture<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    type Output = Result<(), T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        assert!(this.item.is_some(), "PushFuture polled after completion");
+        this.writer.poll_push(cx, &mut this.item)
+    }
+}
+
+/// Future returned by [`RingBufferReader::pull_async`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct PullFuture<'a, T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    reader: &'a mut RingBufferReader<T, N, R, S>,
+}
+
+impl<T, const N: usize, R, S> Future for PullFuture<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().reader.poll_pull(cx)
+    }
+}
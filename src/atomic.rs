@@ -0,0 +1,530 @@
+//! Atomic integer backing the indexes and the flags of the shared state.
+//!
+//! With the `critical-section` feature every access is performed inside a
+//! critical section instead of relying on native atomic instructions. This
+//! supports targets lacking them, or where the producer runs in an interrupt
+//! preempting the consumer and the platform atomics are not enough.
+//!
+//! With the `paranoid` feature every access is performed with `SeqCst` ordering
+//! and followed by a `SeqCst` fence, whatever the ordering requested. This is
+//! slower but rules out the ring buffer when debugging suspected memory
+//! ordering issues on weakly ordered hardware like ARM or POWER. Critical
+//! sections already provide the strongest ordering and take precedence.
+//!
+//! On wasm32 without the `atomics` target feature, there are no threads to
+//! share the ring buffer with, so the accesses are plain reads and writes of
+//! a `Cell` instead. This keeps the same API, so that code generic over the
+//! ring buffer also builds for single-threaded WASM. Critical sections still
+//! take precedence, e.g. to share the ring buffer with a host callback.
+//!
+//! With the `u64-indexes` feature, the read and write indexes are 64-bit
+//! integers whatever the width of `usize`, so that they do not wrap around
+//! every 2^32 elements on 32-bit targets. The backends above apply to them as
+//! well, the native one being the `AtomicU64` of the `portable-atomic` crate,
+//! which falls back to a lock on targets lacking native 64-bit atomics.
+//!
+//! When built with `--cfg shuttle`, the accesses go through the atomics of the
+//! `shuttle` crate instead, so that its randomized scheduler can explore their
+//! interleavings. This takes precedence over the features above.
+#[cfg(not(any(
+    feature = "critical-section",
+    feature = "paranoid",
+    shuttle,
+    all(target_arch = "wasm32", not(target_feature = "atomics"))
+)))]
+pub(crate) use core::sync::atomic::AtomicUsize;
+
+#[cfg(all(feature = "critical-section", not(shuttle)))]
+pub(crate) use self::cs::AtomicUsize;
+
+#[cfg(all(
+    feature = "paranoid",
+    not(feature = "critical-section"),
+    not(shuttle),
+    not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+))]
+pub(crate) use self::paranoid::AtomicUsize;
+
+#[cfg(all(
+    target_arch = "wasm32",
+    not(target_feature = "atomics"),
+    not(feature = "critical-section"),
+    not(shuttle)
+))]
+pub(crate) use self::single::AtomicUsize;
+
+#[cfg(shuttle)]
+pub(crate) use self::shuttle::AtomicUsize;
+
+// Atomic integer backing the read and write indexes
+#[cfg(not(feature = "u64-indexes"))]
+pub(crate) type AtomicIndex = AtomicUsize;
+
+#[cfg(all(
+    feature = "u64-indexes",
+    not(any(
+        feature = "critical-section",
+        feature = "paranoid",
+        shuttle,
+        all(target_arch = "wasm32", not(target_feature = "atomics"))
+    ))
+))]
+pub(crate) use portable_atomic::AtomicU64 as AtomicIndex;
+
+#[cfg(all(feature = "u64-indexes", feature = "critical-section", not(shuttle)))]
+pub(crate) use self::cs::AtomicU64 as AtomicIndex;
+
+#[cfg(all(
+    feature = "u64-indexes",
+    feature = "paranoid",
+    not(feature = "critical-section"),
+    not(shuttle),
+    not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+))]
+pub(crate) use self::paranoid::AtomicU64 as AtomicIndex;
+
+#[cfg(all(
+    feature = "u64-indexes",
+    target_arch = "wasm32",
+    not(target_feature = "atomics"),
+    not(feature = "critical-section"),
+    not(shuttle)
+))]
+pub(crate) use self::single::AtomicU64 as AtomicIndex;
+
+#[cfg(all(feature = "u64-indexes", shuttle))]
+compile_error!("The `u64-indexes` feature is not supported with `--cfg shuttle`.");
+
+#[cfg(all(feature = "async", shuttle))]
+pub(crate) use ::shuttle::sync::atomic::fence;
+#[cfg(all(feature = "async", not(shuttle)))]
+pub(crate) use core::sync::atomic::fence;
+
+#[cfg(all(
+    feature = "paranoid",
+    not(feature = "critical-section"),
+    not(shuttle),
+    not(all(target_arch = "wasm32", not(target_feature = "atomics")))
+))]
+mod paranoid {
+    use core::sync::atomic::{self, fence, Ordering};
+
+    pub(crate) struct AtomicUsize {
+        v: atomic::AtomicUsize,
+    }
+
+    impl AtomicUsize {
+        pub(crate) const fn new(v: usize) -> Self {
+            Self {
+                v: atomic::AtomicUsize::new(v),
+            }
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+        #[inline]
+        pub(crate) fn as_ptr(&self) -> *mut usize {
+            self.v.as_ptr()
+        }
+
+        #[inline]
+        fn with<R>(&self, f: impl FnOnce(&atomic::AtomicUsize) -> R) -> R {
+            let r = f(&self.v);
+            fence(Ordering::SeqCst);
+            r
+        }
+
+        #[inline]
+        pub(crate) fn load(&self, _order: Ordering) -> usize {
+            self.with(|v| v.load(Ordering::SeqCst))
+        }
+
+        #[cfg_attr(feature = "u64-indexes", allow(dead_code))]
+        #[inline]
+        pub(crate) fn store(&self, val: usize, _order: Ordering) {
+            self.with(|v| v.store(val, Ordering::SeqCst))
+        }
+
+        #[cfg(feature = "async")]
+        #[inline]
+        pub(crate) fn swap(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| v.swap(val, Ordering::SeqCst))
+        }
+
+        #[inline]
+        pub(crate) fn compare_exchange(
+            &self,
+            current: usize,
+            new: usize,
+            _success: Ordering,
+            _failure: Ordering,
+        ) -> Result<usize, usize> {
+            self.with(|v| v.compare_exchange(current, new, Ordering::SeqCst, Ordering::SeqCst))
+        }
+
+        #[inline]
+        pub(crate) fn fetch_and(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| v.fetch_and(val, Ordering::SeqCst))
+        }
+
+        #[cfg(any(feature = "alloc", feature = "async"))]
+        #[inline]
+        pub(crate) fn fetch_or(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| v.fetch_or(val, Ordering::SeqCst))
+        }
+
+        #[cfg(feature = "alloc")]
+        #[inline]
+        pub(crate) fn fetch_add(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| v.fetch_add(val, Ordering::SeqCst))
+        }
+
+        #[cfg(feature = "alloc")]
+        #[inline]
+        pub(crate) fn fetch_sub(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| v.fetch_sub(val, Ordering::SeqCst))
+        }
+    }
+
+    /// 64-bit counterpart of [`AtomicUsize`] for the indexes.
+    #[cfg(feature = "u64-indexes")]
+    pub(crate) struct AtomicU64 {
+        v: portable_atomic::AtomicU64,
+    }
+
+    #[cfg(feature = "u64-indexes")]
+    impl AtomicU64 {
+        pub(crate) const fn new(v: u64) -> Self {
+            Self {
+                v: portable_atomic::AtomicU64::new(v),
+            }
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+        #[inline]
+        pub(crate) fn as_ptr(&self) -> *mut u64 {
+            self.v.as_ptr()
+        }
+
+        #[inline]
+        pub(crate) fn load(&self, _order: Ordering) -> u64 {
+            let r = self.v.load(Ordering::SeqCst);
+            fence(Ordering::SeqCst);
+            r
+        }
+
+        #[inline]
+        pub(crate) fn store(&self, val: u64, _order: Ordering) {
+            self.v.store(val, Ordering::SeqCst);
+            fence(Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(all(feature = "critical-section", not(shuttle)))]
+mod cs {
+    use core::{cell::UnsafeCell, sync::atomic::Ordering};
+
+    pub(crate) struct AtomicUsize {
+        v: UnsafeCell<usize>,
+    }
+
+    // SAFETY: the value is only ever accessed inside a critical section
+    unsafe impl Sync for AtomicUsize {}
+
+    impl AtomicUsize {
+        pub(crate) const fn new(v: usize) -> Self {
+            Self {
+                v: UnsafeCell::new(v),
+            }
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+        #[inline]
+        pub(crate) fn as_ptr(&self) -> *mut usize {
+            self.v.get()
+        }
+
+        #[inline]
+        fn with<R>(&self, f: impl FnOnce(&mut usize) -> R) -> R {
+            critical_section::with(|_| f(unsafe { &mut *self.v.get() }))
+        }
+
+        #[inline]
+        pub(crate) fn load(&self, _order: Ordering) -> usize {
+            self.with(|v| *v)
+        }
+
+        // Only used by the indexes, which are not these atomics when 64-bit
+        #[cfg_attr(feature = "u64-indexes", allow(dead_code))]
+        #[inline]
+        pub(crate) fn store(&self, val: usize, _order: Ordering) {
+            self.with(|v| *v = val)
+        }
+
+        #[cfg(feature = "async")]
+        #[inline]
+        pub(crate) fn swap(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| core::mem::replace(v, val))
+        }
+
+        #[inline]
+        pub(crate) fn compare_exchange(
+            &self,
+            current: usize,
+            new: usize,
+            _success: Ordering,
+            _failure: Ordering,
+        ) -> Result<usize, usize> {
+            self.with(|v| {
+                if *v == current {
+                    *v = new;
+                    Ok(current)
+                } else {
+                    Err(*v)
+                }
+            })
+        }
+
+        #[inline]
+        pub(crate) fn fetch_and(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| core::mem::replace(v, *v & val))
+        }
+
+        #[cfg(any(feature = "alloc", feature = "async"))]
+        #[inline]
+        pub(crate) fn fetch_or(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| core::mem::replace(v, *v | val))
+        }
+
+        #[cfg(feature = "alloc")]
+        #[inline]
+        pub(crate) fn fetch_add(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| core::mem::replace(v, v.wrapping_add(val)))
+        }
+
+        #[cfg(feature = "alloc")]
+        #[inline]
+        pub(crate) fn fetch_sub(&self, val: usize, _order: Ordering) -> usize {
+            self.with(|v| core::mem::replace(v, v.wrapping_sub(val)))
+        }
+    }
+
+    /// 64-bit counterpart of [`AtomicUsize`] for the indexes.
+    #[cfg(feature = "u64-indexes")]
+    pub(crate) struct AtomicU64 {
+        v: UnsafeCell<u64>,
+    }
+
+    // SAFETY: the value is only ever accessed inside a critical section
+    #[cfg(feature = "u64-indexes")]
+    unsafe impl Sync for AtomicU64 {}
+
+    #[cfg(feature = "u64-indexes")]
+    impl AtomicU64 {
+        pub(crate) const fn new(v: u64) -> Self {
+            Self {
+                v: UnsafeCell::new(v),
+            }
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+        #[inline]
+        pub(crate) fn as_ptr(&self) -> *mut u64 {
+            self.v.get()
+        }
+
+        #[inline]
+        pub(crate) fn load(&self, _order: Ordering) -> u64 {
+            critical_section::with(|_| unsafe { *self.v.get() })
+        }
+
+        #[inline]
+        pub(crate) fn store(&self, val: u64, _order: Ordering) {
+            critical_section::with(|_| unsafe { *self.v.get() = val })
+        }
+    }
+}
+
+#[cfg(all(
+    target_arch = "wasm32",
+    not(target_feature = "atomics"),
+    not(feature = "critical-section"),
+    not(shuttle)
+))]
+mod single {
+    use core::{cell::Cell, sync::atomic::Ordering};
+
+    pub(crate) struct AtomicUsize {
+        v: Cell<usize>,
+    }
+
+    // SAFETY: wasm32 without the `atomics` target feature has no shared memory,
+    // so the value can only ever be accessed from a single thread
+    unsafe impl Sync for AtomicUsize {}
+
+    impl AtomicUsize {
+        pub(crate) const fn new(v: usize) -> Self {
+            Self { v: Cell::new(v) }
+        }
+
+        #[inline]
+        pub(crate) fn load(&self, _order: Ordering) -> usize {
+            self.v.get()
+        }
+
+        #[cfg_attr(feature = "u64-indexes", allow(dead_code))]
+        #[inline]
+        pub(crate) fn store(&self, val: usize, _order: Ordering) {
+            self.v.set(val)
+        }
+
+        #[cfg(feature = "async")]
+        #[inline]
+        pub(crate) fn swap(&self, val: usize, _order: Ordering) -> usize {
+            self.v.replace(val)
+        }
+
+        #[inline]
+        pub(crate) fn compare_exchange(
+            &self,
+            current: usize,
+            new: usize,
+            _success: Ordering,
+            _failure: Ordering,
+        ) -> Result<usize, usize> {
+            match self.v.get() {
+                v if v == current => {
+                    self.v.set(new);
+                    Ok(v)
+                }
+                v => Err(v),
+            }
+        }
+
+        #[inline]
+        pub(crate) fn fetch_and(&self, val: usize, _order: Ordering) -> usize {
+            self.v.replace(self.v.get() & val)
+        }
+
+        #[cfg(any(feature = "alloc", feature = "async"))]
+        #[inline]
+        pub(crate) fn fetch_or(&self, val: usize, _order: Ordering) -> usize {
+            self.v.replace(self.v.get() | val)
+        }
+
+        #[cfg(feature = "alloc")]
+        #[inline]
+        pub(crate) fn fetch_add(&self, val: usize, _order: Ordering) -> usize {
+            self.v.replace(self.v.get().wrapping_add(val))
+        }
+
+        #[cfg(feature = "alloc")]
+        #[inline]
+        pub(crate) fn fetch_sub(&self, val: usize, _order: Ordering) -> usize {
+            self.v.replace(self.v.get().wrapping_sub(val))
+        }
+    }
+
+    /// 64-bit counterpart of [`AtomicUsize`] for the indexes.
+    #[cfg(feature = "u64-indexes")]
+    pub(crate) struct AtomicU64 {
+        v: Cell<u64>,
+    }
+
+    // SAFETY: see `AtomicUsize`
+    #[cfg(feature = "u64-indexes")]
+    unsafe impl Sync for AtomicU64 {}
+
+    #[cfg(feature = "u64-indexes")]
+    impl AtomicU64 {
+        pub(crate) const fn new(v: u64) -> Self {
+            Self { v: Cell::new(v) }
+        }
+
+        #[inline]
+        pub(crate) fn load(&self, _order: Ordering) -> u64 {
+            self.v.get()
+        }
+
+        #[inline]
+        pub(crate) fn store(&self, val: u64, _order: Ordering) {
+            self.v.set(val)
+        }
+    }
+}
+
+#[cfg(shuttle)]
+mod shuttle {
+    use core::sync::atomic::Ordering;
+    use std::sync::OnceLock;
+
+    pub(crate) struct AtomicUsize {
+        init: usize,
+        // Created on first access, since shuttle atomics cannot be created in a const context
+        v: OnceLock<::shuttle::sync::atomic::AtomicUsize>,
+    }
+
+    impl AtomicUsize {
+        pub(crate) const fn new(v: usize) -> Self {
+            Self {
+                init: v,
+                v: OnceLock::new(),
+            }
+        }
+
+        #[inline]
+        fn get(&self) -> &::shuttle::sync::atomic::AtomicUsize {
+            self.v
+                .get_or_init(|| ::shuttle::sync::atomic::AtomicUsize::new(self.init))
+        }
+
+        #[inline]
+        pub(crate) fn load(&self, order: Ordering) -> usize {
+            self.get().load(order)
+        }
+
+        #[inline]
+        pub(crate) fn store(&self, val: usize, order: Ordering) {
+            self.get().store(val, order)
+        }
+
+        #[cfg(feature = "async")]
+        #[inline]
+        pub(crate) fn swap(&self, val: usize, order: Ordering) -> usize {
+            self.get().swap(val, order)
+        }
+
+        #[inline]
+        pub(crate) fn compare_exchange(
+            &self,
+            current: usize,
+            new: usize,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<usize, usize> {
+            self.get().compare_exchange(current, new, success, failure)
+        }
+
+        #[inline]
+        pub(crate) fn fetch_and(&self, val: usize, order: Ordering) -> usize {
+            self.get().fetch_and(val, order)
+        }
+
+        #[inline]
+        pub(crate) fn fetch_or(&self, val: usize, order: Ordering) -> usize {
+            self.get().fetch_or(val, order)
+        }
+
+        #[cfg(feature = "alloc")]
+        #[inline]
+        pub(crate) fn fetch_add(&self, val: usize, order: Ordering) -> usize {
+            self.get().fetch_add(val, order)
+        }
+
+        #[cfg(feature = "alloc")]
+        #[inline]
+        pub(crate) fn fetch_sub(&self, val: usize, order: Ordering) -> usize {
+            self.get().fetch_sub(val, order)
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Waiting strategy of the blocking operations, available with the `std` feature.
+
+const SPIN_LIMIT: u32 = 6;
+
+/// Spin with an exponentially growing number of iterations first, then
+/// yield the thread to the OS scheduler for as long as the wait lasts.
+///
+/// Pushing and pulling never block, so there is nothing to park on: a
+/// short spin catches the other side being just behind, yielding keeps
+/// the cost of longer waits low.
+pub(crate) struct Backoff {
+    step: u32,
+}
+
+impl Backoff {
+    pub(crate) fn new() -> Self {
+        Self { step: 0 }
+    }
+
+    pub(crate) fn snooze(&mut self) {
+        if self.step <= SPIN_LIMIT {
+            for _ in 0..1 << self.step {
+                core::hint::spin_loop();
+            }
+            self.step += 1;
+        } else {
+            std::thread::yield_now();
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! `bytes` integration for `u8` ring buffers, available with the `bytes` feature.
+//!
+//! The reader implements [`Buf`] over the elements ready to be read, and the
+//! writer implements [`BufMut`] over the free slots, so that both plug into
+//! codecs and network stacks working on the `bytes` traits without extra
+//! copies:
+//!
+//! ```
+//! use bytes::{Buf, BufMut};
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 16>::init();
+//! tx.put_u16(0x0102);
+//! tx.put_slice(b"abc");
+//! assert_eq!(rx.get_u16(), 0x0102);
+//! assert_eq!(rx.copy_to_bytes(3), &b"abc"[..]);
+//! assert!(!rx.has_remaining());
+//! ```
+//!
+//! Unlike [`BufMut::put`], [`RingBufferWriter::push_buf`] does not panic
+//! when the ring buffer gets full, and pushes as many bytes as fit instead.
+//!
+//! The regions only grow while the other side keeps pushing or pulling, so
+//! the values returned by the traits are lower bounds.
+use crate::{index, storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use bytes::{buf::UninitSlice, Buf, BufMut};
+use core::{ops::Deref, sync::atomic::Ordering};
+
+impl<const N: usize, R, S> Buf for RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.slots()
+    }
+
+    #[inline]
+    fn chunk(&self) -> &[u8] {
+        self.peek_slice().0
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.slots(),
+            "cannot advance past the readable bytes"
+        );
+        // SAFETY: the bytes are readable and do not need to be dropped
+        unsafe { self.release(cnt) };
+    }
+}
+
+impl<const N: usize, R, S> RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Push as many bytes of `src` as fit, advancing it and returning how many
+    /// have been pushed.
+    pub fn push_buf<B: Buf>(&mut self, src: &mut B) -> usize {
+        let mut pushed = 0;
+        while src.has_remaining() {
+            let chunk = src.chunk();
+            let n = self.push_slice(chunk);
+            let partial = n < chunk.len();
+            src.advance(n);
+            pushed += n;
+            if partial {
+                break;
+            }
+        }
+        pushed
+    }
+}
+
+unsafe impl<const N: usize, R, S> BufMut for RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    #[inline]
+    fn remaining_mut(&self) -> usize {
+        let used = index::len(self.inner.idx_r.load(Ordering::Acquire), self.local_idx_w);
+        self.limit.saturating_sub(used)
+    }
+
+    #[inline]
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        let free = self.remaining_mut();
+        let len = free.min(N - index::slot::<N>(self.local_idx_w));
+        // SAFETY: the slots are free and only accessible through the writer
+        let slots = unsafe { self.inner.get_slice_mut(self.local_idx_w, len) };
+        UninitSlice::uninit(slots)
+    }
+
+    #[inline]
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.remaining_mut(),
+            "cannot advance past the free slots"
+        );
+        self.publish(cnt);
+    }
+}
@@ -0,0 +1,168 @@
+//! Writer staging elements locally to transfer them in bursts.
+use crate::{
+    storage::{Inline, Storage},
+    DefaultRef, Producer, RingBuffer, RingBufferWriter,
+};
+use core::{
+    mem::{ManuallyDrop, MaybeUninit},
+    ops::Deref,
+    ptr,
+};
+
+/// A [`RingBufferWriter`] accumulating up to `B` elements in a local buffer.
+///
+/// The staged elements are transferred with a single bulk write and index
+/// publication once the local buffer is full or on [`Self::flush`], which
+/// amortizes the synchronization cost for producers emitting many small
+/// elements at once. Elements still staged when dropped are flushed if they fit.
+///
+/// ```
+/// use ringbuffer_spsc::{BurstWriter, RingBuffer};
+///
+/// let (tx, mut rx) = RingBuffer::<u8, 16>::init();
+/// let mut tx = BurstWriter::<_, 16, 4>::new(tx);
+/// for i in 0..3 {
+///     assert!(tx.push(i).is_none());
+/// }
+/// assert_eq!(rx.pull(), None);
+/// assert_eq!(tx.flush(), 3);
+/// assert_eq!(rx.pull(), Some(0));
+/// ```
+pub struct BurstWriter<T, const N: usize, const B: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    tx: RingBufferWriter<T, N, R, S>,
+    staged: [MaybeUninit<T>; B],
+    len: usize,
+}
+
+impl<T, const N: usize, const B: usize, R, S> BurstWriter<T, N, B, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    pub fn new(tx: RingBufferWriter<T, N, R, S>) -> Self {
+        Self {
+            tx,
+            // SAFETY: an array of `MaybeUninit` does not require initialization
+            staged: unsafe { MaybeUninit::uninit().assume_init() },
+            len: 0,
+        }
+    }
+
+    /// Flush the staged elements and get back the wrapped writer.
+    ///
+    /// The wrapper is handed back if some elements do not fit in the ring buffer.
+    // Handed back by value like `io::BufWriter::into_inner`, its staged elements included
+    #[allow(clippy::result_large_err)]
+    pub fn into_inner(mut self) -> Result<RingBufferWriter<T, N, R, S>, Self> {
+        self.flush();
+        if self.len > 0 {
+            return Err(self);
+        }
+        let this = ManuallyDrop::new(self);
+        // SAFETY: the wrapper is not dropped and has no staged element left
+        Ok(unsafe { ptr::read(&this.tx) })
+    }
+
+    /// Stage an element, flushing the local buffer once full.
+    ///
+    /// The element is handed back if both the local buffer and the ring buffer are full.
+    #[inline]
+    pub fn push(&mut self, t: T) -> Option<T> {
+        if self.len == B && self.flush() == 0 {
+            return Some(t);
+        }
+        self.staged[self.len].write(t);
+        self.len += 1;
+        if self.len == B {
+            self.flush();
+        }
+        None
+    }
+
+    /// Stage an element, merging it with `merge` into the last staged one if
+    /// both the local buffer and the ring buffer are full.
+    ///
+    /// Staged elements are not visible to the reader yet, so they can still be
+    /// updated, e.g. to coalesce damage rectangles or counters instead of
+    /// dropping the new element. The element is handed back only if `B` is zero.
+    #[inline]
+    pub fn push_or_merge<F>(&mut self, t: T, merge: F) -> Option<T>
+    where
+        F: FnOnce(&mut T, T),
+    {
+        let t = self.push(t)?;
+        let Some(last) = self.staged[..self.len].last_mut() else {
+            return Some(t);
+        };
+        // SAFETY: the first `len` staged elements are initialized
+        merge(unsafe { last.assume_init_mut() }, t);
+        None
+    }
+
+    /// Transfer as many staged elements as fit into the ring buffer,
+    /// returning how many have been transferred.
+    pub fn flush(&mut self) -> usize {
+        let chunk = self.tx.write_free_chunk(self.len);
+        let staged = &self.staged;
+        // SAFETY: the first `len` staged elements are initialized and only
+        // the ones moved into the chunk are considered moved out
+        let n = chunk.fill_from_iter(
+            staged[..self.len]
+                .iter()
+                .map(|t| unsafe { t.assume_init_read() }),
+        );
+        // Move the remaining elements to the front of the local buffer
+        unsafe {
+            let ptr = self.staged.as_mut_ptr();
+            ptr::copy(ptr.add(n), ptr, self.len - n);
+        }
+        self.len -= n;
+        n
+    }
+
+    /// Return the number of staged elements
+    #[inline]
+    pub fn staged(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, const N: usize, const B: usize, R, S> Drop for BurstWriter<T, N, B, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        self.flush();
+        for t in &mut self.staged[..self.len] {
+            unsafe { t.assume_init_drop() };
+        }
+    }
+}
+
+impl<T, const N: usize, const B: usize, R, S> Producer<T> for BurstWriter<T, N, B, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    #[inline]
+    fn push(&mut self, t: T) -> Option<T> {
+        BurstWriter::push(self, t)
+    }
+
+    /// Return the number of elements that can be staged before one is handed
+    /// back, flushing the local buffer along the way
+    #[inline]
+    fn slots(&self) -> usize {
+        B - self.len + Producer::slots(&self.tx)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
@@ -0,0 +1,100 @@
+//! Data cache maintenance for non-coherent DMA.
+//!
+//! On parts with a data cache that DMA engines bypass, like Cortex-M7 or
+//! Cortex-A, the cache has to be cleaned before a device reads memory written
+//! by the CPU, and invalidated before the CPU reads memory written by a
+//! device. [`CacheHooks`] registered on the handles are called on the regions
+//! concerned along the lifecycle of the slots:
+//!
+//! - the writer cleans the region returned by
+//!   [`RingBufferWriter::writable_ptr`] before a device fills it, so that no
+//!   dirty line is evicted over the data it writes;
+//! - the reader cleans the region returned by
+//!   [`RingBufferReader::readable_ptr`] before a device drains it;
+//! - the reader invalidates the elements published by the writer as soon as
+//!   it observes them, before reading them.
+//!
+//! For a device filling the ring buffer, register `clean` on the writer and
+//! `invalidate` on the reader; for a device draining it, register `clean` on
+//! the reader only. The hooks receive the address and the length in bytes of
+//! each contiguous region, and the slots should be aligned to cache lines,
+//! e.g. with [`CachePadded`](crate::CachePadded) elements, for the
+//! maintenance of a region not to affect its neighbours:
+//!
+//! ```
+//! use ringbuffer_spsc::{CacheHooks, RingBuffer};
+//!
+//! fn clean_dcache(_addr: *const u8, _len: usize) {
+//!     // e.g. `SCB::clean_dcache_by_address(addr as usize, len)` on a Cortex-M7
+//! }
+//!
+//! let (mut tx, _rx) = RingBuffer::<u8, 64>::init();
+//! tx.set_cache_hooks(CacheHooks {
+//!     clean: Some(clean_dcache),
+//!     ..CacheHooks::NONE
+//! });
+//! ```
+use crate::{index, storage::Storage, Index, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::{mem::size_of, ops::Deref};
+
+/// Cache maintenance operations, called with the address and the length in
+/// bytes of a region.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct CacheHooks {
+    /// Write back the dirty lines of the region to memory.
+    pub clean: Option<fn(*const u8, usize)>,
+    /// Discard the lines of the region, for the next reads to fetch memory.
+    pub invalidate: Option<fn(*const u8, usize)>,
+}
+
+impl CacheHooks {
+    /// No cache maintenance, the default.
+    pub const NONE: Self = Self {
+        clean: None,
+        invalidate: None,
+    };
+}
+
+impl<T, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Call `op` on the slots of the `n` elements following `idx`, in up to two regions.
+    pub(crate) fn maintain(&self, op: fn(*const u8, usize), idx: Index, n: usize) {
+        let start = index::slot::<N>(idx);
+        let first = n.min(N - start);
+        let slots = self.slots().cast::<u8>().cast_const();
+        // SAFETY: the offsets stay within the slots
+        unsafe {
+            op(slots.add(start * size_of::<T>()), first * size_of::<T>());
+            if n > first {
+                op(slots, (n - first) * size_of::<T>());
+            }
+        }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Register the cache maintenance of the writer, which only cleans the
+    /// regions returned by [`Self::writable_ptr`].
+    pub fn set_cache_hooks(&mut self, cache: CacheHooks) {
+        self.cache = cache;
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Register the cache maintenance of the reader, which cleans the regions
+    /// returned by [`Self::readable_ptr`] and invalidates the elements newly
+    /// published by the writer.
+    pub fn set_cache_hooks(&mut self, cache: CacheHooks) {
+        self.cache = cache;
+    }
+}
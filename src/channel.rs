@@ -0,0 +1,177 @@
+//! Adapter mirroring the `std::sync::mpsc` API, available with the `std` feature.
+//!
+//! [`channel`] returns a [`Sender`] and a [`Receiver`] with the same methods
+//! and error types as their `std::sync::mpsc` counterparts, so that a
+//! bounded channel with a single sender can be replaced without changes
+//! besides the imports and the capacity:
+//!
+//! ```
+//! use ringbuffer_spsc::channel::{channel, TryRecvError};
+//!
+//! let (mut tx, mut rx) = channel::<u32, 16>();
+//! let t = std::thread::spawn(move || {
+//!     for i in 0..100 {
+//!         tx.send(i).unwrap();
+//!     }
+//! });
+//! assert_eq!(rx.iter().sum::<u32>(), 4950);
+//! assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+//! t.join().unwrap();
+//! ```
+//!
+//! Unlike `std::sync::mpsc`, the sender cannot be cloned and both halves
+//! take `&mut self`. Blocking operations spin and yield while waiting.
+use crate::{backoff::Backoff, RingBuffer, RingBufferReader, RingBufferWriter, StdClock};
+pub use std::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError, TrySendError};
+use std::time::Duration;
+
+/// Create a channel holding up to `N` elements, which must be a power of two.
+pub fn channel<T, const N: usize>() -> (Sender<T, N>, Receiver<T, N>) {
+    let (tx, rx) = RingBuffer::init();
+    (Sender { tx }, Receiver { rx })
+}
+
+/// The sending half of a [`channel`].
+pub struct Sender<T, const N: usize> {
+    tx: RingBufferWriter<T, N>,
+}
+
+impl<T, const N: usize> Sender<T, N> {
+    /// Send a value, blocking while the channel is full.
+    ///
+    /// The value is handed back if the receiver has been dropped.
+    pub fn send(&mut self, mut t: T) -> Result<(), SendError<T>> {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.try_send(t) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(v)) => t = v,
+                Err(TrySendError::Disconnected(v)) => return Err(SendError(v)),
+            }
+            backoff.snooze();
+        }
+    }
+
+    /// Send a value without blocking.
+    pub fn try_send(&mut self, t: T) -> Result<(), TrySendError<T>> {
+        if self.tx.is_reader_dropped() {
+            return Err(TrySendError::Disconnected(t));
+        }
+        match self.tx.push(t) {
+            None => Ok(()),
+            Some(t) => Err(TrySendError::Full(t)),
+        }
+    }
+}
+
+/// The receiving half of a [`channel`].
+pub struct Receiver<T, const N: usize> {
+    rx: RingBufferReader<T, N>,
+}
+
+impl<T, const N: usize> Receiver<T, N> {
+    /// Receive a value, blocking while the channel is empty.
+    ///
+    /// Fails once the sender has been dropped and the channel is drained.
+    pub fn recv(&mut self) -> Result<T, RecvError> {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.try_recv() {
+                Ok(t) => return Ok(t),
+                Err(TryRecvError::Empty) => backoff.snooze(),
+                Err(TryRecvError::Disconnected) => return Err(RecvError),
+            }
+        }
+    }
+
+    /// Receive a value, blocking while the channel is empty for up to `timeout`.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let nanos = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+        match self.rx.pull_timeout(&StdClock::new(), nanos) {
+            Some(t) => Ok(t),
+            None if self.rx.is_writer_dropped() && self.rx.is_empty() => {
+                Err(RecvTimeoutError::Disconnected)
+            }
+            None => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
+    /// Receive a value without blocking.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        if let Some(t) = self.rx.pull() {
+            return Ok(t);
+        }
+        if !self.rx.is_writer_dropped() {
+            return Err(TryRecvError::Empty);
+        }
+        // The sender might have sent a last value before being dropped
+        self.rx.pull().ok_or(TryRecvError::Disconnected)
+    }
+
+    /// Return an iterator blocking for values until the sender is dropped
+    pub fn iter(&mut self) -> Iter<'_, T, N> {
+        Iter { rx: self }
+    }
+
+    /// Return an iterator over the values currently in the channel
+    pub fn try_iter(&mut self) -> TryIter<'_, T, N> {
+        TryIter { rx: self }
+    }
+}
+
+/// Iterator returned by [`Receiver::iter`].
+pub struct Iter<'a, T, const N: usize> {
+    rx: &'a mut Receiver<T, N>,
+}
+
+impl<T, const N: usize> Iterator for Iter<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+/// Iterator returned by [`Receiver::try_iter`].
+pub struct TryIter<'a, T, const N: usize> {
+    rx: &'a mut Receiver<T, N>,
+}
+
+impl<T, const N: usize> Iterator for TryIter<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// Iterator returned by [`Receiver::into_iter`].
+pub struct IntoIter<T, const N: usize> {
+    rx: Receiver<T, N>,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for Receiver<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        IntoIter { rx: self }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut Receiver<T, N> {
+    type Item = T;
+    type IntoIter = Iter<'a, T, N>;
+
+    fn into_iter(self) -> Iter<'a, T, N> {
+        self.iter()
+    }
+}
@@ -0,0 +1,595 @@
+//! Chunk-based access to multiple slots at once.
+//!
+//! The types and method names match the ones of the `rtrb` crate, so code
+//! written against its chunk API can switch to this ring buffer as is.
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u32, 8>::init();
+//!
+//! let mut chunk = tx.write_chunk_uninit(4).unwrap();
+//! let (first, second) = chunk.as_mut_slices();
+//! for (i, slot) in first.iter_mut().chain(second).enumerate() {
+//!     slot.write(i as u32);
+//! }
+//! unsafe { chunk.commit_all() };
+//!
+//! let chunk = rx.read_chunk(3).unwrap();
+//! assert_eq!(chunk.as_slices(), (&[0, 1, 2][..], &[][..]));
+//! chunk.commit_all();
+//! assert_eq!(rx.read_chunk(1).unwrap().into_iter().collect::<Vec<_>>(), [3]);
+//! ```
+use crate::{
+    index,
+    storage::{Inline, Storage},
+    DefaultRef, RingBuffer, RingBufferReader, RingBufferWriter,
+};
+use core::{fmt, mem::MaybeUninit, ops::Deref, sync::atomic::Ordering};
+
+/// Error returned when a chunk of the requested size is not available.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChunkError {
+    /// Contains the number of slots that are currently available.
+    TooFewSlots(usize),
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkError::TooFewSlots(n) => write!(f, "only {n} slots available in ring buffer"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChunkError {}
+
+/// Move elements from `iter` into the slots, returning how many have been initialized.
+///
+/// If `iter` panics, the elements already moved into the slots are dropped, so
+/// that the slots are left uninitialized as a whole.
+fn init_slots<T>(slots: [&mut [MaybeUninit<T>]; 2], iter: impl IntoIterator<Item = T>) -> usize {
+    struct Guard<'a, T> {
+        slots: [&'a mut [MaybeUninit<T>]; 2],
+        init: usize,
+    }
+
+    impl<T> Drop for Guard<'_, T> {
+        fn drop(&mut self) {
+            let [first, second] = &mut self.slots;
+            for slot in first.iter_mut().chain(second.iter_mut()).take(self.init) {
+                // SAFETY: the first `init` slots have been initialized
+                unsafe { slot.assume_init_drop() };
+            }
+        }
+    }
+
+    let len = slots[0].len() + slots[1].len();
+    let mut guard = Guard { slots, init: 0 };
+    let mut iter = iter.into_iter();
+    // Only take an element from the iterator when there is a slot for it
+    while guard.init < len {
+        let Some(t) = iter.next() else {
+            break;
+        };
+        let [first, second] = &mut guard.slots;
+        match first.get_mut(guard.init) {
+            Some(slot) => slot.write(t),
+            None => second[guard.init - first.len()].write(t),
+        };
+        guard.init += 1;
+    }
+    let init = guard.init;
+    core::mem::forget(guard);
+    init
+}
+
+#[inline]
+unsafe fn assume_init_ref<T>(slice: &[MaybeUninit<T>]) -> &[T] {
+    &*(slice as *const [MaybeUninit<T>] as *const [T])
+}
+
+#[inline]
+unsafe fn assume_init_mut<T>(slice: &mut [MaybeUninit<T>]) -> &mut [T] {
+    &mut *(slice as *mut [MaybeUninit<T>] as *mut [T])
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the number of free slots
+    #[inline]
+    pub fn slots(&self) -> usize {
+        let idx_r = self.inner.idx_r.load(Ordering::Acquire);
+        N - index::len(idx_r, self.local_idx_w)
+    }
+
+    /// Return `n` uninitialized slots for writing.
+    ///
+    /// The slots have to be initialized before committing them with
+    /// [`WriteChunkUninit::commit`] or [`WriteChunkUninit::commit_all`].
+    /// If not enough slots are free, an error with the number of free slots is returned.
+    pub fn write_chunk_uninit(
+        &mut self,
+        n: usize,
+    ) -> Result<WriteChunkUninit<'_, T, N, R, S>, ChunkError> {
+        #[cfg(feature = "rt-assert")]
+        let _section = crate::rt::Section::enter();
+        #[cfg(feature = "owner-check")]
+        self.owner.check();
+        // Check if the ring buffer potentially has not enough free slots
+        if N - index::len(self.cached_idx_r, self.local_idx_w) < n {
+            self.refresh_idx_r();
+            // Check if the ring buffer really has not enough free slots
+            let slots = N - index::len(self.cached_idx_r, self.local_idx_w);
+            if slots < n {
+                return Err(ChunkError::TooFewSlots(slots));
+            }
+        }
+        // The chunk may wrap around the end of the buffer
+        let first_len = n.min(N - index::slot::<N>(self.local_idx_w));
+        Ok(WriteChunkUninit {
+            writer: self,
+            first_len,
+            second_len: n - first_len,
+        })
+    }
+
+    /// Return `n` slots initialized with [`Default`] for writing.
+    ///
+    /// The slots are made available to the reader with [`WriteChunk::commit`]
+    /// or [`WriteChunk::commit_all`]. Uncommitted slots are dropped.
+    pub fn write_chunk(&mut self, n: usize) -> Result<WriteChunk<'_, T, N, R, S>, ChunkError>
+    where
+        T: Default,
+    {
+        self.write_chunk_uninit(n).map(WriteChunk::from)
+    }
+
+    /// Return `n` contiguous uninitialized slots for writing.
+    ///
+    /// If the `n` slots do not fit before the end of the buffer, the slots up to
+    /// the end are filled with [`Default`] and made available to the reader as
+    /// padding, so that the chunk always starts at the beginning of the buffer in
+    /// a single slice. The reader sees the padding as regular elements: record
+    /// formats have to tell it apart, e.g. with a zero length header.
+    /// If not enough slots are free, an error with the largest length that would
+    /// fit is returned.
+    pub fn write_chunk_contiguous(
+        &mut self,
+        n: usize,
+    ) -> Result<WriteChunkUninit<'_, T, N, R, S>, ChunkError>
+    where
+        T: Default,
+    {
+        let tail = N - index::slot::<N>(self.local_idx_w);
+        if n <= tail {
+            return self.write_chunk_uninit(n);
+        }
+        self.refresh_idx_r();
+        let slots = N - index::len(self.cached_idx_r, self.local_idx_w);
+        if slots < tail + n {
+            let max = if slots <= tail {
+                slots
+            } else {
+                tail.max(slots - tail)
+            };
+            return Err(ChunkError::TooFewSlots(max));
+        }
+        self.write_chunk_uninit(tail)?
+            .fill_from_iter(core::iter::repeat_with(T::default));
+        self.write_chunk_uninit(n)
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the number of slots ready to be read
+    #[inline]
+    pub fn slots(&self) -> usize {
+        let idx_w = self.inner.idx_w.load(Ordering::Acquire);
+        index::len(self.local_idx_r, idx_w)
+    }
+
+    /// Return `n` slots for reading.
+    ///
+    /// The slots are made available again to the writer with [`ReadChunk::commit`]
+    /// or [`ReadChunk::commit_all`], or by iterating the chunk.
+    /// If not enough slots are ready, an error with the number of ready slots is returned.
+    pub fn read_chunk(&mut self, n: usize) -> Result<ReadChunk<'_, T, N, R, S>, ChunkError> {
+        #[cfg(feature = "rt-assert")]
+        let _section = crate::rt::Section::enter();
+        #[cfg(feature = "owner-check")]
+        self.owner.check();
+        // Check if the ring buffer potentially has not enough elements
+        if index::len(self.local_idx_r, self.cached_idx_w) < n {
+            self.refresh_idx_w();
+            // Check if the ring buffer really has not enough elements
+            let slots = index::len(self.local_idx_r, self.cached_idx_w);
+            if slots < n {
+                return Err(ChunkError::TooFewSlots(slots));
+            }
+        }
+        // The chunk may wrap around the end of the buffer
+        let first_len = n.min(N - index::slot::<N>(self.local_idx_r));
+        Ok(ReadChunk {
+            reader: self,
+            first_len,
+            second_len: n - first_len,
+        })
+    }
+
+    /// Return all the readable slots, but only once there are at least `n` of them.
+    ///
+    /// Consumers processing fixed-size blocks can poll this instead of
+    /// keeping track of partially received blocks.
+    pub fn pull_min(&mut self, n: usize) -> Option<ReadChunk<'_, T, N, R, S>> {
+        self.refresh_idx_w();
+        let ready = index::len(self.local_idx_r, self.cached_idx_w);
+        if ready < n {
+            return None;
+        }
+        self.read_chunk(ready).ok()
+    }
+}
+
+/// Uninitialized slots obtained with [`RingBufferWriter::write_chunk_uninit`].
+pub struct WriteChunkUninit<'a, T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    writer: &'a mut RingBufferWriter<T, N, R, S>,
+    first_len: usize,
+    second_len: usize,
+}
+
+impl<T, const N: usize, R, S> WriteChunkUninit<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the slots of the chunk, split in two when wrapping around the end of the buffer
+    pub fn as_mut_slices(&mut self) -> (&mut [MaybeUninit<T>], &mut [MaybeUninit<T>]) {
+        // SAFETY: the slots are free and only accessible through this chunk
+        unsafe {
+            (
+                self.writer
+                    .inner
+                    .get_slice_mut(self.writer.local_idx_w, self.first_len),
+                self.writer.inner.get_slice_mut(0, self.second_len),
+            )
+        }
+    }
+
+    /// Make the first `n` slots of the chunk available to the reader.
+    ///
+    /// # Safety
+    /// The first `n` slots must have been initialized.
+    ///
+    /// # Panics
+    /// This function panics if `n` is greater than the chunk size.
+    pub unsafe fn commit(self, n: usize) {
+        assert!(n <= self.len(), "cannot commit more than chunk size");
+        self.writer.publish(n);
+    }
+
+    /// Make all the slots of the chunk available to the reader.
+    ///
+    /// # Safety
+    /// All the slots must have been initialized.
+    pub unsafe fn commit_all(self) {
+        let n = self.len();
+        self.writer.publish(n);
+    }
+
+    /// Move elements from an iterator into the chunk and commit them,
+    /// returning how many have been moved.
+    ///
+    /// If the iterator panics, nothing is committed and the elements already
+    /// moved into the chunk are dropped.
+    pub fn fill_from_iter<I>(mut self, iter: I) -> usize
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let (first, second) = self.as_mut_slices();
+        let iterated = init_slots([first, second], iter);
+        // SAFETY: the first `iterated` slots have been initialized
+        unsafe { self.writer.publish(iterated) };
+        iterated
+    }
+
+    /// Return the number of slots of the chunk
+    pub fn len(&self) -> usize {
+        self.first_len + self.second_len
+    }
+
+    /// Check whether the chunk has no slots
+    pub fn is_empty(&self) -> bool {
+        self.first_len == 0
+    }
+
+    /// Drop the elements of the slots from the `n`-th onwards.
+    unsafe fn drop_suffix(&mut self, n: usize) {
+        let (first, second) = self.as_mut_slices();
+        for slot in first.iter_mut().chain(second.iter_mut()).skip(n) {
+            slot.assume_init_drop();
+        }
+    }
+}
+
+/// [`Default`]-initialized slots obtained with [`RingBufferWriter::write_chunk`].
+pub struct WriteChunk<'a, T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    // Always `Some` until committed
+    chunk: Option<WriteChunkUninit<'a, T, N, R, S>>,
+}
+
+impl<'a, T, const N: usize, R, S> From<WriteChunkUninit<'a, T, N, R, S>>
+    for WriteChunk<'a, T, N, R, S>
+where
+    T: Default,
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn from(mut chunk: WriteChunkUninit<'a, T, N, R, S>) -> Self {
+        let (first, second) = chunk.as_mut_slices();
+        init_slots([first, second], core::iter::repeat_with(T::default));
+        WriteChunk { chunk: Some(chunk) }
+    }
+}
+
+impl<T, const N: usize, R, S> WriteChunk<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the slots of the chunk, split in two when wrapping around the end of the buffer
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (first, second) = self.chunk.as_mut().unwrap().as_mut_slices();
+        // SAFETY: all the slots have been initialized on creation
+        unsafe { (assume_init_mut(first), assume_init_mut(second)) }
+    }
+
+    /// Make the first `n` slots of the chunk available to the reader, dropping the others.
+    ///
+    /// # Panics
+    /// This function panics if `n` is greater than the chunk size.
+    pub fn commit(mut self, n: usize) {
+        let mut chunk = self.chunk.take().unwrap();
+        unsafe {
+            chunk.drop_suffix(n);
+            chunk.commit(n);
+        }
+    }
+
+    /// Make all the slots of the chunk available to the reader.
+    pub fn commit_all(mut self) {
+        let chunk = self.chunk.take().unwrap();
+        unsafe { chunk.commit_all() };
+    }
+
+    /// Return the number of slots of the chunk
+    pub fn len(&self) -> usize {
+        self.chunk.as_ref().unwrap().len()
+    }
+
+    /// Check whether the chunk has no slots
+    pub fn is_empty(&self) -> bool {
+        self.chunk.as_ref().unwrap().is_empty()
+    }
+}
+
+impl<T, const N: usize, R, S> Drop for WriteChunk<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        // Nothing has been committed, drop all the slots
+        if let Some(mut chunk) = self.chunk.take() {
+            unsafe { chunk.drop_suffix(0) };
+        }
+    }
+}
+
+/// Readable slots obtained with [`RingBufferReader::read_chunk`].
+pub struct ReadChunk<'a, T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    reader: &'a mut RingBufferReader<T, N, R, S>,
+    first_len: usize,
+    second_len: usize,
+}
+
+impl<T, const N: usize, R, S> ReadChunk<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the slots of the chunk, split in two when wrapping around the end of the buffer
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        // SAFETY: the slots are initialized and only accessible through this chunk
+        unsafe {
+            let first = self
+                .reader
+                .inner
+                .get_slice(self.reader.local_idx_r, self.first_len);
+            let second = self.reader.inner.get_slice(0, self.second_len);
+            (assume_init_ref(first), assume_init_ref(second))
+        }
+    }
+
+    /// Return the slots of the chunk, split in two when wrapping around the end of the buffer
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        // SAFETY: the slots are initialized and only accessible through this chunk
+        unsafe {
+            let first = self
+                .reader
+                .inner
+                .get_slice_mut(self.reader.local_idx_r, self.first_len);
+            let second = self.reader.inner.get_slice_mut(0, self.second_len);
+            (assume_init_mut(first), assume_init_mut(second))
+        }
+    }
+
+    /// Drop the first `n` elements of the chunk and make their slots available to the writer.
+    ///
+    /// # Panics
+    /// This function panics if `n` is greater than the chunk size.
+    pub fn commit(self, n: usize) {
+        assert!(n <= self.len(), "cannot commit more than chunk size");
+        self.commit_unchecked(n);
+    }
+
+    /// Drop all the elements of the chunk and make their slots available to the writer.
+    pub fn commit_all(self) {
+        let n = self.len();
+        self.commit_unchecked(n);
+    }
+
+    fn commit_unchecked(mut self, n: usize) {
+        struct Guard<'b, T, const N: usize, R, S>
+        where
+            R: Deref<Target = RingBuffer<T, N, S>>,
+            S: Storage<T>,
+        {
+            reader: &'b mut RingBufferReader<T, N, R, S>,
+            dropped: usize,
+        }
+
+        impl<T, const N: usize, R, S> Drop for Guard<'_, T, N, R, S>
+        where
+            R: Deref<Target = RingBuffer<T, N, S>>,
+            S: Storage<T>,
+        {
+            fn drop(&mut self) {
+                // Release the dropped slots even if a drop panicked
+                unsafe { self.reader.release(self.dropped) };
+            }
+        }
+
+        let (first, second) = self.as_mut_slices();
+        let (first, second) = (first as *mut [T], second as *mut [T]);
+        let mut guard = Guard {
+            reader: self.reader,
+            dropped: 0,
+        };
+        // SAFETY: the slots are initialized and only accessible through this chunk
+        for slot in unsafe { (*first).iter_mut().chain((*second).iter_mut()) }.take(n) {
+            // Count the element before dropping it: it is considered dropped even if it panics
+            guard.dropped += 1;
+            unsafe { core::ptr::drop_in_place(slot) };
+        }
+    }
+
+    /// Return the number of slots of the chunk
+    pub fn len(&self) -> usize {
+        self.first_len + self.second_len
+    }
+
+    /// Check whether the chunk has no slots
+    pub fn is_empty(&self) -> bool {
+        self.first_len == 0
+    }
+}
+
+impl<'a, T, const N: usize, R, S> IntoIterator for ReadChunk<'a, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    type Item = T;
+    type IntoIter = ReadChunkIntoIter<'a, T, N, R, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ReadChunkIntoIter {
+            chunk: self,
+            iterated: 0,
+        }
+    }
+}
+
+/// Iterator moving the elements out of a [`ReadChunk`].
+///
+/// The slots of the iterated elements are made available to the writer when dropped.
+pub struct ReadChunkIntoIter<'a, T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    chunk: ReadChunk<'a, T, N, R, S>,
+    iterated: usize,
+}
+
+impl<T, const N: usize, R, S> ReadChunkIntoIter<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the number of elements moved out so far
+    pub fn iterated(&self) -> usize {
+        self.iterated
+    }
+}
+
+impl<T, const N: usize, R, S> Iterator for ReadChunkIntoIter<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.iterated == self.chunk.len() {
+            return None;
+        }
+        let idx = index::add(self.chunk.reader.local_idx_r, self.iterated);
+        self.iterated += 1;
+        // SAFETY: the slot is initialized and its slot is released on drop
+        Some(unsafe { self.chunk.reader.inner.get_mut(idx).assume_init_read() })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.chunk.len() - self.iterated;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize, R, S> ExactSizeIterator for ReadChunkIntoIter<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+}
+
+impl<T, const N: usize, R, S> core::iter::FusedIterator for ReadChunkIntoIter<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+}
+
+impl<T, const N: usize, R, S> Drop for ReadChunkIntoIter<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        // SAFETY: the iterated elements have been moved out
+        unsafe { self.chunk.reader.release(self.iterated) };
+    }
+}
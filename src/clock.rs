@@ -0,0 +1,155 @@
+//! Timeouts measured by a user-supplied clock.
+//!
+//! [`RingBufferWriter::push_timeout`] and [`RingBufferReader::pull_timeout`]
+//! retry until they succeed or the timeout expires, as measured by a
+//! [`Clock`]. With the `std` feature [`StdClock`] counts nanoseconds, while
+//! embedded targets can provide their SysTick or RTC counter, any
+//! `Fn() -> u64` being a clock:
+//!
+//! ```
+//! use core::sync::atomic::{AtomicU64, Ordering};
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! static TICKS: AtomicU64 = AtomicU64::new(0);
+//! let systick = || TICKS.fetch_add(1, Ordering::Relaxed);
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 2>::init();
+//! assert_eq!(rx.pull_timeout(&systick, 100), None);
+//! assert!(TICKS.load(Ordering::Relaxed) > 100);
+//! assert_eq!(tx.push_timeout(1, &systick, 100), Ok(()));
+//! assert_eq!(rx.pull_timeout(&systick, 100), Some(1));
+//! ```
+//!
+//! The waits spin, and also yield the thread with the `std` feature.
+use crate::{storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::ops::Deref;
+
+/// A monotonic clock counting ticks of arbitrary length.
+pub trait Clock {
+    /// Return the current time in ticks, which may wrap around `u64::MAX`.
+    fn now(&self) -> u64;
+}
+
+impl<F: Fn() -> u64> Clock for F {
+    #[inline]
+    fn now(&self) -> u64 {
+        self()
+    }
+}
+
+/// A [`Clock`] counting nanoseconds since its creation, available with the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone)]
+pub struct StdClock {
+    epoch: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl StdClock {
+    pub fn new() -> Self {
+        Self {
+            epoch: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    #[inline]
+    fn now(&self) -> u64 {
+        self.epoch.elapsed().as_nanos() as u64
+    }
+}
+
+/// Time left to retry an operation.
+struct Deadline<'a, C: ?Sized> {
+    clock: &'a C,
+    start: u64,
+    timeout: u64,
+    #[cfg(feature = "std")]
+    backoff: crate::backoff::Backoff,
+}
+
+impl<'a, C: Clock + ?Sized> Deadline<'a, C> {
+    fn new(clock: &'a C, timeout: u64) -> Self {
+        Self {
+            clock,
+            start: clock.now(),
+            timeout,
+            #[cfg(feature = "std")]
+            backoff: crate::backoff::Backoff::new(),
+        }
+    }
+
+    /// Wait before the next attempt, returning `false` if the timeout expired.
+    fn wait(&mut self) -> bool {
+        if self.clock.now().wrapping_sub(self.start) >= self.timeout {
+            return false;
+        }
+        #[cfg(feature = "std")]
+        self.backoff.snooze();
+        #[cfg(not(feature = "std"))]
+        core::hint::spin_loop();
+        true
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Push an element, retrying while the ring buffer is full for up to
+    /// `timeout` ticks of `clock`.
+    ///
+    /// The element is handed back once the timeout expires, or right away
+    /// if the reader has been dropped.
+    pub fn push_timeout<C: Clock + ?Sized>(
+        &mut self,
+        mut t: T,
+        clock: &C,
+        timeout: u64,
+    ) -> Result<(), T> {
+        let mut deadline = Deadline::new(clock, timeout);
+        loop {
+            match self.push(t) {
+                None => return Ok(()),
+                Some(v) => t = v,
+            }
+            if self.is_reader_dropped() || !deadline.wait() {
+                return Err(t);
+            }
+        }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Pull an element, retrying while the ring buffer is empty for up to
+    /// `timeout` ticks of `clock`.
+    ///
+    /// Returns `None` once the timeout expires, or as soon as the ring buffer
+    /// is drained if the writer has been dropped.
+    pub fn pull_timeout<C: Clock + ?Sized>(&mut self, clock: &C, timeout: u64) -> Option<T> {
+        let mut deadline = Deadline::new(clock, timeout);
+        loop {
+            if let Some(t) = self.pull() {
+                return Some(t);
+            }
+            // The writer might have pushed a last element before being dropped
+            if self.is_writer_dropped() && self.is_empty() || !deadline.wait() {
+                return None;
+            }
+        }
+    }
+}
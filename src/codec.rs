@@ -0,0 +1,236 @@
+//! COBS and SLIP framing over byte ring buffers.
+//!
+//! Frames are encoded straight into the free slots and decoded straight from
+//! the ready ones, without going through a separate codec buffer. A frame is
+//! pushed as a whole or not at all, delimiter included, and is only pulled
+//! once its delimiter has been received:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 16>::init();
+//! assert!(tx.push_frame_cobs(&[0x11, 0x00, 0x22]));
+//! assert_eq!(rx.peek_slice().0, [0x02, 0x11, 0x02, 0x22, 0x00]);
+//!
+//! let mut frame = Vec::new();
+//! assert_eq!(rx.pull_frame_cobs(&mut frame), Ok(Some(3)));
+//! assert_eq!(frame, [0x11, 0x00, 0x22]);
+//! assert_eq!(rx.pull_frame_cobs(&mut frame), Ok(None));
+//! ```
+//!
+//! [COBS](https://en.wikipedia.org/wiki/Consistent_Overhead_Byte_Stuffing)
+//! delimits frames with a zero byte, at the cost of one byte every 254.
+//! [SLIP](https://www.rfc-editor.org/rfc/rfc1055) delimits them with `0xC0`
+//! and escapes the delimiter and the escape byte within the frames.
+#[cfg(feature = "alloc")]
+use crate::RingBufferReader;
+use crate::{storage::Storage, RingBuffer, RingBufferWriter};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{fmt, ops::Deref};
+
+type Encoder = fn(&[u8], &mut dyn FnMut(usize, u8)) -> usize;
+#[cfg(feature = "alloc")]
+type Decoder = fn(&mut dyn Iterator<Item = u8>, &mut Vec<u8>) -> Result<(), FrameError>;
+
+const SLIP_END: u8 = 0xC0;
+const SLIP_ESC: u8 = 0xDB;
+const SLIP_ESC_END: u8 = 0xDC;
+const SLIP_ESC_ESC: u8 = 0xDD;
+
+/// Error returned when pulling a frame fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// The frame is not validly encoded. It has been discarded.
+    Malformed,
+    /// The ring buffer is full without holding a delimiter, so the frame can
+    /// never be completed. Its bytes have been discarded.
+    Overflow,
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Malformed => write!(f, "malformed frame in ring buffer"),
+            FrameError::Overflow => write!(f, "frame larger than the ring buffer"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameError {}
+
+/// COBS-encode `data` followed by the delimiter, calling `put` on every output byte.
+///
+/// Returns the length of the output.
+fn cobs_encode(data: &[u8], put: &mut dyn FnMut(usize, u8)) -> usize {
+    let (mut code_idx, mut code, mut o) = (0, 1u8, 1);
+    for (i, &b) in data.iter().enumerate() {
+        if b != 0 {
+            put(o, b);
+            o += 1;
+            code += 1;
+        }
+        // A block ends with a zero, or after 254 non-zero bytes unless they end the frame
+        if b == 0 || (code == 0xFF && i + 1 < data.len()) {
+            put(code_idx, code);
+            (code_idx, code) = (o, 1);
+            o += 1;
+        }
+    }
+    put(code_idx, code);
+    put(o, 0);
+    o + 1
+}
+
+/// Decode the COBS-encoded bytes of a frame, without its delimiter, into `dst`.
+#[cfg(feature = "alloc")]
+fn cobs_decode(src: &mut dyn Iterator<Item = u8>, dst: &mut Vec<u8>) -> Result<(), FrameError> {
+    let mut src = src.peekable();
+    while let Some(code) = src.next() {
+        // Zeros never appear before the delimiter, which has been stripped
+        for _ in 1..code {
+            dst.push(src.next().ok_or(FrameError::Malformed)?);
+        }
+        if code != 0xFF && src.peek().is_some() {
+            dst.push(0);
+        }
+    }
+    Ok(())
+}
+
+/// SLIP-encode `data` followed by the delimiter, calling `put` on every output byte.
+///
+/// Returns the length of the output.
+fn slip_encode(data: &[u8], put: &mut dyn FnMut(usize, u8)) -> usize {
+    let mut o = 0;
+    for &b in data {
+        let escaped = match b {
+            SLIP_END => Some(SLIP_ESC_END),
+            SLIP_ESC => Some(SLIP_ESC_ESC),
+            _ => None,
+        };
+        match escaped {
+            Some(e) => {
+                put(o, SLIP_ESC);
+                put(o + 1, e);
+                o += 2;
+            }
+            None => {
+                put(o, b);
+                o += 1;
+            }
+        }
+    }
+    put(o, SLIP_END);
+    o + 1
+}
+
+/// Decode the SLIP-encoded bytes of a frame, without its delimiter, into `dst`.
+#[cfg(feature = "alloc")]
+fn slip_decode(src: &mut dyn Iterator<Item = u8>, dst: &mut Vec<u8>) -> Result<(), FrameError> {
+    while let Some(b) = src.next() {
+        dst.push(match b {
+            SLIP_ESC => match src.next() {
+                Some(SLIP_ESC_END) => SLIP_END,
+                Some(SLIP_ESC_ESC) => SLIP_ESC,
+                _ => return Err(FrameError::Malformed),
+            },
+            b => b,
+        });
+    }
+    Ok(())
+}
+
+impl<const N: usize, R, S> RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Push `data` as a COBS frame, returning `false` without pushing anything
+    /// if the encoded frame does not fit.
+    pub fn push_frame_cobs(&mut self, data: &[u8]) -> bool {
+        self.push_encoded(data, cobs_encode)
+    }
+
+    /// Push `data` as a SLIP frame, returning `false` without pushing anything
+    /// if the encoded frame does not fit.
+    pub fn push_frame_slip(&mut self, data: &[u8]) -> bool {
+        self.push_encoded(data, slip_encode)
+    }
+
+    fn push_encoded(&mut self, data: &[u8], encode: Encoder) -> bool {
+        let len = encode(data, &mut |_, _| {});
+        let mut chunk = self.write_free_chunk(len);
+        if chunk.len() < len {
+            return false;
+        }
+        let (first, second) = chunk.as_mut_slices();
+        encode(data, &mut |i, b| match first.get_mut(i) {
+            Some(slot) => {
+                slot.write(b);
+            }
+            None => {
+                second[i - first.len()].write(b);
+            }
+        });
+        // SAFETY: the encoder wrote every byte of the frame
+        unsafe { chunk.commit_all() };
+        true
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, R, S> RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Pull the next COBS frame, appending its decoded bytes to `dst`.
+    ///
+    /// Returns `Ok(None)` if no frame is complete yet, and the decoded length otherwise.
+    pub fn pull_frame_cobs(&mut self, dst: &mut Vec<u8>) -> Result<Option<usize>, FrameError> {
+        self.pull_decoded(dst, 0, cobs_decode)
+    }
+
+    /// Pull the next SLIP frame, appending its decoded bytes to `dst`.
+    ///
+    /// Empty frames, e.g. from senders flushing the line with a delimiter
+    /// before each frame, are skipped. Returns `Ok(None)` if no frame is
+    /// complete yet, and the decoded length otherwise.
+    pub fn pull_frame_slip(&mut self, dst: &mut Vec<u8>) -> Result<Option<usize>, FrameError> {
+        loop {
+            match self.pull_decoded(dst, SLIP_END, slip_decode) {
+                Ok(Some(0)) => continue,
+                res => return res,
+            }
+        }
+    }
+
+    fn pull_decoded(
+        &mut self,
+        dst: &mut Vec<u8>,
+        end: u8,
+        decode: Decoder,
+    ) -> Result<Option<usize>, FrameError> {
+        let chunk = self.read_ready_chunk(usize::MAX);
+        let (first, second) = chunk.as_slices();
+        let Some(len) = first.iter().chain(second).position(|&b| b == end) else {
+            if chunk.len() == N {
+                chunk.commit_all();
+                return Err(FrameError::Overflow);
+            }
+            return Ok(None);
+        };
+        let start = dst.len();
+        let res = decode(&mut first.iter().chain(second).take(len).copied(), dst);
+        chunk.commit(len + 1);
+        match res {
+            Ok(()) => Ok(Some(dst.len() - start)),
+            Err(e) => {
+                dst.truncate(start);
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Double-buffered transfers alternating between the two halves of a ring buffer.
+//!
+//! Circular DMA engines usually fill a buffer in two halves, raising an
+//! interrupt once each of them is complete. A [`DoubleBuffer`] maps them onto
+//! the two halves of the ring buffer: the engine fills the current one, and
+//! [`DoubleBuffer::swap`] publishes it to the reader and moves to the other
+//! one, which the reader has to drain in the meantime:
+//!
+//! ```
+//! use ringbuffer_spsc::{DoubleBuffer, RingBuffer};
+//!
+//! let (tx, mut rx) = RingBuffer::<u8, 8>::init();
+//! let mut tx = DoubleBuffer::new(tx);
+//! for (i, slot) in tx.current_half_mut().unwrap().iter_mut().enumerate() {
+//!     slot.write(i as u8);
+//! }
+//! // Half-transfer interrupt
+//! assert!(unsafe { tx.swap() });
+//! assert_eq!(rx.pull(), Some(0));
+//! ```
+//!
+//! The halves span the whole capacity, regardless of the soft limit.
+use crate::{
+    index,
+    storage::{Inline, Storage},
+    DefaultRef, RingBuffer, RingBufferWriter,
+};
+use core::{mem::MaybeUninit, ops::Deref};
+
+/// A writer filling the ring buffer one half at a time.
+pub struct DoubleBuffer<T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    tx: RingBufferWriter<T, N, R, S>,
+}
+
+impl<T, const N: usize, R, S> DoubleBuffer<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    const HALF: usize = N / 2;
+
+    /// Wrap `tx`, whose write index must be at the start of a half.
+    ///
+    /// # Panics
+    /// This function panics if the capacity is 1 or if the write index is
+    /// in the middle of a half.
+    pub fn new(tx: RingBufferWriter<T, N, R, S>) -> Self {
+        assert!(N > 1, "a double buffer requires a capacity of at least 2");
+        assert!(
+            index::slot::<N>(tx.local_idx_w).is_multiple_of(Self::HALF),
+            "the write index is in the middle of a half"
+        );
+        Self { tx }
+    }
+
+    /// Get back the wrapped writer
+    pub fn into_inner(self) -> RingBufferWriter<T, N, R, S> {
+        self.tx
+    }
+
+    /// Return the slots of the current half, or `None` if the reader has not
+    /// drained it yet.
+    ///
+    /// A DMA engine can be handed the pointer of the slots: they stay valid
+    /// until [`Self::swap`], as long as the double buffer is not used otherwise.
+    pub fn current_half_mut(&mut self) -> Option<&mut [MaybeUninit<T>]> {
+        if !self.is_current_half_free() {
+            return None;
+        }
+        // SAFETY: the half is free and does not cross the end of the buffer
+        Some(unsafe { self.tx.inner.get_slice_mut(self.tx.local_idx_w, Self::HALF) })
+    }
+
+    /// Publish the current half to the reader and move to the other one.
+    ///
+    /// Returns whether the other half is free. If not, the reader is late
+    /// and a circular DMA engine is about to overrun elements it has not
+    /// pulled yet: the transfer should be stopped.
+    ///
+    /// # Safety
+    /// The current half must be free, i.e. [`Self::current_half_mut`] returns
+    /// `Some`, and all its slots must have been initialized.
+    pub unsafe fn swap(&mut self) -> bool {
+        self.tx.publish(Self::HALF);
+        self.is_current_half_free()
+    }
+
+    fn is_current_half_free(&mut self) -> bool {
+        self.tx.refresh_read_index() >= Self::HALF
+    }
+}
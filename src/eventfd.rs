@@ -0,0 +1,162 @@
+//! eventfd doorbell for `epoll`-based readers, available with the `eventfd`
+//! feature on Linux.
+//!
+//! The reader creates an eventfd with [`RingBufferReader::eventfd`] and
+//! registers it in its event loop alongside its sockets. Before waiting, it
+//! arms the doorbell with [`RingBufferReader::arm_eventfd`], which the writer
+//! rings at its next push:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u32, 16>::init();
+//! let fd = rx.eventfd().unwrap();
+//! // Register `fd` for reading in the event loop
+//! # let _ = fd;
+//! loop {
+//!     while let Some(t) = rx.pull() {
+//!         // Process `t`
+//! #       let _ = t;
+//!     }
+//!     if rx.arm_eventfd() {
+//!         // Wait for `fd` to be readable
+//! #       tx.push(1);
+//! #       break;
+//!     }
+//! }
+//! ```
+//!
+//! With the `mio` feature, the reader implements `mio::event::Source` by
+//! registering its eventfd, so that it can be polled alongside the other
+//! sources of a `mio::Poll`. The same loop applies: drain the reader, then
+//! arm it before polling again.
+//!
+//! The writer only pays for a fence and a load per push once an eventfd is
+//! attached, and for a `write` system call when the reader is waiting.
+use crate::{storage::Storage, RingBuffer, RingBufferReader};
+use core::{
+    ops::Deref,
+    sync::atomic::{fence, AtomicBool, AtomicI32, Ordering},
+};
+use std::{io, os::fd::RawFd};
+
+pub(crate) struct Doorbell {
+    // Negative until the reader attaches an eventfd
+    fd: AtomicI32,
+    // Set by the reader before waiting on the eventfd
+    armed: AtomicBool,
+}
+
+impl Doorbell {
+    pub(crate) const fn new() -> Self {
+        Self {
+            fd: AtomicI32::new(-1),
+            armed: AtomicBool::new(false),
+        }
+    }
+
+    /// Signal the eventfd if the reader is waiting on it
+    #[inline]
+    pub(crate) fn ring(&self) {
+        let fd = self.fd.load(Ordering::Acquire);
+        if fd < 0 {
+            return;
+        }
+        // Pairs with the fence of `arm_eventfd`: either the reader sees the
+        // index or flag just updated by the writer, or the writer sees it armed.
+        fence(Ordering::SeqCst);
+        if self.armed.load(Ordering::Relaxed) && self.armed.swap(false, Ordering::Relaxed) {
+            let one = 1u64;
+            // SAFETY: the eventfd stays open until the ring buffer is dropped
+            unsafe { libc::write(fd, (&one as *const u64).cast(), size_of::<u64>()) };
+        }
+    }
+}
+
+impl Drop for Doorbell {
+    fn drop(&mut self) {
+        let fd = *self.fd.get_mut();
+        if fd >= 0 {
+            // SAFETY: the eventfd is owned by the ring buffer
+            unsafe { libc::close(fd) };
+        }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the eventfd signalled by the writer once armed, creating it on the first call.
+    ///
+    /// The eventfd is non-blocking and closed when the ring buffer is dropped.
+    pub fn eventfd(&mut self) -> io::Result<RawFd> {
+        let fd = self.inner.doorbell.fd.load(Ordering::Relaxed);
+        if fd >= 0 {
+            return Ok(fd);
+        }
+        // SAFETY: no pointer is involved
+        let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.inner.doorbell.fd.store(fd, Ordering::Release);
+        Ok(fd)
+    }
+
+    /// Arm the doorbell before waiting for the eventfd to be readable.
+    ///
+    /// Returns `false` if elements are already available or the writer has
+    /// been dropped, in which case the eventfd would not be signalled and the
+    /// reader must not wait. The eventfd is reset, so that it only becomes
+    /// readable again once the writer pushes or is dropped.
+    ///
+    /// # Panics
+    /// This function panics if no eventfd has been created with [`Self::eventfd`].
+    pub fn arm_eventfd(&mut self) -> bool {
+        let doorbell = &self.inner.doorbell;
+        let fd = doorbell.fd.load(Ordering::Relaxed);
+        assert!(fd >= 0, "no eventfd attached to the ring buffer");
+        let mut counter = 0u64;
+        // SAFETY: the buffer is a valid `u64`, a failure means the counter is already zero
+        unsafe { libc::read(fd, (&mut counter as *mut u64).cast(), size_of::<u64>()) };
+        doorbell.armed.store(true, Ordering::Relaxed);
+        // Pairs with the fence of `Doorbell::ring`
+        fence(Ordering::SeqCst);
+        if !self.is_empty() || self.is_writer_dropped() {
+            doorbell.armed.store(false, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(feature = "mio")]
+impl<T, const N: usize, R, S> mio::event::Source for RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        mio::unix::SourceFd(&self.eventfd()?).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        mio::unix::SourceFd(&self.eventfd()?).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        mio::unix::SourceFd(&self.eventfd()?).deregister(registry)
+    }
+}
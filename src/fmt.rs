@@ -0,0 +1,46 @@
+//! Formatted text for `u8` ring buffers.
+//!
+//! The writer implements [`core::fmt::Write`], so `write!` formats straight
+//! into the ring buffer, e.g. for deferred logging on embedded targets:
+//!
+//! ```
+//! use core::fmt::Write;
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 16>::init();
+//! write!(tx, "t={}ms", 42).unwrap();
+//! let mut buf = [0; 16];
+//! let n = rx.pull_slice(&mut buf);
+//! assert_eq!(&buf[..n], b"t=42ms");
+//! ```
+//!
+//! Each fragment of the formatted text is pushed as a whole or not at all:
+//! when the free slots below the soft limit are not enough, the fragment is
+//! dropped and [`fmt::Error`] is returned, which stops the formatting. A full
+//! ring buffer thus truncates the text between two fragments, never in the
+//! middle of a UTF-8 sequence. Texts that must never be truncated should be
+//! formatted into a buffer first and pushed with `try_push_n`.
+use crate::{storage::Storage, RingBuffer, RingBufferWriter};
+use core::{fmt, ops::Deref, ptr};
+
+impl<const N: usize, R, S> fmt::Write for RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut chunk = self.write_free_chunk(s.len());
+        if chunk.len() < s.len() {
+            return Err(fmt::Error);
+        }
+        let (first, second) = chunk.as_mut_slices();
+        let (head, tail) = s.as_bytes().split_at(first.len());
+        // SAFETY: the chunk is exactly as long as the text, so both copies fill it
+        unsafe {
+            ptr::copy_nonoverlapping(head.as_ptr(), first.as_mut_ptr().cast(), head.len());
+            ptr::copy_nonoverlapping(tail.as_ptr(), second.as_mut_ptr().cast(), tail.len());
+            chunk.commit_all();
+        }
+        Ok(())
+    }
+}
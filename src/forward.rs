@@ -0,0 +1,148 @@
+//! Background thread forwarding the content of a byte ring buffer to a sink.
+//!
+//! [`Forwarder`] owns the reader and writes everything pushed into the ring
+//! buffer to an [`io::Write`], e.g. a file or a socket, from its own thread.
+//! The producer never blocks on the sink, it only ever sees the ring buffer
+//! filling up while the sink is slow:
+//!
+//! ```
+//! use ringbuffer_spsc::{FlushPolicy, Forwarder, RingBuffer};
+//! use std::io::Write;
+//!
+//! let (mut tx, rx) = RingBuffer::<u8, 64>::init();
+//! let forwarder = Forwarder::spawn(rx, Vec::new(), FlushPolicy::OnIdle);
+//! tx.write_all(b"hello").unwrap();
+//! drop(tx);
+//! assert_eq!(forwarder.join().unwrap(), b"hello");
+//! ```
+//!
+//! The thread checks for new bytes every millisecond while the ring buffer
+//! is empty. Sinks failing with [`io::ErrorKind::WouldBlock`] or
+//! [`io::ErrorKind::Interrupted`] are retried, any other error stops it.
+use crate::{storage::Storage, RingBuffer, RingBufferReader};
+use core::ops::Deref;
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+const IDLE_PERIOD: Duration = Duration::from_millis(1);
+
+/// When a [`Forwarder`] flushes its sink.
+///
+/// Whatever the policy, the sink is flushed before the thread returns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Flush after every write.
+    Always,
+    /// Flush whenever the ring buffer runs empty.
+    OnIdle,
+    /// Flush at most once per period while bytes are written.
+    Interval(Duration),
+    /// Never flush until the thread returns.
+    Never,
+}
+
+/// Handle of the thread forwarding a byte ring buffer to a sink of type `W`.
+///
+/// Dropping the handle detaches the thread after asking it to stop, as
+/// [`Self::stop`] does.
+pub struct Forwarder<W> {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<io::Result<W>>>,
+}
+
+impl<W> Forwarder<W>
+where
+    W: Write + Send + 'static,
+{
+    /// Spawn the thread writing everything pulled from `reader` to `out`,
+    /// flushing it according to `policy`.
+    pub fn spawn<const N: usize, R, S>(
+        mut reader: RingBufferReader<u8, N, R, S>,
+        mut out: W,
+        policy: FlushPolicy,
+    ) -> Self
+    where
+        R: Deref<Target = RingBuffer<u8, N, S>> + Send + 'static,
+        S: Storage<u8> + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let thread = thread::spawn(move || {
+            let mut flushed = Instant::now();
+            let mut dirty = false;
+            loop {
+                // Check for the end before forwarding: the bytes pushed before
+                // are then guaranteed to be visible.
+                let done = flag.load(Ordering::Acquire) || reader.is_writer_dropped();
+                // Checked before draining: checked after an empty drain, new
+                // bytes of the writer would be taken for a sink writing nothing
+                while !reader.is_empty() {
+                    match reader.drain_to(&mut out) {
+                        Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                        Ok(_) => dirty = true,
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                            thread::sleep(IDLE_PERIOD)
+                        }
+                        Err(e) => return Err(e),
+                    }
+                    let due = match policy {
+                        FlushPolicy::Always => true,
+                        FlushPolicy::Interval(period) => flushed.elapsed() >= period,
+                        FlushPolicy::OnIdle | FlushPolicy::Never => false,
+                    };
+                    if dirty && due {
+                        out.flush()?;
+                        (flushed, dirty) = (Instant::now(), false);
+                    }
+                }
+                if dirty && (done || policy == FlushPolicy::OnIdle) {
+                    out.flush()?;
+                    (flushed, dirty) = (Instant::now(), false);
+                }
+                if done {
+                    return Ok(out);
+                }
+                thread::sleep(IDLE_PERIOD);
+            }
+        });
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Wait for the writer to be dropped and its last bytes forwarded,
+    /// returning the sink or the I/O error that stopped the thread.
+    pub fn join(mut self) -> io::Result<W> {
+        let thread = self.thread.take().unwrap();
+        thread
+            .join()
+            .unwrap_or_else(|e| std::panic::resume_unwind(e))
+    }
+
+    /// Ask the thread to forward the bytes currently ready and return,
+    /// then wait for it like [`Self::join`].
+    pub fn stop(self) -> io::Result<W> {
+        self.stop.store(true, Ordering::Release);
+        self.join()
+    }
+
+    /// Check whether the thread has returned
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+}
+
+impl<W> Drop for Forwarder<W> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+    }
+}
@@ -0,0 +1,81 @@
+//! Frame-based access, treating the ring buffer as a sequence of `CH` elements each.
+//!
+//! Interleaved audio is the typical use case: a frame holds one sample per
+//! channel, and is always pushed and pulled as a whole, so that the reader
+//! never observes half a frame:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<f32, 8>::init();
+//! assert_eq!(tx.push_frames(&[[0.0, 0.1], [0.2, 0.3], [0.4, 0.5]]), 3);
+//! assert_eq!(tx.push_frame([0.6, 0.7]), None);
+//! assert_eq!(tx.push_frame([0.8, 0.9]), Some([0.8, 0.9]));
+//!
+//! assert_eq!(rx.pull_frame(), Some([0.0, 0.1]));
+//! let mut dst = [[0.0; 2]; 4];
+//! assert_eq!(rx.pull_frames(&mut dst), 3);
+//! ```
+//!
+//! The frames stay aligned only as long as the ring buffer is exclusively
+//! accessed by frames of the same size.
+use crate::{storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::ops::Deref;
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Push a frame, handing it back if there is not enough room for all its elements
+    #[inline]
+    pub fn push_frame<const CH: usize>(&mut self, frame: [T; CH]) -> Option<[T; CH]> {
+        let chunk = self.write_free_chunk(CH);
+        if chunk.len() < CH {
+            return Some(frame);
+        }
+        chunk.fill_from_iter(frame);
+        None
+    }
+
+    /// Push as many whole frames of `src` as fit, returning how many have been pushed.
+    ///
+    /// # Panics
+    /// This function panics if `CH` is zero.
+    #[inline]
+    pub fn push_frames<const CH: usize>(&mut self, src: &[[T; CH]]) -> usize
+    where
+        T: Copy,
+    {
+        assert!(CH > 0, "frames cannot be empty");
+        let n = self.write_free_chunk(src.len() * CH).len() / CH;
+        self.push_slice(src[..n].as_flattened()) / CH
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Pull the next frame, if all its elements are available
+    #[inline]
+    pub fn pull_frame<const CH: usize>(&mut self) -> Option<[T; CH]> {
+        let mut iter = self.read_chunk(CH).ok()?.into_iter();
+        Some(core::array::from_fn(|_| iter.next().unwrap()))
+    }
+
+    /// Pull as many whole frames as available into `dst`, returning how many have been pulled.
+    ///
+    /// # Panics
+    /// This function panics if `CH` is zero.
+    #[inline]
+    pub fn pull_frames<const CH: usize>(&mut self, dst: &mut [[T; CH]]) -> usize
+    where
+        T: Copy,
+    {
+        assert!(CH > 0, "frames cannot be empty");
+        let n = self.read_ready_chunk(dst.len() * CH).len() / CH;
+        self.pull_slice(dst[..n].as_flattened_mut()) / CH
+    }
+}
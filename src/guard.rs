@@ -0,0 +1,102 @@
+//! Ring buffers mapped between guard pages, available with the `guard-pages`
+//! feature on Unix.
+//!
+//! [`RingBuffer::init_guarded`] maps the ring buffer on its own pages,
+//! surrounded by two inaccessible pages. An out-of-bounds access, e.g. from
+//! unsafe code built on the raw pointers or from a misconfigured DMA engine,
+//! then faults right away instead of silently corrupting the neighboring heap:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 4096>::init_guarded();
+//! assert!(tx.push(1).is_none());
+//! assert_eq!(rx.pull(), Some(1));
+//! ```
+//!
+//! The slots are laid out last and the ring buffer is placed at the end of its
+//! pages, so that the trailing guard page borders them, up to the padding of
+//! the ring buffer to its alignment. The shared indexes lie between the
+//! leading guard page and the slots. The mapping costs at least three pages
+//! and a system call, which suits hardened or debug builds rather than ring
+//! buffers created on the fly.
+use crate::{
+    storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter, SharedRingBuffer, GUARD_PAGES,
+};
+use core::{
+    alloc::Layout,
+    ptr::{self, NonNull},
+    sync::atomic::Ordering,
+};
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// Create a ring buffer mapped between guard pages and return its two halves.
+    ///
+    /// # Panics
+    /// This function panics if the pages cannot be mapped or protected.
+    pub fn init_guarded() -> (RingBufferWriter<T, N>, RingBufferReader<T, N>) {
+        assert!(
+            N.is_power_of_two(),
+            "RingBuffer requires the capacity to be a power of 2. {N} is not."
+        );
+        let ptr = map::<T, N>();
+        // SAFETY: the mapping is initialized and only released through `unmap`,
+        // which the flag set below selects
+        let (tx, rx) = Self::init_shared(unsafe { SharedRingBuffer::pair_from_ptr(ptr) }, 0);
+        tx.inner.flags.fetch_or(GUARD_PAGES, Ordering::Relaxed);
+        (tx, rx)
+    }
+}
+
+/// Return the page size, the length of the mapping and the offset of the ring
+/// buffer within it.
+fn geometry<T, const N: usize, S: Storage<T>>() -> (usize, usize, usize) {
+    // SAFETY: `sysconf` has no precondition
+    let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let layout = Layout::new::<RingBuffer<T, N, S>>();
+    assert!(
+        layout.align() <= page,
+        "RingBuffer alignment exceeds the page size"
+    );
+    let body = layout.size().div_ceil(page).max(1) * page;
+    // The size is a multiple of the alignment, which divides the page size
+    (page, body + 2 * page, page + body - layout.size())
+}
+
+/// Map an empty ring buffer between two guard pages.
+fn map<T, const N: usize>() -> NonNull<RingBuffer<T, N>> {
+    let (page, len, offset) = geometry::<T, N, crate::storage::Inline<T, N>>();
+    // SAFETY: an anonymous private mapping does not alias any memory
+    let base = unsafe {
+        libc::mmap(
+            ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        )
+    };
+    assert!(base != libc::MAP_FAILED, "failed to map the ring buffer");
+    let base = base.cast::<u8>();
+    // SAFETY: both guard pages are within the mapping
+    unsafe {
+        let lead = libc::mprotect(base.cast(), page, libc::PROT_NONE);
+        let trail = libc::mprotect(base.add(len - page).cast(), page, libc::PROT_NONE);
+        assert!(lead == 0 && trail == 0, "failed to protect the guard pages");
+        let rb = base.add(offset).cast::<RingBuffer<T, N>>();
+        rb.write(RingBuffer::empty());
+        NonNull::new_unchecked(rb)
+    }
+}
+
+/// Drop the ring buffer and release its mapping.
+///
+/// # Safety
+/// The pointer must come from [`map`], and not be used anymore.
+pub(crate) unsafe fn unmap<T, const N: usize, S: Storage<T>>(ptr: NonNull<RingBuffer<T, N, S>>) {
+    let (_, len, offset) = geometry::<T, N, S>();
+    ptr::drop_in_place(ptr.as_ptr());
+    let base = ptr.as_ptr().cast::<u8>().sub(offset);
+    libc::munmap(base.cast(), len);
+}
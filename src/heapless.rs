@@ -0,0 +1,175 @@
+//! Adapter mirroring the `heapless::spsc` API.
+//!
+//! [`Queue`], [`Producer`] and [`Consumer`] have the same names and methods
+//! as their `heapless::spsc` counterparts, so that code written against
+//! `heapless` can migrate by only changing its imports:
+//!
+//! ```
+//! use ringbuffer_spsc::heapless::Queue;
+//!
+//! let mut queue: Queue<u8, 4> = Queue::new();
+//! let (mut producer, mut consumer) = queue.split();
+//! assert_eq!(producer.enqueue(1), Ok(()));
+//! assert_eq!(consumer.peek(), Some(&1));
+//! assert_eq!(consumer.dequeue(), Some(1));
+//! assert_eq!(consumer.dequeue(), None);
+//! ```
+//!
+//! Unlike `heapless`, a `Queue<T, N>` holds up to `N` elements instead of
+//! `N - 1`, and `N` is required to be a power of two.
+use crate::{index, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::sync::atomic::Ordering;
+
+/// A statically allocated single-producer single-consumer queue.
+pub struct Queue<T, const N: usize> {
+    rb: RingBuffer<T, N>,
+}
+
+impl<T, const N: usize> Queue<T, N> {
+    /// Create an empty queue. This can be used to initialize a `static`.
+    pub const fn new() -> Self {
+        Self {
+            rb: RingBuffer::empty(),
+        }
+    }
+
+    /// Return the maximum number of elements the queue can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Return the number of elements in the queue
+    pub fn len(&self) -> usize {
+        let idx_r = self.rb.idx_r.load(Ordering::Acquire);
+        let idx_w = self.rb.idx_w.load(Ordering::Acquire);
+        index::len(idx_r, idx_w)
+    }
+
+    /// Check whether the queue is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check whether the queue is full
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Return a reference to the front element without dequeuing it
+    pub fn peek(&self) -> Option<&T> {
+        let idx_r = self.rb.idx_r.load(Ordering::Acquire);
+        let idx_w = self.rb.idx_w.load(Ordering::Acquire);
+        if idx_r == idx_w {
+            return None;
+        }
+        // SAFETY: the slot is initialized and the queue is borrowed, so it cannot be dequeued
+        Some(unsafe { &*self.rb.slot_ptr(idx_r) })
+    }
+
+    /// Add an element at the back of the queue, handing it back if the queue is full
+    pub fn enqueue(&mut self, val: T) -> Result<(), T> {
+        self.split().0.enqueue(val)
+    }
+
+    /// Remove the element at the front of the queue
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.split().1.dequeue()
+    }
+
+    /// Split the queue into a producer and a consumer
+    pub fn split(&mut self) -> (Producer<'_, T, N>, Consumer<'_, T, N>) {
+        let (tx, rx) = self.rb.split();
+        (Producer { tx }, Consumer { rx })
+    }
+}
+
+impl<T, const N: usize> Default for Queue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The producing end of a split [`Queue`].
+pub struct Producer<'a, T, const N: usize> {
+    tx: RingBufferWriter<T, N, &'a RingBuffer<T, N>>,
+}
+
+impl<T, const N: usize> Producer<'_, T, N> {
+    /// Add an element at the back of the queue, handing it back if the queue is full
+    #[inline]
+    pub fn enqueue(&mut self, val: T) -> Result<(), T> {
+        match self.tx.push(val) {
+            None => Ok(()),
+            Some(val) => Err(val),
+        }
+    }
+
+    /// Check whether there is room to enqueue an element
+    #[inline]
+    pub fn ready(&self) -> bool {
+        self.len() < N
+    }
+
+    /// Return the number of elements in the queue
+    #[inline]
+    pub fn len(&self) -> usize {
+        let idx_r = self.tx.inner.idx_r.load(Ordering::Acquire);
+        index::len(idx_r, self.tx.local_idx_w)
+    }
+
+    /// Check whether the queue is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the maximum number of elements the queue can hold
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+/// The consuming end of a split [`Queue`].
+pub struct Consumer<'a, T, const N: usize> {
+    rx: RingBufferReader<T, N, &'a RingBuffer<T, N>>,
+}
+
+impl<T, const N: usize> Consumer<'_, T, N> {
+    /// Remove the element at the front of the queue
+    #[inline]
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.rx.pull()
+    }
+
+    /// Return a reference to the front element without dequeuing it
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.rx.peek()
+    }
+
+    /// Check whether there is an element to dequeue
+    #[inline]
+    pub fn ready(&self) -> bool {
+        self.peek().is_some()
+    }
+
+    /// Return the number of elements in the queue
+    #[inline]
+    pub fn len(&self) -> usize {
+        let idx_w = self.rx.inner.idx_w.load(Ordering::Acquire);
+        index::len(self.rx.local_idx_r, idx_w)
+    }
+
+    /// Check whether the queue is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the maximum number of elements the queue can hold
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
@@ -0,0 +1,106 @@
+//! Pushes and pulls reporting how full the ring buffer is.
+//!
+//! [`RingBufferWriter::push_hinted`] tells when the ring buffer is nearly full,
+//! so that a producer can start shedding load before pushes actually fail, and
+//! [`RingBufferReader::pull_hinted`] tells when it is nearly empty, e.g. for a
+//! consumer to slow down its polling. The default thresholds are 7/8 and 1/8
+//! of the capacity:
+//!
+//! ```
+//! use ringbuffer_spsc::{Hint, RingBuffer};
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+//! tx.set_nearly_full(3);
+//! assert_eq!(tx.push_hinted(1), Ok(Hint::Normal));
+//! assert_eq!(tx.push_hinted(2), Ok(Hint::Normal));
+//! assert_eq!(tx.push_hinted(3), Ok(Hint::NearlyFull));
+//! assert_eq!(rx.pull_hinted(), Some((1, Hint::Normal)));
+//! assert_eq!(rx.pull_hinted(), Some((2, Hint::NearlyEmpty)));
+//! ```
+use crate::{index, storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::ops::Deref;
+
+/// How full the ring buffer is after a hinted push or pull.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Hint {
+    /// The ring buffer is between the thresholds.
+    Normal,
+    /// The ring buffer holds at least the nearly full threshold of elements.
+    NearlyFull,
+    /// The ring buffer holds at most the nearly empty threshold of elements.
+    NearlyEmpty,
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Push an element like [`Self::push`], reporting [`Hint::NearlyFull`] if
+    /// the ring buffer holds at least [`Self::set_nearly_full`] elements
+    /// afterwards.
+    ///
+    /// The read index is only refreshed when the cached one says the ring
+    /// buffer is nearly full, so the hint costs nothing otherwise.
+    #[inline]
+    pub fn push_hinted(&mut self, t: T) -> Result<Hint, T> {
+        if let Some(t) = self.push(t) {
+            return Err(t);
+        }
+        if index::len(self.cached_idx_r, self.local_idx_w) < self.nearly_full {
+            return Ok(Hint::Normal);
+        }
+        self.refresh_idx_r();
+        if index::len(self.cached_idx_r, self.local_idx_w) < self.nearly_full {
+            Ok(Hint::Normal)
+        } else {
+            Ok(Hint::NearlyFull)
+        }
+    }
+
+    /// Set the number of elements from which [`Self::push_hinted`] reports the
+    /// ring buffer as nearly full, 7/8 of the capacity by default.
+    ///
+    /// # Panics
+    /// This function panics if `n` is greater than the capacity.
+    pub fn set_nearly_full(&mut self, n: usize) {
+        assert!(n <= N, "nearly full threshold cannot exceed the capacity");
+        self.nearly_full = n;
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Pull an element like [`Self::pull`], reporting [`Hint::NearlyEmpty`] if
+    /// the ring buffer holds at most [`Self::set_nearly_empty`] elements
+    /// afterwards.
+    ///
+    /// The write index is only refreshed when the cached one says the ring
+    /// buffer is nearly empty.
+    #[inline]
+    pub fn pull_hinted(&mut self) -> Option<(T, Hint)> {
+        let t = self.pull()?;
+        if index::len(self.local_idx_r, self.cached_idx_w) > self.nearly_empty {
+            return Some((t, Hint::Normal));
+        }
+        self.refresh_idx_w();
+        if index::len(self.local_idx_r, self.cached_idx_w) > self.nearly_empty {
+            Some((t, Hint::Normal))
+        } else {
+            Some((t, Hint::NearlyEmpty))
+        }
+    }
+
+    /// Set the number of elements up to which [`Self::pull_hinted`] reports the
+    /// ring buffer as nearly empty, 1/8 of the capacity by default.
+    ///
+    /// # Panics
+    /// This function panics if `n` is greater than the capacity.
+    pub fn set_nearly_empty(&mut self, n: usize) {
+        assert!(n <= N, "nearly empty threshold cannot exceed the capacity");
+        self.nearly_empty = n;
+    }
+}
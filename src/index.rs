@@ -0,0 +1,58 @@
+//! Read and write indexes of the ring buffer.
+//!
+//! The indexes grow indefinitely and wrap around at their maximum value: the
+//! ring buffer only relies on their differences, which never exceed the
+//! capacity, and on their low bits selecting the slots. On 32-bit targets,
+//! `usize` indexes wrap around every 2^32 elements though, which breaks the
+//! throughputs and backlogs computed from snapshots of
+//! [`raw_indices`](crate::RingBufferWriter::raw_indices) taken further
+//! apart. The `u64-indexes` feature makes them 64-bit on every target.
+
+/// Type of the read and write indexes: `usize`, or `u64` with the
+/// `u64-indexes` feature.
+#[cfg(not(feature = "u64-indexes"))]
+pub type Index = usize;
+/// Type of the read and write indexes: `usize`, or `u64` with the
+/// `u64-indexes` feature.
+#[cfg(feature = "u64-indexes")]
+pub type Index = u64;
+
+// The casts below are no-ops without `u64-indexes`. Otherwise the numbers of
+// elements fit in a `usize` since they never exceed the capacity, and the low
+// bits of the indexes are enough to select the slots.
+
+/// Return the number of elements from `from` up to `to`
+#[allow(clippy::unnecessary_cast)]
+#[inline(always)]
+pub(crate) const fn len(from: Index, to: Index) -> usize {
+    to.wrapping_sub(from) as usize
+}
+
+/// Return the index `n` elements after `idx`
+#[allow(clippy::unnecessary_cast)]
+#[inline(always)]
+pub(crate) const fn add(idx: Index, n: usize) -> Index {
+    idx.wrapping_add(n as Index)
+}
+
+/// Return the index `n` elements before `idx`
+#[allow(clippy::unnecessary_cast)]
+#[inline(always)]
+pub(crate) const fn sub(idx: Index, n: usize) -> Index {
+    idx.wrapping_sub(n as Index)
+}
+
+/// Return the position of the slot of `idx` in a buffer of `N` slots
+#[allow(clippy::unnecessary_cast)]
+#[inline(always)]
+pub(crate) const fn slot<const N: usize>(idx: Index) -> usize {
+    // Since N is a power of two, N - 1 is a mask of the low bits
+    idx as usize & (N - 1)
+}
+
+/// Return `idx` as a 64-bit sequence number
+#[allow(clippy::unnecessary_cast)]
+#[inline(always)]
+pub(crate) const fn to_u64(idx: Index) -> u64 {
+    idx as u64
+}
@@ -0,0 +1,475 @@
+//! Byte I/O for `u8` ring buffers.
+//!
+//! The reader implements [`io::Read`] and the writer implements [`io::Write`].
+//! They never block: an empty or full ring buffer fails with
+//! [`io::ErrorKind::WouldBlock`], so they fit non-blocking event loops.
+//! Vectored reads and writes map the I/O slices onto the two regions of the
+//! ring buffer, split where they wrap around its end, with one copy per pair.
+//!
+//! With the `tokio` or `futures-io` features, the reader also implements
+//! `AsyncRead` and `AsyncBufRead` and the writer implements `AsyncWrite`,
+//! making an in-memory async pipe. `poll_fill_buf` exposes the readable region
+//! directly out of the ring buffer storage, so parsers can work on it without
+//! any extra copy.
+//!
+//! Reading returns EOF once the writer is dropped and the buffer is drained.
+//! Writing fails with [`io::ErrorKind::BrokenPipe`] once the reader is dropped.
+//! Flushing and shutting down are no-ops: written bytes are visible to the
+//! reader right away and EOF is only signalled by dropping the writer.
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//! use std::io::{IoSlice, IoSliceMut, Read, Write};
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+//! let n = tx.write_vectored(&[IoSlice::new(b"head"), IoSlice::new(b"body")]).unwrap();
+//! assert_eq!(n, 8);
+//!
+//! let (mut head, mut body) = ([0; 4], [0; 4]);
+//! let n = rx.read_vectored(&mut [IoSliceMut::new(&mut head), IoSliceMut::new(&mut body)]);
+//! assert_eq!(n.unwrap(), 8);
+//! assert_eq!((&head, &body), (b"head", b"body"));
+//! ```
+use crate::{index, storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::{mem::MaybeUninit, ops::Deref, ptr};
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use core::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use std::io::{self, IoSlice, IoSliceMut};
+
+/// Copy the bytes of `src` into `dst` in order, until either runs out,
+/// returning how many bytes have been copied.
+fn copy_vectored<'a, 'b>(
+    src: impl IntoIterator<Item = &'a [u8]>,
+    dst: impl IntoIterator<Item = &'b mut [MaybeUninit<u8>]>,
+) -> usize {
+    let mut src = src.into_iter();
+    let mut s: &[u8] = &[];
+    let mut copied = 0;
+    for mut d in dst {
+        while !d.is_empty() {
+            if s.is_empty() {
+                match src.next() {
+                    Some(next) => s = next,
+                    None => return copied,
+                }
+                continue;
+            }
+            let n = s.len().min(d.len());
+            let (head, tail) = core::mem::take(&mut d).split_at_mut(n);
+            // SAFETY: the regions are distinct borrows of at least `n` bytes
+            unsafe { ptr::copy_nonoverlapping(s.as_ptr(), head.as_mut_ptr().cast::<u8>(), n) };
+            d = tail;
+            s = &s[n..];
+            copied += n;
+        }
+    }
+    copied
+}
+
+/// Return the total length of `bufs`
+fn total_len<B: Deref<Target = [u8]>>(bufs: &[B]) -> usize {
+    bufs.iter().fold(0, |n, b| n.saturating_add(b.len()))
+}
+
+impl<const N: usize, R, S> RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Push as many bytes of `bufs` as fit below the soft limit, in order,
+    /// returning how many have been pushed.
+    fn push_vectored(&mut self, bufs: &[IoSlice<'_>]) -> usize {
+        let mut chunk = self.write_free_chunk(total_len(bufs));
+        let (first, second) = chunk.as_mut_slices();
+        let n = copy_vectored(bufs.iter().map(|b| &**b), [first, second]);
+        debug_assert_eq!(n, chunk.len());
+        // SAFETY: the chunk is not longer than `bufs`, so it has been filled
+        unsafe { chunk.commit_all() };
+        n
+    }
+}
+
+impl<const N: usize, R, S> RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Pull as many bytes as fit into `bufs`, in order, returning how many have been pulled.
+    fn pull_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> usize {
+        let chunk = self.read_ready_chunk(total_len(bufs));
+        let (first, second) = chunk.as_slices();
+        // SAFETY: only initialized bytes are written through the uninit view
+        let dst = bufs
+            .iter_mut()
+            .map(|b| unsafe { &mut *(&mut **b as *mut [u8] as *mut [MaybeUninit<u8>]) });
+        let n = copy_vectored([first, second], dst);
+        debug_assert_eq!(n, chunk.len());
+        chunk.commit_all();
+        n
+    }
+}
+
+impl<const N: usize, R, S> RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Read from `src` straight into the free slots below the soft limit,
+    /// returning how many bytes have been pushed.
+    ///
+    /// The free slots are handed to a single [`io::Read::read_vectored`] call,
+    /// split in two where they wrap around the end of the buffer, the ones
+    /// never written before being zeroed first since `src` may read them.
+    /// `Ok(0)` is returned without calling `src` if the ring buffer is full,
+    /// and means EOF otherwise.
+    pub fn fill_from<I: io::Read + ?Sized>(&mut self, src: &mut I) -> io::Result<usize> {
+        // The slots of the first lap of this writer, the others still holding
+        // the bytes written on a previous one
+        let fresh = N.saturating_sub(index::len(self.init_idx_w, self.local_idx_w));
+        let mut chunk = self.write_free_chunk(usize::MAX);
+        if chunk.is_empty() {
+            return Ok(0);
+        }
+        let (first, second) = chunk.as_mut_slices();
+        let fresh_first = fresh.min(first.len());
+        let fresh_second = (fresh - fresh_first).min(second.len());
+        // SAFETY: the slots are zeroed before being viewed as bytes, unless
+        // written on a previous lap
+        let (first, second) = unsafe {
+            first.as_mut_ptr().write_bytes(0, fresh_first);
+            second.as_mut_ptr().write_bytes(0, fresh_second);
+            (
+                &mut *(first as *mut [MaybeUninit<u8>] as *mut [u8]),
+                &mut *(second as *mut [MaybeUninit<u8>] as *mut [u8]),
+            )
+        };
+        let n = src.read_vectored(&mut [IoSliceMut::new(first), IoSliceMut::new(second)])?;
+        assert!(
+            n <= chunk.len(),
+            "the source read more bytes than available"
+        );
+        // SAFETY: all the slots of the chunk have been initialized
+        unsafe { chunk.commit(n) };
+        Ok(n)
+    }
+}
+
+impl<const N: usize, R, S> RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Write the bytes ready to be pulled straight to `dst`, returning how
+    /// many have been pulled.
+    ///
+    /// The bytes are handed to a single [`io::Write::write_vectored`] call,
+    /// split in two where they wrap around the end of the buffer. `Ok(0)` is
+    /// returned without calling `dst` if the ring buffer is empty.
+    pub fn drain_to<D: io::Write + ?Sized>(&mut self, dst: &mut D) -> io::Result<usize> {
+        let chunk = self.read_ready_chunk(usize::MAX);
+        if chunk.is_empty() {
+            return Ok(0);
+        }
+        let (first, second) = chunk.as_slices();
+        let n = dst.write_vectored(&[IoSlice::new(first), IoSlice::new(second)])?;
+        chunk.commit(n);
+        Ok(n)
+    }
+}
+
+impl<const N: usize, R, S> io::Write for RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_vectored(&[IoSlice::new(buf)])
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        if self.is_reader_dropped() {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        if total_len(bufs) == 0 {
+            return Ok(0);
+        }
+        match self.push_vectored(bufs) {
+            0 => Err(io::ErrorKind::WouldBlock.into()),
+            n => Ok(n),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<const N: usize, R, S> io::Read for RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_vectored(&mut [IoSliceMut::new(buf)])
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        if total_len(bufs) == 0 {
+            return Ok(0);
+        }
+        // Check before pulling, the writer might push its last bytes right before dropping
+        let dropped = self.is_writer_dropped();
+        match self.pull_vectored(bufs) {
+            0 if dropped => Ok(0),
+            0 => Err(io::ErrorKind::WouldBlock.into()),
+            n => Ok(n),
+        }
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+impl<const N: usize, R, S> RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Poll for the contiguous readable region starting at the read index.
+    /// An empty slice means that the writer has been dropped.
+    fn poll_fill_buf_inner(&mut self, cx: &mut Context<'_>) -> Poll<&[u8]> {
+        let n = ready!(self.poll_available(cx));
+        // The readable region may wrap around the end of the buffer,
+        // only return the part before the wrap.
+        let len = n.min(N - index::slot::<N>(self.local_idx_r));
+        let slice = unsafe { self.inner.get_slice(self.local_idx_r, len) };
+        // SAFETY: all the slots between the read and the write index are initialized
+        Poll::Ready(unsafe { &*(slice as *const [MaybeUninit<u8>] as *const [u8]) })
+    }
+
+    fn consume_inner(&mut self, amt: usize) {
+        assert!(
+            amt <= index::len(self.local_idx_r, self.cached_idx_w),
+            "Cannot consume more bytes than available."
+        );
+        if amt == 0 {
+            return;
+        }
+        // SAFETY: the bytes are readable and do not need to be dropped
+        unsafe { self.release(amt) };
+    }
+
+    #[cfg(feature = "futures-io")]
+    fn poll_read_inner(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<usize> {
+        if buf.is_empty() {
+            return Poll::Ready(0);
+        }
+        let src = ready!(self.poll_fill_buf_inner(cx));
+        let amt = src.len().min(buf.len());
+        buf[..amt].copy_from_slice(&src[..amt]);
+        self.consume_inner(amt);
+        Poll::Ready(amt)
+    }
+
+    #[cfg(feature = "futures-io")]
+    fn poll_read_vectored_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<usize> {
+        if total_len(bufs) == 0 {
+            return Poll::Ready(0);
+        }
+        ready!(self.poll_available(cx));
+        Poll::Ready(self.pull_vectored(bufs))
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+impl<const N: usize, R, S> RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    fn poll_write_inner(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        if self.is_reader_dropped() {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let n = ready!(self.poll_vacant(cx));
+        if n == 0 {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        // The free region may wrap around the end of the buffer,
+        // only write the part before the wrap.
+        let len = n.min(N - index::slot::<N>(self.local_idx_w)).min(buf.len());
+        let dst = unsafe { self.inner.get_slice_mut(self.local_idx_w, len) };
+        unsafe {
+            ptr::copy_nonoverlapping(buf.as_ptr(), dst.as_mut_ptr().cast::<u8>(), len);
+            self.publish(len);
+        }
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_write_vectored_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        if self.is_reader_dropped() {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if total_len(bufs) == 0 {
+            return Poll::Ready(Ok(0));
+        }
+        if ready!(self.poll_vacant(cx)) == 0 {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        Poll::Ready(Ok(self.push_vectored(bufs)))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize, R, S> tokio::io::AsyncRead for RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>> + Unpin,
+    S: Storage<u8>,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        let src = ready!(this.poll_fill_buf_inner(cx));
+        let amt = src.len().min(buf.remaining());
+        buf.put_slice(&src[..amt]);
+        this.consume_inner(amt);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize, R, S> tokio::io::AsyncBufRead for RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>> + Unpin,
+    S: Storage<u8>,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.get_mut().poll_fill_buf_inner(cx).map(Ok)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_inner(amt)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize, R, S> tokio::io::AsyncWrite for RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>> + Unpin,
+    S: Storage<u8>,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_inner(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_vectored_inner(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<const N: usize, R, S> futures_io::AsyncRead for RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>> + Unpin,
+    S: Storage<u8>,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_inner(cx, buf).map(Ok)
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_vectored_inner(cx, bufs).map(Ok)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<const N: usize, R, S> futures_io::AsyncBufRead for RingBufferReader<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>> + Unpin,
+    S: Storage<u8>,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.get_mut().poll_fill_buf_inner(cx).map(Ok)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_inner(amt)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<const N: usize, R, S> futures_io::AsyncWrite for RingBufferWriter<u8, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>> + Unpin,
+    S: Storage<u8>,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_inner(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_vectored_inner(cx, bufs)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,130 @@
+//! Writer for interrupt handlers.
+//!
+//! An interrupt handler cannot wait for the consumer, nor allocate: an
+//! [`IsrWriter`] only offers pushes that either succeed right away or drop
+//! the element, counting the drops so that a task can report them later. It
+//! wraps the writer of a ring buffer, typically a static one split once at
+//! startup, while the reader is owned by a task:
+//!
+//! ```
+//! use core::cell::RefCell;
+//! use critical_section::Mutex;
+//! use ringbuffer_spsc::{static_ringbuffer, IsrWriter};
+//!
+//! static_ringbuffer!(UART_RX: u8, 64);
+//! static WRITER: Mutex<RefCell<Option<IsrWriter<u8, 64>>>> = Mutex::new(RefCell::new(None));
+//!
+//! // Registered as the UART RX interrupt handler
+//! fn uart_rx_isr(byte: u8) {
+//!     critical_section::with(|cs| {
+//!         if let Some(tx) = WRITER.borrow_ref_mut(cs).as_mut() {
+//!             tx.push(byte);
+//!         }
+//!     });
+//! }
+//!
+//! let (tx, mut rx) = UART_RX.split();
+//! critical_section::with(|cs| WRITER.replace(cs, Some(IsrWriter::new(tx))));
+//!
+//! uart_rx_isr(b'a');
+//! // The task-level consumer
+//! assert_eq!(rx.pull(), Some(b'a'));
+//! ```
+//!
+//! The handler and the task synchronize through the atomic indexes of the
+//! ring buffer only. On targets without native atomics, or to never rely on
+//! them between an interrupt and the code it preempts, the `critical-section`
+//! feature performs every access to the shared indexes and flags inside a
+//! critical section.
+use crate::{
+    storage::{Inline, Storage},
+    Producer, RingBuffer, RingBufferWriter,
+};
+use core::ops::Deref;
+
+/// A writer whose pushes never block nor allocate, counting the elements
+/// dropped because the ring buffer was full.
+pub struct IsrWriter<T, const N: usize, R = &'static RingBuffer<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    tx: RingBufferWriter<T, N, R, S>,
+    dropped: usize,
+}
+
+impl<T, const N: usize, R, S> IsrWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    pub fn new(tx: RingBufferWriter<T, N, R, S>) -> Self {
+        Self { tx, dropped: 0 }
+    }
+
+    /// Get back the wrapped writer
+    pub fn into_inner(self) -> RingBufferWriter<T, N, R, S> {
+        self.tx
+    }
+
+    /// Push an element, dropping it if the ring buffer is full.
+    ///
+    /// Returns whether the element has been pushed.
+    #[inline]
+    pub fn push(&mut self, t: T) -> bool {
+        let pushed = self.tx.push(t).is_none();
+        if !pushed {
+            self.dropped = self.dropped.wrapping_add(1);
+        }
+        pushed
+    }
+
+    /// Push as many elements of `src` as fit, dropping the others.
+    ///
+    /// Returns how many elements have been pushed.
+    #[inline]
+    pub fn push_slice(&mut self, src: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        let n = self.tx.push_slice(src);
+        self.dropped = self.dropped.wrapping_add(src.len() - n);
+        n
+    }
+
+    /// Return the number of elements dropped so far, wrapping around `usize::MAX`
+    #[inline]
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Return the number of elements dropped since the last call, resetting the count
+    #[inline]
+    pub fn take_dropped(&mut self) -> usize {
+        core::mem::take(&mut self.dropped)
+    }
+}
+
+impl<T, const N: usize, R, S> Producer<T> for IsrWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Push an element, handing it back if the ring buffer is full.
+    ///
+    /// Unlike [`IsrWriter::push`], an element handed back is not counted as dropped.
+    #[inline]
+    fn push(&mut self, t: T) -> Option<T> {
+        self.tx.push(t)
+    }
+
+    #[inline]
+    fn slots(&self) -> usize {
+        Producer::slots(&self.tx)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
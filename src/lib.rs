@@ -36,168 +36,1725 @@
 //! p.join().unwrap();
 //! c.join().unwrap();
 //! ```
-// #![no_std]
+//!
+//! # Capacity
+//! A ring buffer with `N = 1` is a mailbox holding a single element: a push
+//! hands the element back while the previous one has not been pulled, and
+//! every element is made visible to the reader on its own. `N = 0` is
+//! rejected at compile time:
+//!
+//! ```compile_fail
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! // error: RingBuffer requires a non-zero capacity.
+//! let rb = RingBuffer::<u8, 0>::empty();
+//! ```
+//!
+//! # Thread safety
+//! The heap-allocated handles are `Send` only if `T` is `Send`, since pushing
+//! and pulling moves the elements from one thread to another. The ring buffer,
+//! and thus the borrowed handles, is `Sync` only if `T` is `Sync` as well,
+//! since [`RingBufferReader::peek`] and the like hand out `&T` through a
+//! shared reference: a reader of [`Cell`](core::cell::Cell)s can move to
+//! another thread, but not be shared between threads:
+//!
+//! ```compile_fail
+//! use ringbuffer_spsc::RingBuffer;
+//! use std::cell::Cell;
+//!
+//! let (mut tx, rx) = RingBuffer::<Cell<u32>, 4>::init();
+//! assert!(tx.push(Cell::new(1)).is_none());
+//!
+//! // `Cell<u32>` is not `Sync`, neither is the reader
+//! let rx = &rx;
+//! std::thread::scope(|s| {
+//!     s.spawn(|| rx.peek().unwrap().set(2));
+//!     s.spawn(|| rx.peek().unwrap().set(3));
+//! });
+//! ```
+//!
+//! Elements that are not `Send`, like [`Rc`](alloc::rc::Rc), can still go
+//! through a ring buffer whose halves both stay on the thread creating them,
+//! e.g. between tasks of a single-threaded executor, and the compiler rejects
+//! any attempt to move a half to another thread:
+//!
+//! ```compile_fail
+//! use ringbuffer_spsc::RingBuffer;
+//! use std::rc::Rc;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<Rc<u32>, 4>::init();
+//! assert!(tx.push(Rc::new(1)).is_none());
+//! assert_eq!(rx.pull().as_deref(), Some(&1));
+//!
+//! // `Rc<u32>` is not `Send`, neither is the reader
+//! std::thread::spawn(move || rx.pull());
+//! ```
+//!
+//! The [`local`] ring buffer avoids the atomic operations altogether in that case.
+//! On wasm32 without the `atomics` target feature, where no other thread can
+//! exist, the shared indexes are plain `Cell`s behind the same API, so code
+//! generic over the ring buffer builds unchanged for single-threaded WASM.
+//!
+//! # Panics
+//! A panic in user code running in the middle of an operation, like the closure
+//! of `push_with`, a `Clone` or `Default` implementation, the iterator filling a
+//! chunk or an overrun callback, never leaves the ring buffer inconsistent. The
+//! indexes are only published once the slots are initialized or emptied, so the
+//! other half sees either the whole operation or nothing of it, and the elements
+//! of an unpublished operation are dropped, or leaked where documented.
+//!
+//! A handle dropped while unwinding, e.g. by a panicking thread, is seen as
+//! dropped by the other half, which can still pull or push the remaining
+//! elements. This is why the handles are `UnwindSafe` as long as `T` is both
+//! `UnwindSafe` and `RefUnwindSafe`.
+#![cfg_attr(not(feature = "std"), no_std)]
+// Threads on wasm32 require a nightly toolchain anyway to rebuild the standard library
+#![cfg_attr(
+    all(target_arch = "wasm32", target_feature = "atomics"),
+    feature(stdarch_wasm_atomic_wait)
+)]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
-use alloc::sync::Arc;
-use cache_padded::CachePadded;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod atomic;
+#[cfg(feature = "std")]
+mod backoff;
+#[cfg(feature = "bytes")]
+mod buf;
+mod burst;
+mod cache;
+#[cfg(feature = "std")]
+pub mod channel;
+mod chunk;
+mod clock;
+mod codec;
+mod double;
+#[cfg(all(feature = "eventfd", target_os = "linux"))]
+mod eventfd;
+mod fmt;
+#[cfg(feature = "std")]
+mod forward;
+mod frame;
+#[cfg(all(feature = "guard-pages", unix))]
+mod guard;
+pub mod heapless;
+mod hint;
+mod index;
+#[cfg(feature = "std")]
+mod io;
+mod isr;
+#[cfg(feature = "alloc")]
+pub mod local;
+#[cfg(feature = "std")]
+mod locked;
+#[cfg(feature = "log")]
+pub mod logger;
+mod mark;
+pub mod notify;
+#[cfg(feature = "owner-check")]
+mod owner;
+mod padding;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod raw;
+#[cfg(feature = "ringbuf")]
+pub mod ringbuf;
+#[cfg(feature = "rt-assert")]
+pub mod rt;
+mod sample;
+#[cfg(feature = "alloc")]
+mod shared;
+#[cfg(feature = "shared-stats")]
+mod shared_stats;
+#[cfg(feature = "futures-sink")]
+mod sink;
+#[cfg(feature = "stats")]
+mod stats;
+pub mod storage;
+#[cfg(feature = "futures-stream")]
+mod stream;
+#[cfg(feature = "std")]
+pub mod timed;
+mod traits;
+#[cfg(feature = "postcard")]
+mod typed;
+#[cfg(feature = "async")]
+mod waker;
+#[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+mod wasm;
+#[cfg(feature = "watchdog")]
+mod watchdog;
+#[cfg(feature = "alloc")]
+mod weak;
+#[cfg(all(feature = "wfe", any(target_arch = "arm", target_arch = "aarch64")))]
+mod wfe;
+mod zeroed;
+
+#[cfg(feature = "async")]
+pub use asynchronous::{DrainedFuture, PullFuture, PushFuture, WatermarkFuture};
+pub use burst::BurstWriter;
+pub use cache::CacheHooks;
+pub use chunk::{ChunkError, ReadChunk, ReadChunkIntoIter, WriteChunk, WriteChunkUninit};
+pub use clock::Clock;
+#[cfg(feature = "std")]
+pub use clock::StdClock;
+pub use codec::FrameError;
+pub use double::DoubleBuffer;
+#[cfg(feature = "std")]
+pub use forward::{FlushPolicy, Forwarder};
+pub use hint::Hint;
+pub use index::Index;
+pub use isr::IsrWriter;
+#[cfg(feature = "std")]
+pub use locked::SharedWriter;
+pub use mark::ReadMark;
+pub use padding::CachePadded;
+#[cfg(feature = "alloc")]
+pub use raw::{ReaderMetadata, WriterMetadata};
+#[cfg(feature = "alloc")]
+pub use shared::SharedRingBuffer;
+#[cfg(feature = "shared-stats")]
+pub use shared_stats::StatsSnapshot;
+#[cfg(feature = "futures-sink")]
+pub use sink::Disconnected;
+#[cfg(feature = "futures-stream")]
+pub use stream::ReadyChunks;
+pub use traits::{Consumer, Producer};
+#[cfg(feature = "postcard")]
+pub use typed::{TypedError, TypedReceiver, TypedSender};
+#[cfg(feature = "alloc")]
+pub use weak::WeakRingBuffer;
+pub use zeroed::ZeroedWriter;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use atomic::{AtomicIndex, AtomicUsize};
 use core::{
-    cell::UnsafeCell,
+    marker::PhantomData,
     mem::{self, MaybeUninit},
-    sync::atomic::{AtomicUsize, Ordering},
+    ops::Deref,
+    panic::RefUnwindSafe,
+    sync::atomic::Ordering,
 };
+use storage::{Inline, Storage};
+
+// Flags of the shared state telling which handles are alive
+const WRITER_ALIVE: usize = 0b01;
+const READER_ALIVE: usize = 0b10;
+// Flag set once the first owner of a heap-allocated ring buffer is dropped
+#[cfg(feature = "alloc")]
+const HANDLE_RELEASED: usize = 0b100;
+// Flag of the ring buffers mapped between guard pages
+#[cfg(all(feature = "guard-pages", unix))]
+const GUARD_PAGES: usize = 0b1000;
+
+// Pointer to the ring buffer of the handles when not specified: the heap
+// allocation of `RingBuffer::init` if available, a `static` one otherwise.
+#[cfg(feature = "alloc")]
+pub(crate) type DefaultRef<T, const N: usize> = SharedRingBuffer<T, N>;
+#[cfg(not(feature = "alloc"))]
+pub(crate) type DefaultRef<T, const N: usize> = &'static RingBuffer<T, N>;
 
-pub struct RingBuffer<T, const N: usize> {
-    buffer: UnsafeCell<[MaybeUninit<T>; N]>,
-    idx_r: CachePadded<AtomicUsize>,
-    idx_w: CachePadded<AtomicUsize>,
+/// Writer half of a ring buffer declared with [`static_ringbuffer!`].
+pub type StaticRingBufferWriter<T, const N: usize> =
+    RingBufferWriter<T, N, &'static RingBuffer<T, N>>;
+/// Reader half of a ring buffer declared with [`static_ringbuffer!`].
+pub type StaticRingBufferReader<T, const N: usize> =
+    RingBufferReader<T, N, &'static RingBuffer<T, N>>;
+
+/// Declare a `static` ring buffer to be split into its writer and reader
+/// at runtime, without any allocation.
+///
+/// Both halves borrow the static: they can be handed out as `'static`
+/// resources to different tasks, e.g. the writer to an interrupt handler
+/// and the reader to the idle task in RTIC.
+///
+/// ```
+/// use ringbuffer_spsc::{static_ringbuffer, StaticRingBufferReader, StaticRingBufferWriter};
+///
+/// static_ringbuffer!(UART_RX: u8, 256);
+///
+/// let (mut tx, mut rx): (StaticRingBufferWriter<u8, 256>, StaticRingBufferReader<u8, 256>) =
+///     UART_RX.split();
+/// assert!(tx.push(42).is_none());
+/// assert_eq!(rx.pull(), Some(42));
+/// ```
+#[macro_export]
+macro_rules! static_ringbuffer {
+    ($(#[$attr:meta])* $vis:vis $name:ident : $t:ty, $n:expr) => {
+        $(#[$attr])*
+        $vis static $name: $crate::RingBuffer<$t, { $n }> = $crate::RingBuffer::empty();
+    };
 }
 
-unsafe impl<T, const N: usize> Send for RingBuffer<T, N> {}
-unsafe impl<T, const N: usize> Sync for RingBuffer<T, N> {}
+// With guard pages, the slots are laid out last to border the trailing one
+#[cfg_attr(all(feature = "guard-pages", unix), repr(C))]
+pub struct RingBuffer<T, const N: usize, S = Inline<T, N>>
+where
+    S: Storage<T>,
+{
+    idx_r: CachePadded<AtomicIndex>,
+    idx_w: CachePadded<AtomicIndex>,
+    flags: AtomicUsize,
+    // Address of the header of the weak handles, 0 until the first downgrade
+    #[cfg(feature = "alloc")]
+    weak: AtomicUsize,
+    // Woken by the writer when new elements are published
+    #[cfg(feature = "async")]
+    rx_waker: waker::AtomicWaker,
+    // Woken by the reader when slots are released
+    #[cfg(feature = "async")]
+    tx_waker: waker::AtomicWaker,
+    // Read index the pending writer waits for
+    #[cfg(feature = "async")]
+    tx_wake_at: AtomicIndex,
+    #[cfg(feature = "watchdog")]
+    watchdog: watchdog::Activity,
+    // Failure counters readable from both sides
+    #[cfg(feature = "shared-stats")]
+    counters: shared_stats::Counters,
+    // Rung by the writer when the reader waits on an eventfd
+    #[cfg(all(feature = "eventfd", target_os = "linux"))]
+    doorbell: eventfd::Doorbell,
+    buffer: S,
+    _marker: PhantomData<T>,
+}
 
-impl<T, const N: usize> RingBuffer<T, N> {
-    #[allow(clippy::new_ret_no_self)]
-    #[deprecated(since = "0.1.8", note = "please use `init()` instead.")]
-    pub fn new() -> (RingBufferWriter<T, N>, RingBufferReader<T, N>) {
-        Self::init()
+// The elements move from the thread of the writer to the one of the reader,
+// and each slot is only ever accessed by one of them at a time. Sharing also
+// requires `T: Sync`, since the reader hands out `&T` through `&self`.
+unsafe impl<T: Send, const N: usize, S: Storage<T> + Send> Send for RingBuffer<T, N, S> {}
+unsafe impl<T: Send + Sync, const N: usize, S: Storage<T> + Send> Sync for RingBuffer<T, N, S> {}
+// The shared state stays consistent across panics, see the crate documentation:
+// the interior mutability of the indexes and the slots is never observed half
+// way. What a shared reference can still reach after a panic are the elements,
+// only ever handed out as `&T` by the reader, so they must be `RefUnwindSafe`.
+impl<T: RefUnwindSafe, const N: usize, S: Storage<T>> RefUnwindSafe for RingBuffer<T, N, S> {}
+
+impl<T, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Maximum number of elements the ring buffer can hold
+    pub const CAPACITY: usize = N;
+    /// Mask turning an index into the position of its slot
+    pub const MASK: usize = N - 1;
+
+    /// Return the maximum number of elements the ring buffer can hold
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
     }
 
-    pub fn init() -> (RingBufferWriter<T, N>, RingBufferReader<T, N>) {
+    /// Create an empty ring buffer whose slots live in `storage`, to be
+    /// [split](Self::split) into its halves.
+    ///
+    /// See the [`storage`] module for the available storages.
+    ///
+    /// # Panics
+    /// This function panics if the capacity is not a power of 2, or if the
+    /// storage holds fewer than `N` slots.
+    pub fn with_storage(storage: S) -> Self {
+        const { assert!(N > 0, "RingBuffer requires a non-zero capacity.") };
         assert!(
             N.is_power_of_two(),
             "RingBuffer requires the capacity to be a power of 2. {N} is not."
         );
-        let rb = Arc::new(RingBuffer {
-            buffer: UnsafeCell::new(array_init::array_init(|_| MaybeUninit::uninit())),
-            idx_r: CachePadded::new(AtomicUsize::new(0)),
-            idx_w: CachePadded::new(AtomicUsize::new(0)),
-        });
+        assert!(
+            storage.capacity() >= N,
+            "RingBuffer requires a storage of at least {N} slots."
+        );
+        Self::from_storage(storage)
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Create a heap-allocated ring buffer whose slots live in `storage`, and
+    /// return its two halves.
+    ///
+    /// ```
+    /// use ringbuffer_spsc::{storage::Boxed, RingBuffer};
+    ///
+    /// let (mut tx, mut rx) = RingBuffer::<u8, 65536, _>::init_with_storage(Boxed::new(65536));
+    /// assert!(tx.push(1).is_none());
+    /// assert_eq!(rx.pull(), Some(1));
+    /// ```
+    ///
+    /// # Panics
+    /// This function panics like [`Self::with_storage`].
+    #[allow(clippy::type_complexity)]
+    pub fn init_with_storage(
+        storage: S,
+    ) -> (
+        RingBufferWriter<T, N, SharedRingBuffer<T, N, S>, S>,
+        RingBufferReader<T, N, SharedRingBuffer<T, N, S>, S>,
+    ) {
+        let rb = Box::new(Self::with_storage(storage));
+        Self::init_shared(SharedRingBuffer::pair(rb), 0)
+    }
+
+    const fn from_storage(buffer: S) -> Self {
+        RingBuffer {
+            buffer,
+            _marker: PhantomData,
+            idx_r: CachePadded::new(AtomicIndex::new(0)),
+            idx_w: CachePadded::new(AtomicIndex::new(0)),
+            flags: AtomicUsize::new(0),
+            #[cfg(feature = "alloc")]
+            weak: AtomicUsize::new(0),
+            #[cfg(feature = "async")]
+            rx_waker: waker::AtomicWaker::new(),
+            #[cfg(feature = "async")]
+            tx_waker: waker::AtomicWaker::new(),
+            #[cfg(feature = "async")]
+            tx_wake_at: AtomicIndex::new(0),
+            #[cfg(feature = "watchdog")]
+            watchdog: watchdog::Activity::new(),
+            #[cfg(feature = "shared-stats")]
+            counters: shared_stats::Counters::new(),
+            #[cfg(all(feature = "eventfd", target_os = "linux"))]
+            doorbell: eventfd::Doorbell::new(),
+        }
+    }
+
+    /// Set up the handles of a newly allocated ring buffer, with indexes starting at `idx`.
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::type_complexity)]
+    fn init_shared(
+        (rb_w, rb_r): (SharedRingBuffer<T, N, S>, SharedRingBuffer<T, N, S>),
+        idx: Index,
+    ) -> (
+        RingBufferWriter<T, N, SharedRingBuffer<T, N, S>, S>,
+        RingBufferReader<T, N, SharedRingBuffer<T, N, S>, S>,
+    ) {
+        rb_w.idx_r.store(idx, Ordering::Relaxed);
+        rb_w.idx_w.store(idx, Ordering::Relaxed);
+        rb_w.flags
+            .store(WRITER_ALIVE | READER_ALIVE, Ordering::Relaxed);
         (
-            RingBufferWriter {
-                inner: rb.clone(),
-                cached_idx_r: 0,
-                local_idx_w: 0,
-            },
-            RingBufferReader {
-                inner: rb,
-                local_idx_r: 0,
-                cached_idx_w: 0,
-            },
+            RingBufferWriter::new_raw(rb_w, idx, idx),
+            RingBufferReader::new_raw(rb_r, idx, idx),
+        )
+    }
+
+    /// Split a ring buffer into a writer and a reader borrowing it.
+    ///
+    /// The ring buffer can be split again once both halves have been
+    /// dropped, picking up where they left off.
+    ///
+    /// The ring buffer can be owned by the user, e.g. as a field of their
+    /// own struct or in a `Box` for large capacities: the halves access it
+    /// without any reference counting nor extra allocation.
+    ///
+    /// ```
+    /// use ringbuffer_spsc::RingBuffer;
+    ///
+    /// let rb = Box::new(RingBuffer::<u64, 1024>::empty());
+    /// std::thread::scope(|s| {
+    ///     let (mut tx, mut rx) = rb.split();
+    ///     s.spawn(move || assert!(tx.push(42).is_none()));
+    ///     s.spawn(move || while rx.pull().is_none() {});
+    /// });
+    /// ```
+    ///
+    /// # Panics
+    /// This function panics if the halves of a previous split are still alive.
+    pub fn split(
+        &self,
+    ) -> (
+        RingBufferWriter<T, N, &Self, S>,
+        RingBufferReader<T, N, &Self, S>,
+    ) {
+        let res = self.flags.compare_exchange(
+            0,
+            WRITER_ALIVE | READER_ALIVE,
+            Ordering::Acquire,
+            Ordering::Relaxed,
+        );
+        assert!(res.is_ok(), "RingBuffer has already been split.");
+        let idx_r = self.idx_r.load(Ordering::Acquire);
+        let idx_w = self.idx_w.load(Ordering::Acquire);
+        (
+            RingBufferWriter::new_raw(self, idx_w, idx_r),
+            RingBufferReader::new_raw(self, idx_r, idx_w),
         )
     }
 
+    /// Return a pointer to the first slot, deriving its provenance from the whole storage.
+    #[inline]
+    fn slots(&self) -> *mut MaybeUninit<T> {
+        self.buffer.as_ptr()
+    }
+
     #[allow(clippy::mut_from_ref)]
     #[inline]
-    unsafe fn get_mut(&self, idx: usize) -> &mut MaybeUninit<T> {
+    unsafe fn get_mut(&self, idx: Index) -> &mut MaybeUninit<T> {
         // Since N is a power of two, N-1 is a mask covering N
         // elements overflowing when N elements have been added.
         // Indexes are left growing indefinetely and naturally wraps
-        // around once the index increment reaches usize::MAX.
-        &mut (*self.buffer.get())[idx & (N - 1)]
+        // around once the index increment reaches its maximum value.
+        let idx = index::slot::<N>(idx);
+        debug_assert!(idx < N);
+        // The mask keeps the index in bounds: skip the bounds check, and go through
+        // a raw pointer so that no reference to the whole array is ever created
+        &mut *self.slots().add(idx)
+    }
+
+    /// Returns `len` slots starting at `idx` for reading, which must not cross
+    /// the end of the array.
+    ///
+    /// Slots only read through `&self`, e.g. by `peek`, must go through this
+    /// or [`Self::slot_ptr`]: a `&mut` would assert unique access and
+    /// invalidate the shared references handed out before.
+    #[inline]
+    unsafe fn get_slice(&self, idx: Index, len: usize) -> &[MaybeUninit<T>] {
+        let start = index::slot::<N>(idx);
+        debug_assert!(start + len <= N);
+        core::slice::from_raw_parts(self.slots().add(start).cast_const(), len)
+    }
+
+    /// Returns `len` slots starting at `idx`, which must not cross the end of the array.
+    #[allow(clippy::mut_from_ref)]
+    #[inline]
+    unsafe fn get_slice_mut(&self, idx: Index, len: usize) -> &mut [MaybeUninit<T>] {
+        let start = index::slot::<N>(idx);
+        debug_assert!(start + len <= N);
+        // Go through a raw pointer so that disjoint slices can be alive at the same time
+        let ptr = self.slots().add(start);
+        core::slice::from_raw_parts_mut(ptr, len)
     }
 }
 
-impl<T, const N: usize> Drop for RingBuffer<T, N> {
-    fn drop(&mut self) {
+impl<T, const N: usize> RingBuffer<T, N> {
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::new_ret_no_self)]
+    #[deprecated(since = "0.1.8", note = "please use `init()` instead.")]
+    pub fn new() -> (RingBufferWriter<T, N>, RingBufferReader<T, N>) {
+        Self::init()
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn init() -> (RingBufferWriter<T, N>, RingBufferReader<T, N>) {
+        Self::init_at(0)
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Create a full ring buffer, whose `i`-th element is `f(i)`.
+    ///
+    /// This suits pool-like uses, where the reader takes the elements out and
+    /// the writer hands them back once recycled.
+    pub fn init_with<F>(f: F) -> (RingBufferWriter<T, N>, RingBufferReader<T, N>)
+    where
+        F: FnMut(usize) -> T,
+    {
+        let (mut tx, rx) = Self::init();
+        tx.write_free_chunk(N).fill_from_iter((0..N).map(f));
+        (tx, rx)
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Create `M` independent ring buffers, returning their writers and their
+    /// readers in two arrays, e.g. for sharded designs with one ring buffer
+    /// per core or per connection.
+    ///
+    /// ```
+    /// use ringbuffer_spsc::RingBuffer;
+    ///
+    /// let (mut txs, mut rxs) = RingBuffer::<usize, 4>::init_array::<8>();
+    /// for (i, tx) in txs.iter_mut().enumerate() {
+    ///     assert!(tx.push(i).is_none());
+    /// }
+    /// assert_eq!(rxs[3].pull(), Some(3));
+    /// ```
+    pub fn init_array<const M: usize>() -> ([RingBufferWriter<T, N>; M], [RingBufferReader<T, N>; M])
+    {
+        let mut readers = [const { None }; M];
+        let writers = core::array::from_fn(|i| {
+            let (tx, rx) = Self::init();
+            readers[i] = Some(rx);
+            tx
+        });
+        (writers, readers.map(Option::unwrap))
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Create a ring buffer whose indexes start at `idx` instead of 0.
+    ///
+    /// Starting close to `Index::MAX` allows to exercise the wrap around of
+    /// the indexes without pushing 2^64 elements first.
+    #[doc(hidden)]
+    pub fn init_at(idx: Index) -> (RingBufferWriter<T, N>, RingBufferReader<T, N>) {
+        assert!(
+            N.is_power_of_two(),
+            "RingBuffer requires the capacity to be a power of 2. {N} is not."
+        );
+        Self::init_shared(SharedRingBuffer::pair(Box::default()), idx)
+    }
+
+    /// Create an empty ring buffer to be [split](Self::split) into its halves.
+    ///
+    /// This is a `const fn` so that it can be used to initialize a `static`,
+    /// see [`static_ringbuffer!`].
+    pub const fn empty() -> Self {
+        const { assert!(N > 0, "RingBuffer requires a non-zero capacity.") };
+        assert!(
+            N.is_power_of_two(),
+            "RingBuffer requires the capacity to be a power of 2."
+        );
+        Self::from_storage(Inline::new())
+    }
+
+    /// Run `f` with the halves of a ring buffer living on the stack.
+    ///
+    /// The halves borrow the ring buffer, so they can be moved into the threads
+    /// of a [`std::thread::scope`] without any heap allocation, which suits
+    /// short-lived pipelines. The buffer takes `N` slots of stack space: large
+    /// capacities should rather [split](Self::split) a boxed ring buffer.
+    ///
+    /// ```
+    /// use ringbuffer_spsc::RingBuffer;
+    ///
+    /// let sum = RingBuffer::<u64, 64>::scoped(|mut tx, mut rx| {
+    ///     std::thread::scope(|s| {
+    ///         s.spawn(move || {
+    ///             for i in 0..1000 {
+    ///                 while tx.push(i).is_some() {
+    ///                     std::thread::yield_now();
+    ///                 }
+    ///             }
+    ///         });
+    ///         let mut sum = 0;
+    ///         for _ in 0..1000 {
+    ///             sum += loop {
+    ///                 match rx.pull() {
+    ///                     Some(i) => break i,
+    ///                     None => std::thread::yield_now(),
+    ///                 }
+    ///             };
+    ///         }
+    ///         sum
+    ///     })
+    /// });
+    /// assert_eq!(sum, 499_500);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn scoped<F, O>(f: F) -> O
+    where
+        F: FnOnce(RingBufferWriter<T, N, &Self>, RingBufferReader<T, N, &Self>) -> O,
+    {
+        let rb = Self::empty();
+        let (tx, rx) = rb.split();
+        f(tx, rx)
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Drop the elements left in the ring buffer, leaving the indexes untouched.
+    ///
+    /// # Safety
+    /// No handle may access the ring buffer anymore, and the elements must
+    /// not be dropped again.
+    unsafe fn drop_elements(&self) {
         let mut idx_r = self.idx_r.load(Ordering::Acquire);
         let idx_w = self.idx_w.load(Ordering::Acquire);
 
         while idx_r != idx_w {
-            let t =
-                unsafe { mem::replace(self.get_mut(idx_r), MaybeUninit::uninit()).assume_init() };
+            let t = mem::replace(self.get_mut(idx_r), MaybeUninit::uninit()).assume_init();
             mem::drop(t);
             idx_r = idx_r.wrapping_add(1);
         }
     }
 }
 
-pub struct RingBufferWriter<T, const N: usize> {
-    inner: Arc<RingBuffer<T, N>>,
-    cached_idx_r: usize,
-    local_idx_w: usize,
+impl<T, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Publish the write index `idx_w` to the reader, after the `pre_publish`
+    /// hook of the writer, and wake the reader up.
+    ///
+    /// This is the only place the write index is published from, by the
+    /// writer and by the `ringbuf` producer alike.
+    #[inline]
+    pub(crate) fn publish_idx_w(&self, idx_w: Index, pre_publish: Option<fn()>) {
+        if let Some(pre_publish) = pre_publish {
+            pre_publish();
+        }
+        #[cfg(feature = "async")]
+        let old_idx_w = self.idx_w.load(Ordering::Relaxed);
+        self.idx_w.store(idx_w, Ordering::Release);
+        #[cfg(feature = "async")]
+        self.notify_reader(old_idx_w);
+        #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+        wasm::notify(&self.idx_w);
+        #[cfg(all(feature = "wfe", any(target_arch = "arm", target_arch = "aarch64")))]
+        wfe::notify();
+        #[cfg(feature = "watchdog")]
+        self.watchdog.push.touch();
+        #[cfg(all(feature = "eventfd", target_os = "linux"))]
+        self.doorbell.ring();
+    }
+
+    /// Release the slots up to the read index `idx_r` to the writer, after
+    /// the `post_consume` hook of the reader, and wake the writer up.
+    ///
+    /// See [`Self::publish_idx_w`].
+    #[inline]
+    pub(crate) fn release_idx_r(&self, idx_r: Index, post_consume: Option<fn()>) {
+        if let Some(post_consume) = post_consume {
+            post_consume();
+        }
+        #[cfg(feature = "async")]
+        let old_idx_r = self.idx_r.load(Ordering::Relaxed);
+        self.idx_r.store(idx_r, Ordering::Release);
+        #[cfg(feature = "async")]
+        self.notify_writer(old_idx_r, idx_r);
+        #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+        wasm::notify(&self.idx_r);
+        #[cfg(all(feature = "wfe", any(target_arch = "arm", target_arch = "aarch64")))]
+        wfe::notify();
+        #[cfg(feature = "watchdog")]
+        self.watchdog.pull.touch();
+    }
 }
 
-impl<T, const N: usize> RingBufferWriter<T, N> {
+impl<T, const N: usize, S> Drop for RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        // SAFETY: the ring buffer is being dropped
+        unsafe { self.drop_elements() };
+    }
+}
+
+pub struct RingBufferWriter<T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    inner: R,
+    cached_idx_r: Index,
+    local_idx_w: Index,
+    // Number of elements above which `push` reports the ring buffer as full
+    limit: usize,
+    // Read index the reader is awaited to reach
+    watermark: Option<Index>,
+    // Called when a push fails because the ring buffer is full
+    overrun: Option<fn()>,
+    // Called before every publication of the write index
+    pre_publish: Option<fn()>,
+    // Whether the last element pushed by `push_or_merge` is kept back from the reader
+    pending: bool,
+    // Cache maintenance of the regions handed to devices
+    cache: CacheHooks,
+    // Number of elements from which `push_hinted` reports the ring buffer as nearly full
+    nearly_full: usize,
+    // Number of retries of the async operations before registering a waker
+    #[cfg(feature = "async")]
+    spins: u32,
+    // Number of free slots required by `Sink::poll_ready`
+    #[cfg(feature = "futures-sink")]
+    sink_vacancy: usize,
+    // Write index of the first slot written by this writer, whose slots up to
+    // `N` elements after are the ones `fill_from` has to zero
+    #[cfg(feature = "std")]
+    init_idx_w: Index,
+    #[cfg(feature = "stats")]
+    refreshes: u64,
+    #[cfg(feature = "owner-check")]
+    owner: owner::Owner,
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Maximum number of elements the ring buffer can hold
+    pub const CAPACITY: usize = N;
+    /// Mask turning an index into the position of its slot
+    pub const MASK: usize = N - 1;
+
+    /// Return the maximum number of elements the ring buffer can hold
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Build a writer with the default configuration, at the write index
+    /// `idx_w` and with the read index `idx_r` cached.
+    pub(crate) fn new_raw(inner: R, idx_w: Index, idx_r: Index) -> Self {
+        Self {
+            inner,
+            cached_idx_r: idx_r,
+            local_idx_w: idx_w,
+            limit: N,
+            watermark: None,
+            overrun: None,
+            pre_publish: None,
+            pending: false,
+            cache: CacheHooks::NONE,
+            nearly_full: N - N / 8,
+            #[cfg(feature = "async")]
+            spins: 0,
+            #[cfg(feature = "futures-sink")]
+            sink_vacancy: 1,
+            #[cfg(feature = "std")]
+            init_idx_w: idx_w,
+            #[cfg(feature = "stats")]
+            refreshes: 0,
+            #[cfg(feature = "owner-check")]
+            owner: owner::Owner::new(),
+        }
+    }
+
     #[inline]
     pub fn push(&mut self, t: T) -> Option<T> {
+        self.push_within(t, self.limit)
+    }
+
+    /// Push an element, handing it back as an error if the ring buffer is full.
+    ///
+    /// This is [`Self::push`] with the signature of `Vec::push_within_capacity`.
+    #[inline]
+    pub fn push_within_capacity(&mut self, t: T) -> Result<(), T> {
+        match self.push(t) {
+            None => Ok(()),
+            Some(t) => Err(t),
+        }
+    }
+
+    /// Push an element, possibly using the headroom above the [soft limit](Self::set_soft_limit).
+    #[inline]
+    pub fn push_priority(&mut self, t: T) -> Option<T> {
+        self.push_within(t, N)
+    }
+
+    /// Push the element returned by `f`, handing `f` back without calling it if
+    /// the ring buffer is full.
+    ///
+    /// The element is written straight into its slot. If `f` panics, nothing
+    /// is made available to the reader and the slot is left free.
+    #[inline]
+    pub fn push_with<F>(&mut self, f: F) -> Result<(), F>
+    where
+        F: FnOnce() -> T,
+    {
+        let chunk = self.write_free_chunk(1);
+        if chunk.is_empty() {
+            return Err(f);
+        }
+        chunk.fill_from_iter(core::iter::once_with(f));
+        Ok(())
+    }
+
+    /// Set the number of elements above which [`Self::push`], [`Self::push_slice`]
+    /// and the other regular writes report the ring buffer as full.
+    ///
+    /// The slots above the limit are reserved to [`Self::push_priority`], so that
+    /// urgent elements get through even when the regular stream saturates the
+    /// ring buffer. The limit is the whole capacity by default.
+    ///
+    /// # Panics
+    /// This function panics if `limit` is greater than the capacity.
+    pub fn set_soft_limit(&mut self, limit: usize) {
+        assert!(limit <= N, "soft limit cannot exceed the capacity");
+        self.limit = limit;
+    }
+
+    /// Return the number of elements above which regular writes report the ring buffer as full
+    #[inline]
+    pub fn soft_limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Set a function called every time [`Self::push`] or [`Self::push_priority`]
+    /// fails because the ring buffer is full, or unset it with `None`.
+    ///
+    /// This allows to count overruns and trigger a mitigation, e.g. in a
+    /// realtime audio callback, without wrapping every push.
+    pub fn set_overrun_callback(&mut self, overrun: Option<fn()>) {
+        self.overrun = overrun;
+    }
+
+    /// Set a function called right before every publication of the write
+    /// index, or unset it with `None`.
+    ///
+    /// The release ordering of the index only covers the writes of the CPU.
+    /// On weakly ordered SoCs, elements written by a DMA engine may still be
+    /// in flight when the index is published: the hook is the place for a
+    /// `DSB` or a platform barrier ordering them before the publication.
+    pub fn set_pre_publish_hook(&mut self, pre_publish: Option<fn()>) {
+        self.pre_publish = pre_publish;
+    }
+
+    /// Arm a watermark reached once the reader has pulled enough elements
+    /// for at most `remaining` of the ones pushed so far to be left.
+    ///
+    /// This allows credit-style pacing: [`Self::watermark_reached`] is cheap to
+    /// call on every iteration, and with the `async` feature `wait_watermark`
+    /// waits for it without polling.
+    /// Arming a watermark replaces the previous one.
+    pub fn set_watermark(&mut self, remaining: usize) {
+        self.watermark = Some(index::sub(self.local_idx_w, remaining));
+    }
+
+    /// Disarm the watermark
+    pub fn clear_watermark(&mut self) {
+        self.watermark = None;
+    }
+
+    /// Check whether the reader has reached the watermark, disarming it if so.
+    ///
+    /// Returns `false` if no watermark is armed.
+    pub fn watermark_reached(&mut self) -> bool {
+        let Some(target) = self.watermark else {
+            return false;
+        };
+        // The cached read index might already be past the watermark
+        if !self.is_past(target, self.cached_idx_r) {
+            self.refresh_idx_r();
+            if !self.is_past(target, self.cached_idx_r) {
+                return false;
+            }
+        }
+        self.watermark = None;
+        true
+    }
+
+    /// Block until the reader has pulled every element pushed so far.
+    ///
+    /// The element kept back by [`Self::push_or_merge`] is published first.
+    /// Returns `false` if the reader is dropped before catching up.
+    #[cfg(feature = "std")]
+    pub fn wait_drained(&mut self) -> bool {
+        let mut backoff = backoff::Backoff::new();
+        loop {
+            // Check the reader before the index: the elements it pulled before
+            // being dropped are then guaranteed to be visible.
+            let dropped = self.is_reader_dropped();
+            if self.is_drained() {
+                return true;
+            } else if dropped {
+                return false;
+            }
+            backoff.snooze();
+        }
+    }
+
+    /// Check whether the reader has pulled every element pushed so far
+    #[cfg(any(feature = "std", feature = "async"))]
+    #[inline]
+    fn is_drained(&mut self) -> bool {
+        self.publish_pending();
+        if self.cached_idx_r != self.local_idx_w {
+            self.refresh_idx_r();
+        }
+        self.cached_idx_r == self.local_idx_w
+    }
+
+    #[inline]
+    fn is_past(&self, target: Index, idx_r: Index) -> bool {
+        // Both indexes are behind the write index
+        self.local_idx_w.wrapping_sub(idx_r) <= self.local_idx_w.wrapping_sub(target)
+    }
+
+    #[inline]
+    fn push_within(&mut self, t: T, limit: usize) -> Option<T> {
+        #[cfg(feature = "rt-assert")]
+        let _section = rt::Section::enter();
+        #[cfg(feature = "owner-check")]
+        self.owner.check();
         // Check if the ring buffer is potentially full.
-        // This happens when the difference between the write and read indexes equals
-        // the ring buffer capacity. Note that the write and read indexes are left growing
-        // indefinitely, so we need to compute the difference by accounting for any eventual
-        // overflow. This requires wrapping the subtraction operation.
-        if self.local_idx_w.wrapping_sub(self.cached_idx_r) == N {
-            self.cached_idx_r = self.inner.idx_r.load(Ordering::Acquire);
+        // This happens when the difference between the write and read indexes reaches
+        // the limit, the ring buffer capacity at most. Note that the write and read indexes
+        // are left growing indefinitely, so we need to compute the difference by accounting
+        // for any eventual overflow. This requires wrapping the subtraction operation.
+        if index::len(self.cached_idx_r, self.local_idx_w) >= limit {
+            self.refresh_idx_r();
             // Check if the ring buffer is really full
-            if self.local_idx_w.wrapping_sub(self.cached_idx_r) == N {
+            if index::len(self.cached_idx_r, self.local_idx_w) >= limit {
+                #[cfg(feature = "shared-stats")]
+                self.inner.counters.failed_push();
+                if let Some(overrun) = self.overrun {
+                    overrun();
+                }
                 return Some(t);
             }
         }
 
         // Insert the element in the ring buffer
-        unsafe { mem::replace(self.inner.get_mut(self.local_idx_w), MaybeUninit::new(t)) };
+        unsafe { self.inner.get_mut(self.local_idx_w).write(t) };
         // Let's increment the counter and let it grow indefinitely and potentially overflow resetting it to 0.
         self.local_idx_w = self.local_idx_w.wrapping_add(1);
-        self.inner.idx_w.store(self.local_idx_w, Ordering::Release);
+        self.publish_idx_w(self.local_idx_w);
+
+        None
+    }
 
+    /// Push an element, merging it with `merge` into the last element pushed
+    /// this way if the ring buffer is full.
+    ///
+    /// An element taking the last free slot below the [soft limit](Self::set_soft_limit)
+    /// is not published right away, so that it can still be updated, e.g. to
+    /// coalesce damage rectangles or counters instead of dropping the new
+    /// element. It is published by the next write, [`Self::publish_pending`],
+    /// or when the writer is dropped.
+    /// The element is handed back if the ring buffer is full of published elements.
+    #[inline]
+    pub fn push_or_merge<F>(&mut self, t: T, merge: F) -> Option<T>
+    where
+        F: FnOnce(&mut T, T),
+    {
+        #[cfg(feature = "rt-assert")]
+        let _section = rt::Section::enter();
+        #[cfg(feature = "owner-check")]
+        self.owner.check();
+        if index::len(self.cached_idx_r, self.local_idx_w) >= self.limit {
+            self.refresh_idx_r();
+        }
+        let len = index::len(self.cached_idx_r, self.local_idx_w);
+        if len < self.limit {
+            unsafe { self.inner.get_mut(self.local_idx_w).write(t) };
+            // Keep the element unpublished if it fills the ring buffer
+            let published = self.local_idx_w;
+            self.local_idx_w = self.local_idx_w.wrapping_add(1);
+            if len + 1 < self.limit {
+                self.publish_idx_w(self.local_idx_w);
+            } else {
+                if self.pending {
+                    self.publish_idx_w(published);
+                }
+                self.pending = true;
+            }
+            return None;
+        }
+        if !self.pending {
+            #[cfg(feature = "shared-stats")]
+            self.inner.counters.failed_push();
+            if let Some(overrun) = self.overrun {
+                overrun();
+            }
+            return Some(t);
+        }
+        // SAFETY: the last slot is initialized and not visible to the reader yet
+        merge(
+            unsafe {
+                self.inner
+                    .get_mut(self.local_idx_w.wrapping_sub(1))
+                    .assume_init_mut()
+            },
+            t,
+        );
         None
     }
+
+    /// Publish the element kept back by [`Self::push_or_merge`], returning
+    /// whether there was one.
+    #[inline]
+    pub fn publish_pending(&mut self) -> bool {
+        #[cfg(feature = "rt-assert")]
+        let _section = rt::Section::enter();
+        #[cfg(feature = "owner-check")]
+        self.owner.check();
+        if !self.pending {
+            return false;
+        }
+        self.publish_idx_w(self.local_idx_w);
+        true
+    }
+
+    /// Make the elements before `idx_w` available to the reader and wake it up
+    #[inline]
+    fn publish_idx_w(&mut self, idx_w: Index) {
+        self.pending = false;
+        self.inner.publish_idx_w(idx_w, self.pre_publish);
+    }
+
+    /// Push as many elements of `src` as fit, returning how many have been pushed.
+    ///
+    /// The elements are copied with two `memcpy` at most, and are made
+    /// available to the reader all at once.
+    #[inline]
+    pub fn push_slice(&mut self, src: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        let mut chunk = self.write_free_chunk(src.len());
+        let (first, second) = chunk.as_mut_slices();
+        let (src_first, src_second) = src[..first.len() + second.len()].split_at(first.len());
+        // SAFETY: `MaybeUninit<T>` has the same layout as `T`
+        unsafe {
+            let src_first = &*(src_first as *const [T] as *const [MaybeUninit<T>]);
+            let src_second = &*(src_second as *const [T] as *const [MaybeUninit<T>]);
+            first.copy_from_slice(src_first);
+            second.copy_from_slice(src_second);
+            let n = chunk.len();
+            chunk.commit_all();
+            n
+        }
+    }
+
+    /// Push clones of as many elements of `src` as fit, returning how many have been pushed.
+    ///
+    /// The elements are made available to the reader all at once. If cloning
+    /// panics, none of them is pushed and the clones made so far are dropped.
+    #[inline]
+    pub fn push_slice_cloned(&mut self, src: &[T]) -> usize
+    where
+        T: Clone,
+    {
+        let chunk = self.write_free_chunk(src.len());
+        chunk.fill_from_iter(src.iter().cloned())
+    }
+
+    /// Push all the elements of `src`, blocking while the ring buffer is full.
+    ///
+    /// If the reader is dropped before all of them are pushed, the number of
+    /// elements pushed is returned as error.
+    #[cfg(feature = "std")]
+    pub fn push_blocking_all(&mut self, src: &[T]) -> Result<(), usize>
+    where
+        T: Copy,
+    {
+        self.push_blocking_with(src, Self::push_slice)
+    }
+
+    /// Push clones of all the elements of `src`, blocking while the ring buffer is full.
+    ///
+    /// See [`Self::push_blocking_all`].
+    #[cfg(feature = "std")]
+    pub fn push_blocking_all_cloned(&mut self, src: &[T]) -> Result<(), usize>
+    where
+        T: Clone,
+    {
+        self.push_blocking_with(src, Self::push_slice_cloned)
+    }
+
+    #[cfg(feature = "std")]
+    fn push_blocking_with(
+        &mut self,
+        src: &[T],
+        push: fn(&mut Self, &[T]) -> usize,
+    ) -> Result<(), usize> {
+        let mut pushed = 0;
+        let mut backoff = backoff::Backoff::new();
+        while pushed < src.len() {
+            if self.is_reader_dropped() {
+                return Err(pushed);
+            }
+            let n = push(self, &src[pushed..]);
+            if n > 0 {
+                pushed += n;
+                backoff = backoff::Backoff::new();
+            } else {
+                backoff.snooze();
+            }
+        }
+        Ok(())
+    }
+
+    /// Push up to `n` clones of `value`, returning how many have been pushed.
+    ///
+    /// The clones are made available to the reader all at once, e.g. to
+    /// insert silence in an audio stream. If cloning panics, none of them
+    /// is pushed.
+    #[inline]
+    pub fn push_repeat(&mut self, value: &T, n: usize) -> usize
+    where
+        T: Clone,
+    {
+        let chunk = self.write_free_chunk(n);
+        let len = chunk.len();
+        chunk.fill_from_iter(core::iter::repeat_n(value, len).cloned())
+    }
+
+    /// Push elements from `iter` until the ring buffer is full, handing back
+    /// the iterator over the remaining ones.
+    ///
+    /// The elements are made available to the reader all at once, and the
+    /// iterator is not advanced past the last element pushed.
+    #[inline]
+    pub fn push_all<I>(&mut self, iter: I) -> I::IntoIter
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+        self.write_free_chunk(usize::MAX)
+            .fill_from_iter(iter.by_ref());
+        iter
+    }
+
+    /// Push all the elements of `items` or none of them, handing them back if
+    /// there are not enough free slots.
+    ///
+    /// The elements are made available to the reader with a single update of
+    /// the write index, so it never observes part of the batch.
+    #[inline]
+    pub fn try_push_n<I>(&mut self, items: I) -> Result<(), I::IntoIter>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let items = items.into_iter();
+        let chunk = self.write_free_chunk(items.len());
+        if chunk.len() < items.len() {
+            return Err(items);
+        }
+        chunk.fill_from_iter(items);
+        Ok(())
+    }
+
+    /// Push all the elements of `items` or none of them, handing the array
+    /// back if there are fewer than `K` free slots.
+    ///
+    /// Like [`Self::try_push_n`], the reader never observes part of the array.
+    #[inline]
+    pub fn push_array<const K: usize>(&mut self, items: [T; K]) -> Result<(), [T; K]> {
+        let chunk = self.write_free_chunk(K);
+        if chunk.len() < K {
+            return Err(items);
+        }
+        chunk.fill_from_iter(items);
+        Ok(())
+    }
+
+    /// Return a chunk of up to `n` free slots below the soft limit.
+    #[inline]
+    fn write_free_chunk(&mut self, n: usize) -> WriteChunkUninit<'_, T, N, R, S> {
+        if self
+            .limit
+            .saturating_sub(index::len(self.cached_idx_r, self.local_idx_w))
+            < n
+        {
+            self.refresh_idx_r();
+        }
+        let n = n.min(
+            self.limit
+                .saturating_sub(index::len(self.cached_idx_r, self.local_idx_w)),
+        );
+        // The cached read index is up to date, so this never fails
+        self.write_chunk_uninit(n).unwrap()
+    }
+
+    /// Make the `n` slots following the write index available to the reader.
+    ///
+    /// This completes a write performed through [`Self::writable_ptr`].
+    ///
+    /// # Safety
+    /// The slots must be free and initialized.
+    #[inline]
+    pub unsafe fn publish(&mut self, n: usize) {
+        #[cfg(feature = "rt-assert")]
+        let _section = rt::Section::enter();
+        #[cfg(feature = "owner-check")]
+        self.owner.check();
+        self.local_idx_w = index::add(self.local_idx_w, n);
+        self.publish_idx_w(self.local_idx_w);
+    }
+
+    /// Reload the read index published by the reader, returning the number of free slots.
+    ///
+    /// The writer only reloads it when the ring buffer looks full otherwise,
+    /// so this gives a fresh view of the occupancy for decisions like adaptive
+    /// batching, and lets the next pushes rely on it.
+    #[inline]
+    pub fn refresh_read_index(&mut self) -> usize {
+        self.refresh_idx_r();
+        N - index::len(self.cached_idx_r, self.local_idx_w)
+    }
+
+    /// Reload the read index published by the reader
+    #[inline]
+    pub(crate) fn refresh_idx_r(&mut self) {
+        self.cached_idx_r = self.inner.idx_r.load(Ordering::Acquire);
+        #[cfg(feature = "stats")]
+        {
+            self.refreshes += 1;
+        }
+    }
+
+    /// Check whether the reader has been dropped
+    #[inline]
+    pub(crate) fn is_reader_dropped(&self) -> bool {
+        self.inner.flags.load(Ordering::Acquire) & READER_ALIVE == 0
+    }
 }
 
-pub struct RingBufferReader<T, const N: usize> {
-    inner: Arc<RingBuffer<T, N>>,
-    local_idx_r: usize,
-    cached_idx_w: usize,
+impl<T, const N: usize, R, S> Drop for RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        // Hand the element kept back by `push_or_merge` to the reader
+        if self.pending {
+            self.publish_idx_w(self.local_idx_w);
+        }
+        self.inner.flags.fetch_and(!WRITER_ALIVE, Ordering::Release);
+        // Let a pending reader observe the disconnection
+        #[cfg(feature = "async")]
+        self.inner.rx_waker.wake();
+        #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+        wasm::notify(&self.inner.idx_w);
+        #[cfg(all(feature = "wfe", any(target_arch = "arm", target_arch = "aarch64")))]
+        wfe::notify();
+        #[cfg(all(feature = "eventfd", target_os = "linux"))]
+        self.inner.doorbell.ring();
+    }
 }
 
-impl<T, const N: usize> RingBufferReader<T, N> {
+pub struct RingBufferReader<T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    inner: R,
+    local_idx_r: Index,
+    cached_idx_w: Index,
+    // Called when a pull fails because the ring buffer is empty
+    underrun: Option<fn()>,
+    // Called before every publication of the read index
+    post_consume: Option<fn()>,
+    // Cache maintenance of the regions handed to devices and of the published elements
+    cache: CacheHooks,
+    // Number of elements up to which `pull_hinted` reports the ring buffer as nearly empty
+    nearly_empty: usize,
+    // Number of retries of the async operations before registering a waker
+    #[cfg(feature = "async")]
+    spins: u32,
+    #[cfg(feature = "stats")]
+    refreshes: u64,
+    #[cfg(feature = "owner-check")]
+    owner: owner::Owner,
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Maximum number of elements the ring buffer can hold
+    pub const CAPACITY: usize = N;
+    /// Mask turning an index into the position of its slot
+    pub const MASK: usize = N - 1;
+
+    /// Return the maximum number of elements the ring buffer can hold
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Build a reader with the default configuration, at the read index
+    /// `idx_r` and with the write index `idx_w` cached.
+    pub(crate) fn new_raw(inner: R, idx_r: Index, idx_w: Index) -> Self {
+        Self {
+            inner,
+            local_idx_r: idx_r,
+            cached_idx_w: idx_w,
+            underrun: None,
+            post_consume: None,
+            cache: CacheHooks::NONE,
+            nearly_empty: N / 8,
+            #[cfg(feature = "async")]
+            spins: 0,
+            #[cfg(feature = "stats")]
+            refreshes: 0,
+            #[cfg(feature = "owner-check")]
+            owner: owner::Owner::new(),
+        }
+    }
+
     /// Calculate the number of elements currently in the ring buffer
     pub fn len(&self) -> usize {
         let write_index = self.inner.idx_w.load(Ordering::Acquire);
         let read_index = self.local_idx_r;
 
-        // Log the current read and write indices
-        // println!("[Debug] RingBufferReader - Write index: {}, Read index: {}", write_index, read_index);
+        // The indexes are left growing indefinitely: wrap the subtraction
+        // in case the write index has overflowed and the read index not yet.
+        index::len(read_index, write_index)
+    }
+
+    /// Check whether the ring buffer is currently empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Set a function called every time [`Self::pull`] fails because the ring
+    /// buffer is empty, or unset it with `None`.
+    ///
+    /// See [`RingBufferWriter::set_overrun_callback`].
+    pub fn set_underrun_callback(&mut self, underrun: Option<fn()>) {
+        self.underrun = underrun;
+    }
 
-        // If the write index is greater than or equal to the read index, calculate the difference directly
-        if write_index >= read_index {
-            let length = write_index - read_index;
-            // println!("[Debug] RingBufferReader - Current length (direct): {}", length);
-            length
-        } else {
-            // If the write index has wrapped around, add the buffer size to the difference
-            let length = (write_index + N) - read_index;
-            // println!("[Debug] RingBufferReader - Current length (wrapped): {}", length);
-            length
+    /// Set a function called right before every publication of the read
+    /// index, once the elements have been consumed, or unset it with `None`.
+    ///
+    /// See [`RingBufferWriter::set_pre_publish_hook`]: the hook orders the
+    /// reads of a DMA engine draining the slots before they are handed back
+    /// to the writer.
+    pub fn set_post_consume_hook(&mut self, post_consume: Option<fn()>) {
+        self.post_consume = post_consume;
+    }
+
+    /// Return a reference to the next element to be pulled without removing it
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        // Check if the ring buffer is really empty only if it potentially is
+        if self.local_idx_r == self.cached_idx_w
+            && self.local_idx_r == self.inner.idx_w.load(Ordering::Acquire)
+        {
+            return None;
         }
+        if let Some(invalidate) = self.cache.invalidate {
+            if self.local_idx_r == self.cached_idx_w {
+                // The element has not been invalidated with a refresh yet
+                self.inner.maintain(invalidate, self.local_idx_r, 1);
+            }
+        }
+        // SAFETY: the slot is initialized and the writer does not access it
+        // until it is released, which requires a mutable borrow of the reader
+        Some(unsafe { &*self.inner.slot_ptr(self.local_idx_r) })
+    }
+
+    /// Return a mutable reference to the next element to be pulled without removing it.
+    ///
+    /// Elements mutated in place sit next to the slots being written: pad
+    /// them with [`CachePadded`] if the writer and the reader then contend
+    /// for the same cache lines.
+    #[inline]
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        if self.local_idx_r == self.cached_idx_w {
+            self.refresh_idx_w();
+            if self.local_idx_r == self.cached_idx_w {
+                return None;
+            }
+        }
+        // SAFETY: the slot is initialized and only the reader accesses it until pulled
+        Some(unsafe { self.inner.get_mut(self.local_idx_r).assume_init_mut() })
+    }
+
+    /// Return the elements ready to be pulled without removing them, split in
+    /// two when wrapping around the end of the buffer.
+    #[inline]
+    pub fn peek_slice(&self) -> (&[T], &[T]) {
+        let n = index::len(self.local_idx_r, self.inner.idx_w.load(Ordering::Acquire));
+        let first_len = n.min(N - index::slot::<N>(self.local_idx_r));
+        // SAFETY: the slots are initialized and the writer does not access them
+        // until they are released, which requires a mutable borrow of the reader
+        unsafe {
+            let first = self.inner.get_slice(self.local_idx_r, first_len);
+            let second = self.inner.get_slice(0, n - first_len);
+            (
+                &*(first as *const [MaybeUninit<T>] as *const [T]),
+                &*(second as *const [MaybeUninit<T>] as *const [T]),
+            )
+        }
+    }
+
+    /// Pull an element, like [`Self::pull`] with the name used by `std` collections.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.pull()
+    }
+
+    /// Return a reference to the next element, like [`Self::peek`] with the
+    /// name used by `std` collections.
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.peek()
     }
 
     #[inline]
     pub fn pull(&mut self) -> Option<T> {
+        #[cfg(feature = "rt-assert")]
+        let _section = rt::Section::enter();
+        #[cfg(feature = "owner-check")]
+        self.owner.check();
         // Check if the ring buffer is potentially empty
         // println!("[Debug] RingBufferReader - Attempting to pull element");
         if self.local_idx_r == self.cached_idx_w {
             // Update the write index
-            self.cached_idx_w = self.inner.idx_w.load(Ordering::Acquire);
+            self.refresh_idx_w();
             // println!("[Debug] RingBufferReader - Updated Write index: {}, Read index: {}", self.cached_idx_w, self.local_idx_r);
 
             // Check if the ring buffer is really empty
             if self.local_idx_r == self.cached_idx_w {
                 // println!("[Debug] RingBufferReader - Ring buffer is empty");
+                #[cfg(feature = "shared-stats")]
+                self.inner.counters.failed_pull();
+                if let Some(underrun) = self.underrun {
+                    underrun();
+                }
                 return None;
             }
         }
         // Remove the element from the ring buffer
         let t = unsafe {
             // println!("[Debug] RingBufferReader - Removing element at index {}", self.local_idx_r);
-            mem::replace(self.inner.get_mut(self.local_idx_r), MaybeUninit::uninit()).assume_init()
+            self.inner.get_mut(self.local_idx_r).assume_init_read()
         };
         // Let's increment the counter and let it grow indefinitely
         // and potentially overflow resetting it to 0.
         self.local_idx_r = self.local_idx_r.wrapping_add(1);
-        self.inner.idx_r.store(self.local_idx_r, Ordering::Release);
+        self.inner
+            .release_idx_r(self.local_idx_r, self.post_consume);
         // println!("[Debug] RingBufferReader - Updated Read index to {}", self.local_idx_r);
 
         Some(t)
     }
+
+    /// Pull an element together with its sequence number, returning `None`
+    /// if the ring buffer is empty.
+    ///
+    /// The sequence number is the read index before the pull, i.e. the number
+    /// of elements pulled before it since the ring buffer was created: a stage
+    /// forwarding the numbers downstream lets the last one detect gaps. It
+    /// wraps around with the indexes, see [`Self::raw_indices`].
+    #[inline]
+    pub fn pull_indexed(&mut self) -> Option<(u64, T)> {
+        let idx = index::to_u64(self.local_idx_r);
+        self.pull().map(|t| (idx, t))
+    }
+
+    /// Pull as many elements as available into `dst`, returning how many have been pulled.
+    ///
+    /// The elements are copied with two `memcpy` at most, and their slots are
+    /// made available to the writer all at once.
+    #[inline]
+    pub fn pull_slice(&mut self, dst: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let chunk = self.read_ready_chunk(dst.len());
+        let (first, second) = chunk.as_slices();
+        let n = first.len() + second.len();
+        dst[..first.len()].copy_from_slice(first);
+        dst[first.len()..n].copy_from_slice(second);
+        // Copy elements do not need to be dropped
+        chunk.commit_all();
+        n
+    }
+
+    /// Pull all the elements currently available, passing them to `f` one by one.
+    ///
+    /// The slots are made available to the writer all at once at the end,
+    /// which is cheaper than pulling the elements in a loop. Returns how many
+    /// elements have been pulled.
+    #[inline]
+    pub fn pull_each<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(T),
+    {
+        let mut iter = self.read_ready_chunk(usize::MAX).into_iter();
+        iter.by_ref().for_each(&mut f);
+        // The slots of the elements passed to `f` are released on drop, even if it panics
+        iter.iterated()
+    }
+
+    /// Pull exactly `K` elements, or none if fewer are ready.
+    ///
+    /// The slots are made available to the writer with a single update of the
+    /// read index, which suits consumers of fixed-size records.
+    #[inline]
+    pub fn pull_array<const K: usize>(&mut self) -> Option<[T; K]> {
+        let mut iter = self.read_chunk(K).ok()?.into_iter();
+        Some(core::array::from_fn(|_| iter.next().unwrap()))
+    }
+
+    /// Pull elements into `dst` until it is filled, blocking while the ring buffer is empty.
+    ///
+    /// If the writer is dropped before `dst` is filled, the number of elements
+    /// pulled into it is returned as error.
+    #[cfg(feature = "std")]
+    pub fn read_exact_into(&mut self, dst: &mut [T]) -> Result<(), usize> {
+        let mut read = 0;
+        let mut backoff = backoff::Backoff::new();
+        while read < dst.len() {
+            // Check the writer before pulling: the elements it pushed before
+            // being dropped are then guaranteed to be visible.
+            let dropped = self.is_writer_dropped();
+            let n = self.pull_into(&mut dst[read..]);
+            if n > 0 {
+                read += n;
+                backoff = backoff::Backoff::new();
+            } else if dropped {
+                return Err(read);
+            } else {
+                backoff.snooze();
+            }
+        }
+        Ok(())
+    }
+
+    /// Move as many elements as available into `dst`, returning how many have been moved.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn pull_into(&mut self, dst: &mut [T]) -> usize {
+        let chunk = self.read_ready_chunk(dst.len());
+        let n = chunk.len();
+        for (d, t) in dst.iter_mut().zip(chunk) {
+            *d = t;
+        }
+        n
+    }
+
+    /// Return a chunk of up to `n` readable slots.
+    #[inline]
+    fn read_ready_chunk(&mut self, n: usize) -> ReadChunk<'_, T, N, R, S> {
+        if index::len(self.local_idx_r, self.cached_idx_w) < n {
+            self.refresh_idx_w();
+        }
+        let n = n.min(index::len(self.local_idx_r, self.cached_idx_w));
+        // The cached write index is up to date, so this never fails
+        self.read_chunk(n).unwrap()
+    }
+
+    /// Make the `n` slots following the read index available to the writer.
+    ///
+    /// This completes a read performed through [`Self::readable_ptr`].
+    ///
+    /// # Safety
+    /// The slots must be readable and their elements must have been moved out or dropped.
+    #[inline]
+    pub unsafe fn release(&mut self, n: usize) {
+        #[cfg(feature = "rt-assert")]
+        let _section = rt::Section::enter();
+        #[cfg(feature = "owner-check")]
+        self.owner.check();
+        self.local_idx_r = index::add(self.local_idx_r, n);
+        self.inner
+            .release_idx_r(self.local_idx_r, self.post_consume);
+    }
+
+    /// Reload the write index published by the writer, returning the number of elements ready.
+    ///
+    /// See [`RingBufferWriter::refresh_read_index`].
+    #[inline]
+    pub fn refresh_write_index(&mut self) -> usize {
+        self.refresh_idx_w();
+        index::len(self.local_idx_r, self.cached_idx_w)
+    }
+
+    /// Reload the write index published by the writer
+    #[inline]
+    pub(crate) fn refresh_idx_w(&mut self) {
+        let idx_w = self.inner.idx_w.load(Ordering::Acquire);
+        if let Some(invalidate) = self.cache.invalidate {
+            let n = index::len(self.cached_idx_w, idx_w);
+            if n > 0 {
+                self.inner.maintain(invalidate, self.cached_idx_w, n);
+            }
+        }
+        self.cached_idx_w = idx_w;
+        #[cfg(feature = "stats")]
+        {
+            self.refreshes += 1;
+        }
+    }
+
+    /// Check whether the writer has been dropped
+    #[inline]
+    pub(crate) fn is_writer_dropped(&self) -> bool {
+        // Acquire synchronizes with the writer drop so that any element it published is visible
+        self.inner.flags.load(Ordering::Acquire) & WRITER_ALIVE == 0
+    }
+}
+
+impl<T, const N: usize, R, S> Drop for RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        self.inner.flags.fetch_and(!READER_ALIVE, Ordering::Release);
+        // Let a pending writer observe the disconnection
+        #[cfg(feature = "async")]
+        self.inner.tx_waker.wake();
+        #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+        wasm::notify(&self.inner.idx_r);
+        #[cfg(all(feature = "wfe", any(target_arch = "arm", target_arch = "aarch64")))]
+        wfe::notify();
+    }
+}
+
+/// Move up to `max` elements from `rx` into `tx`, returning how many have been moved.
+///
+/// The elements are moved as a whole: the write and read indexes are updated once
+/// each, so this is an efficient way to relay elements between two pipeline stages,
+/// possibly with different capacities.
+pub fn pump<T, const N: usize, const M: usize, RR, RW, S1, S2>(
+    rx: &mut RingBufferReader<T, N, RR, S1>,
+    tx: &mut RingBufferWriter<T, M, RW, S2>,
+    max: usize,
+) -> usize
+where
+    RR: Deref<Target = RingBuffer<T, N, S1>>,
+    RW: Deref<Target = RingBuffer<T, M, S2>>,
+    S1: Storage<T>,
+    S2: Storage<T>,
+{
+    let src = rx.read_ready_chunk(max);
+    let dst = tx.write_free_chunk(src.len());
+    // Only the elements actually moved out of `src` are released
+    dst.fill_from_iter(src)
+}
+
+/// Create a ring buffer and spawn a thread running `producer` with its writer
+/// and a thread running `consumer` with its reader.
+///
+/// ```
+/// let (p, c) = ringbuffer_spsc::spawn_pair::<usize, 16, _, _, _, _>(
+///     |mut tx| {
+///         for i in 0..100 {
+///             while tx.push(i).is_some() {
+///                 std::thread::yield_now();
+///             }
+///         }
+///     },
+///     |mut rx| {
+///         let mut sum = 0;
+///         for _ in 0..100 {
+///             sum += loop {
+///                 match rx.pull() {
+///                     Some(i) => break i,
+///                     None => std::thread::yield_now(),
+///                 }
+///             };
+///         }
+///         sum
+///     },
+/// );
+/// p.join().unwrap();
+/// assert_eq!(c.join().unwrap(), 4950);
+/// ```
+#[cfg(feature = "std")]
+pub fn spawn_pair<T, const N: usize, P, C, PR, CR>(
+    producer: P,
+    consumer: C,
+) -> (std::thread::JoinHandle<PR>, std::thread::JoinHandle<CR>)
+where
+    T: Send + 'static,
+    P: FnOnce(RingBufferWriter<T, N>) -> PR + Send + 'static,
+    C: FnOnce(RingBufferReader<T, N>) -> CR + Send + 'static,
+    PR: Send + 'static,
+    CR: Send + 'static,
+{
+    let (tx, rx) = RingBuffer::init();
+    (
+        std::thread::spawn(move || producer(tx)),
+        std::thread::spawn(move || consumer(rx)),
+    )
 }
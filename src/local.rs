@@ -0,0 +1,201 @@
+//! Single-threaded ring buffer for producers and consumers living on the same thread.
+//!
+//! [`RingBuffer`], [`RingBufferWriter`] and [`RingBufferReader`] mirror their
+//! thread-safe counterparts, but the indexes are plain [`Cell`]s shared through
+//! an [`Rc`]: no atomic operation, fence or cache padding is involved. This suits
+//! generators and cooperative schedulers without interrupts, and the handles
+//! being neither `Send` nor `Sync` rules out any use across threads at compile time.
+//!
+//! ```
+//! use ringbuffer_spsc::local::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+//! assert!(tx.push(1).is_none());
+//! assert_eq!(rx.peek(), Some(&1));
+//! assert_eq!(rx.pull(), Some(1));
+//! assert_eq!(rx.pull(), None);
+//! ```
+use crate::storage::{Inline, Storage};
+use alloc::rc::Rc;
+use core::{cell::Cell, marker::PhantomData, mem::MaybeUninit};
+
+/// The state shared by the two halves of a single-threaded ring buffer.
+pub struct RingBuffer<T, const N: usize, S = Inline<T, N>>
+where
+    S: Storage<T>,
+{
+    buffer: S,
+    idx_r: Cell<usize>,
+    idx_w: Cell<usize>,
+    _marker: PhantomData<T>,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// Create a ring buffer and return its two halves.
+    pub fn init() -> (RingBufferWriter<T, N>, RingBufferReader<T, N>) {
+        Self::init_with_storage(Inline::new())
+    }
+}
+
+impl<T, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Create a ring buffer whose slots live in `storage` and return its two
+    /// halves, see the [`storage`](crate::storage) module.
+    ///
+    /// # Panics
+    /// This function panics if the capacity is not a power of 2, or if the
+    /// storage holds fewer than `N` slots.
+    pub fn init_with_storage(storage: S) -> (RingBufferWriter<T, N, S>, RingBufferReader<T, N, S>) {
+        const { assert!(N > 0, "RingBuffer requires a non-zero capacity.") };
+        assert!(
+            N.is_power_of_two(),
+            "RingBuffer requires the capacity to be a power of 2. {N} is not."
+        );
+        assert!(
+            storage.capacity() >= N,
+            "RingBuffer requires a storage of at least {N} slots."
+        );
+        let rb = Rc::new(RingBuffer {
+            buffer: storage,
+            idx_r: Cell::new(0),
+            idx_w: Cell::new(0),
+            _marker: PhantomData,
+        });
+        (
+            RingBufferWriter { inner: rb.clone() },
+            RingBufferReader { inner: rb },
+        )
+    }
+
+    /// Return a pointer to the slot of `idx`
+    #[inline]
+    fn slot(&self, idx: usize) -> *mut MaybeUninit<T> {
+        // SAFETY: the mask keeps the index in bounds
+        unsafe { self.buffer.as_ptr().add(idx & (N - 1)) }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.idx_w.get().wrapping_sub(self.idx_r.get())
+    }
+}
+
+impl<T, const N: usize, S> Drop for RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        while self.idx_r.get() != self.idx_w.get() {
+            // SAFETY: the slots between the read and the write index are initialized
+            unsafe { (*self.slot(self.idx_r.get())).assume_init_drop() };
+            self.idx_r.set(self.idx_r.get().wrapping_add(1));
+        }
+    }
+}
+
+/// The writing half of a single-threaded ring buffer.
+pub struct RingBufferWriter<T, const N: usize, S = Inline<T, N>>
+where
+    S: Storage<T>,
+{
+    inner: Rc<RingBuffer<T, N, S>>,
+}
+
+impl<T, const N: usize, S> RingBufferWriter<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Push an element, handing it back if the ring buffer is full.
+    #[inline]
+    pub fn push(&mut self, t: T) -> Option<T> {
+        let idx_w = self.inner.idx_w.get();
+        if self.inner.len() == N {
+            return Some(t);
+        }
+        // SAFETY: the slot is free and only the writer accesses free slots
+        unsafe { (*self.inner.slot(idx_w)).write(t) };
+        self.inner.idx_w.set(idx_w.wrapping_add(1));
+        None
+    }
+
+    /// Return the number of free slots
+    #[inline]
+    pub fn slots(&self) -> usize {
+        N - self.inner.len()
+    }
+
+    /// Return the maximum number of elements the ring buffer can hold
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Check whether the reader has been dropped
+    #[inline]
+    pub fn is_reader_dropped(&self) -> bool {
+        Rc::strong_count(&self.inner) == 1
+    }
+}
+
+/// The reading half of a single-threaded ring buffer.
+pub struct RingBufferReader<T, const N: usize, S = Inline<T, N>>
+where
+    S: Storage<T>,
+{
+    inner: Rc<RingBuffer<T, N, S>>,
+}
+
+impl<T, const N: usize, S> RingBufferReader<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Pull an element, returning `None` if the ring buffer is empty.
+    #[inline]
+    pub fn pull(&mut self) -> Option<T> {
+        let idx_r = self.inner.idx_r.get();
+        if idx_r == self.inner.idx_w.get() {
+            return None;
+        }
+        // SAFETY: the slot is initialized and the read index moves past it
+        let t = unsafe { (*self.inner.slot(idx_r)).assume_init_read() };
+        self.inner.idx_r.set(idx_r.wrapping_add(1));
+        Some(t)
+    }
+
+    /// Return a reference to the next element to be pulled without removing it
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        let idx_r = self.inner.idx_r.get();
+        if idx_r == self.inner.idx_w.get() {
+            return None;
+        }
+        // SAFETY: the slot stays initialized until pulled, which borrows the reader mutably
+        Some(unsafe { (*self.inner.slot(idx_r)).assume_init_ref() })
+    }
+
+    /// Return the number of elements ready to be pulled
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Check whether the ring buffer is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the maximum number of elements the ring buffer can hold
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Check whether the writer has been dropped
+    #[inline]
+    pub fn is_writer_dropped(&self) -> bool {
+        Rc::strong_count(&self.inner) == 1
+    }
+}
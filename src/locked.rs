@@ -0,0 +1,129 @@
+//! Writer shared by several producers, available with the `std` feature.
+//!
+//! [`SharedWriter`] puts a writer behind a [`Mutex`], so that an occasional
+//! second producer, e.g. a low-rate control thread next to the main data
+//! stream, can push through a shared reference without turning the whole
+//! pipeline into an MPSC queue:
+//!
+//! ```
+//! use ringbuffer_spsc::{RingBuffer, SharedWriter};
+//! use std::sync::Arc;
+//!
+//! let (tx, mut rx) = RingBuffer::<u32, 16>::init();
+//! let tx = Arc::new(SharedWriter::new(tx));
+//! let control = tx.clone();
+//! std::thread::spawn(move || assert!(control.push(0).is_none()))
+//!     .join()
+//!     .unwrap();
+//! assert!(tx.push(1).is_none());
+//! assert_eq!(rx.pull(), Some(0));
+//! assert_eq!(rx.pull(), Some(1));
+//! ```
+//!
+//! Every push takes the lock, so the single producer fast path is lost:
+//! the wrapper is meant for the case where contention is rare.
+use crate::{
+    storage::{Inline, Storage},
+    DefaultRef, Producer, RingBuffer, RingBufferWriter,
+};
+use std::{
+    ops::Deref,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// A writer behind a mutex, pushing through a shared reference.
+pub struct SharedWriter<T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    writer: Mutex<RingBufferWriter<T, N, R, S>>,
+}
+
+impl<T, const N: usize, R, S> SharedWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Put `writer` behind a mutex.
+    pub fn new(writer: RingBufferWriter<T, N, R, S>) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Lock the writer, e.g. to push several elements without interleaving
+    /// with the other producers.
+    ///
+    /// A producer panicking while holding the lock does not poison it: the
+    /// writer is never left in an inconsistent state.
+    pub fn lock(&self) -> MutexGuard<'_, RingBufferWriter<T, N, R, S>> {
+        #[allow(unused_mut)]
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        // The mutex hands the writer over from thread to thread
+        #[cfg(feature = "owner-check")]
+        writer.owner.reset();
+        writer
+    }
+
+    /// Push an element, handing it back if the ring buffer is full.
+    #[inline]
+    pub fn push(&self, t: T) -> Option<T> {
+        self.lock().push(t)
+    }
+
+    /// Push as many elements of `src` as fit, returning how many have been pushed.
+    ///
+    /// The elements are contiguous in the ring buffer: no other producer
+    /// interleaves with them.
+    #[inline]
+    pub fn push_slice(&self, src: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        self.lock().push_slice(src)
+    }
+
+    /// Unwrap the writer, e.g. once the other producers are gone.
+    pub fn into_inner(self) -> RingBufferWriter<T, N, R, S> {
+        #[allow(unused_mut)]
+        let mut writer = self
+            .writer
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        #[cfg(feature = "owner-check")]
+        writer.owner.reset();
+        writer
+    }
+}
+
+impl<T, const N: usize, R, S> From<RingBufferWriter<T, N, R, S>> for SharedWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn from(writer: RingBufferWriter<T, N, R, S>) -> Self {
+        Self::new(writer)
+    }
+}
+
+impl<T, const N: usize, R, S> Producer<T> for SharedWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    #[inline]
+    fn push(&mut self, t: T) -> Option<T> {
+        SharedWriter::push(self, t)
+    }
+
+    #[inline]
+    fn slots(&self) -> usize {
+        Producer::slots(&*self.lock())
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
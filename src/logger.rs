@@ -0,0 +1,154 @@
+//! `log` backend writing the records through a byte ring buffer, available
+//! with the `log` feature.
+//!
+//! [`RingLogger`] formats the records and pushes them into the ring buffer
+//! without ever blocking on I/O, [`LogDrain`] writes them out, typically from
+//! a background thread:
+//!
+//! ```
+//! use log::LevelFilter;
+//! use std::time::Duration;
+//!
+//! let (logger, drain) = ringbuffer_spsc::logger::logger::<4096>(LevelFilter::Info);
+//! log::set_logger(Box::leak(Box::new(logger))).unwrap();
+//! log::set_max_level(LevelFilter::Info);
+//! let _t = drain.spawn(std::io::stderr(), Duration::from_millis(10));
+//!
+//! log::info!("hello");
+//! ```
+//!
+//! Records are dropped, and counted by [`RingLogger::dropped`], when the ring
+//! buffer is full. The threads logging concurrently are serialized by a lock
+//! held while pushing the formatted record only.
+use crate::{RingBuffer, RingBufferReader, RingBufferWriter};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+// Records are prefixed by their length
+const HEADER: usize = size_of::<u32>();
+
+/// Create a logger enabled up to `level` and the drain of its records,
+/// buffering up to `N` bytes.
+pub fn logger<const N: usize>(level: LevelFilter) -> (RingLogger<N>, LogDrain<N>) {
+    let (tx, rx) = RingBuffer::init();
+    let logger = RingLogger {
+        tx: Mutex::new(tx),
+        level,
+        dropped: AtomicUsize::new(0),
+    };
+    (logger, LogDrain { rx })
+}
+
+/// The [`Log`] implementation pushing the records into the ring buffer.
+pub struct RingLogger<const N: usize> {
+    tx: Mutex<RingBufferWriter<u8, N>>,
+    level: LevelFilter,
+    dropped: AtomicUsize,
+}
+
+impl<const N: usize> RingLogger<N> {
+    /// Return the number of records dropped because the ring buffer was full
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<const N: usize> Log for RingLogger<N> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let mut line = vec![0; HEADER];
+        let _ = writeln!(
+            line,
+            "{} {}: {}",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        let len = u32::try_from(line.len() - HEADER).unwrap_or(u32::MAX);
+        line[..HEADER].copy_from_slice(&len.to_le_bytes());
+        // The whole record is made available at once, the drain never sees part of it
+        let pushed = self
+            .tx
+            .lock()
+            .is_ok_and(|mut tx| tx.try_push_n(line.iter().copied()).is_ok());
+        if !pushed {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// The reading half of a [`logger`], writing the records out.
+pub struct LogDrain<const N: usize> {
+    rx: RingBufferReader<u8, N>,
+}
+
+impl<const N: usize> LogDrain<N> {
+    /// Write all the records currently in the ring buffer to `out`, returning how many.
+    ///
+    /// A record failing to be written is left in the ring buffer.
+    pub fn drain<W: Write>(&mut self, out: &mut W) -> io::Result<usize> {
+        let mut n = 0;
+        loop {
+            let (first, second) = self.rx.peek_slice();
+            let mut header = [0; HEADER];
+            let mut bytes = first.iter().chain(second);
+            for (h, b) in header.iter_mut().zip(bytes.by_ref()) {
+                *h = *b;
+            }
+            if first.len() + second.len() < HEADER {
+                return Ok(n);
+            }
+            let len = HEADER + u32::from_le_bytes(header) as usize;
+            // The record has been published together with its header
+            let chunk = self.rx.read_chunk(len).expect("truncated log record");
+            let (first, second) = chunk.as_slices();
+            match first.get(HEADER..) {
+                Some(first) => {
+                    out.write_all(first)?;
+                    out.write_all(second)?;
+                }
+                None => out.write_all(&second[HEADER - first.len()..])?,
+            }
+            chunk.commit_all();
+            n += 1;
+        }
+    }
+
+    /// Spawn a thread writing the records to `out`, checking for new ones every `period`.
+    ///
+    /// The thread returns once the logger is dropped and its last records written,
+    /// or on the first I/O error.
+    pub fn spawn<W>(mut self, mut out: W, period: Duration) -> JoinHandle<io::Result<()>>
+    where
+        W: Write + Send + 'static,
+    {
+        std::thread::spawn(move || loop {
+            // Check the logger before draining: the records it pushed before
+            // being dropped are then guaranteed to be visible.
+            let dropped = self.rx.is_writer_dropped();
+            if self.drain(&mut out)? > 0 {
+                out.flush()?;
+            }
+            if dropped {
+                return Ok(());
+            }
+            std::thread::sleep(period);
+        })
+    }
+}
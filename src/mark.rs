@@ -0,0 +1,101 @@
+//! Speculative reads, rolled back unless committed.
+use crate::{
+    index,
+    storage::{Inline, Storage},
+    DefaultRef, Index, RingBuffer, RingBufferReader,
+};
+use core::ops::Deref;
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Mark the current read position to read elements speculatively.
+    ///
+    /// The elements read through the returned [`ReadMark`] stay in the ring
+    /// buffer until [`ReadMark::commit`], so a decoder can give up on an
+    /// incomplete record and read it again from the start once more
+    /// elements are available:
+    ///
+    /// ```
+    /// use ringbuffer_spsc::RingBuffer;
+    ///
+    /// let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    /// tx.push_slice(&[3, b'a', b'b']);
+    ///
+    /// let mut mark = rx.mark();
+    /// let len = *mark.pull().unwrap();
+    /// assert_eq!(mark.pull(), Some(&b'a'));
+    /// assert_eq!(mark.pull(), Some(&b'b'));
+    /// assert_eq!(mark.pull(), None);
+    /// // Incomplete record, nothing is consumed
+    /// drop(mark);
+    ///
+    /// tx.push(b'c');
+    /// let mut mark = rx.mark();
+    /// mark.pull();
+    /// assert!((0..len).all(|_| mark.pull().is_some()));
+    /// mark.commit();
+    /// assert!(rx.is_empty());
+    /// ```
+    pub fn mark(&mut self) -> ReadMark<'_, T, N, R, S> {
+        let pos = self.local_idx_r;
+        ReadMark { reader: self, pos }
+    }
+}
+
+/// Speculative read position obtained with [`RingBufferReader::mark`].
+///
+/// Dropping it rolls back to the mark: the elements read through it are
+/// left in the ring buffer.
+pub struct ReadMark<'a, T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    reader: &'a mut RingBufferReader<T, N, R, S>,
+    // Speculative read index
+    pos: Index,
+}
+
+impl<T, const N: usize, R, S> ReadMark<'_, T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Read the next element, if any, without removing it from the ring buffer
+    #[inline]
+    pub fn pull(&mut self) -> Option<&T> {
+        if self.pos == self.reader.cached_idx_w {
+            self.reader.refresh_idx_w();
+            if self.pos == self.reader.cached_idx_w {
+                return None;
+            }
+        }
+        // SAFETY: the slots between the read and the write index are initialized
+        // and the writer does not access them until they are released
+        let t = unsafe { &*self.reader.inner.slot_ptr(self.pos) };
+        self.pos = self.pos.wrapping_add(1);
+        Some(t)
+    }
+
+    /// Return the number of elements read since the mark
+    #[inline]
+    pub fn pulled(&self) -> usize {
+        index::len(self.reader.local_idx_r, self.pos)
+    }
+
+    /// Go back to the mark, to read the same elements again
+    #[inline]
+    pub fn rollback(&mut self) {
+        self.pos = self.reader.local_idx_r;
+    }
+
+    /// Remove the elements read since the mark from the ring buffer.
+    pub fn commit(self) {
+        let n = self.pulled();
+        // The elements have been read, so they are available
+        self.reader.read_chunk(n).unwrap().commit_all();
+    }
+}
@@ -0,0 +1,240 @@
+//! Blocking operations backed by user-supplied notifiers.
+//!
+//! [`NotifyWriter`] and [`NotifyReader`] wrap the halves of a ring buffer
+//! together with two [`Notifier`]s: one signalled when data is pushed, which
+//! a blocked reader waits on, and one signalled when slots are released,
+//! which a blocked writer waits on. A notifier can be backed by a FreeRTOS or
+//! Zephyr semaphore, or by bare-metal event flags, so that waiting tasks are
+//! suspended instead of spinning. With the `std` feature, [`Event`] is one:
+//!
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use ringbuffer_spsc::{
+//!     notify::{Event, NotifyReader, NotifyWriter},
+//!     RingBuffer,
+//! };
+//! use std::sync::Arc;
+//!
+//! let (data, space) = (Arc::new(Event::new()), Arc::new(Event::new()));
+//! let (tx, rx) = RingBuffer::<u32, 4>::init();
+//! let mut tx = NotifyWriter::new(tx, data.clone(), space.clone());
+//! let mut rx = NotifyReader::new(rx, data, space);
+//!
+//! let t = std::thread::spawn(move || {
+//!     for i in 0..100 {
+//!         tx.push_blocking(i).unwrap();
+//!     }
+//! });
+//! assert_eq!(core::iter::from_fn(|| rx.pull_blocking()).sum::<u32>(), 4950);
+//! t.join().unwrap();
+//! # }
+//! ```
+//!
+//! Each side checks the ring buffer again after every wakeup, so notifiers
+//! only have to remember a signal raised before the wait, like a binary
+//! semaphore does. Dropping a handle signals the other side one last time.
+use crate::{
+    storage::{Inline, Storage},
+    DefaultRef, RingBuffer, RingBufferReader, RingBufferWriter,
+};
+#[cfg(feature = "alloc")]
+use alloc::sync::Arc;
+use core::{mem::ManuallyDrop, ops::Deref};
+
+/// A wakeup mechanism shared by a waiting side and a signalling side.
+pub trait Notifier {
+    /// Wake the waiting side, or let its next wait return right away.
+    fn signal(&self);
+
+    /// Block until signalled. Returning spuriously is allowed.
+    fn wait(&self);
+}
+
+impl<S: Notifier + ?Sized> Notifier for &S {
+    #[inline]
+    fn signal(&self) {
+        (**self).signal()
+    }
+
+    #[inline]
+    fn wait(&self) {
+        (**self).wait()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: Notifier + ?Sized> Notifier for Arc<S> {
+    #[inline]
+    fn signal(&self) {
+        (**self).signal()
+    }
+
+    #[inline]
+    fn wait(&self) {
+        (**self).wait()
+    }
+}
+
+/// A [`RingBufferWriter`] signalling the reader on push and blocking on a notifier when full.
+pub struct NotifyWriter<T, const N: usize, S, R = DefaultRef<T, N>, M = Inline<T, N>>
+where
+    S: Notifier,
+    R: Deref<Target = RingBuffer<T, N, M>>,
+    M: Storage<T>,
+{
+    tx: ManuallyDrop<RingBufferWriter<T, N, R, M>>,
+    data: S,
+    space: S,
+}
+
+impl<T, const N: usize, S, R, M> NotifyWriter<T, N, S, R, M>
+where
+    S: Notifier,
+    R: Deref<Target = RingBuffer<T, N, M>>,
+    M: Storage<T>,
+{
+    /// Wrap `tx`, signalling `data` on push and waiting on `space` when full.
+    pub fn new(tx: RingBufferWriter<T, N, R, M>, data: S, space: S) -> Self {
+        Self {
+            tx: ManuallyDrop::new(tx),
+            data,
+            space,
+        }
+    }
+
+    /// Push an element, handing it back if the ring buffer is full.
+    #[inline]
+    pub fn push(&mut self, t: T) -> Option<T> {
+        let res = self.tx.push(t);
+        if res.is_none() {
+            self.data.signal();
+        }
+        res
+    }
+
+    /// Push an element, waiting on the space notifier while the ring buffer is full.
+    ///
+    /// The element is handed back if the reader has been dropped.
+    pub fn push_blocking(&mut self, mut t: T) -> Result<(), T> {
+        loop {
+            match self.push(t) {
+                None => return Ok(()),
+                Some(v) => t = v,
+            }
+            if self.tx.is_reader_dropped() {
+                return Err(t);
+            }
+            self.space.wait();
+        }
+    }
+}
+
+impl<T, const N: usize, S, R, M> Drop for NotifyWriter<T, N, S, R, M>
+where
+    S: Notifier,
+    R: Deref<Target = RingBuffer<T, N, M>>,
+    M: Storage<T>,
+{
+    fn drop(&mut self) {
+        // SAFETY: the writer is not used afterwards
+        unsafe { ManuallyDrop::drop(&mut self.tx) };
+        // Let a blocked reader notice that the writer is gone
+        self.data.signal();
+    }
+}
+
+/// A [`RingBufferReader`] signalling the writer on pull and blocking on a notifier when empty.
+pub struct NotifyReader<T, const N: usize, S, R = DefaultRef<T, N>, M = Inline<T, N>>
+where
+    S: Notifier,
+    R: Deref<Target = RingBuffer<T, N, M>>,
+    M: Storage<T>,
+{
+    rx: ManuallyDrop<RingBufferReader<T, N, R, M>>,
+    data: S,
+    space: S,
+}
+
+impl<T, const N: usize, S, R, M> NotifyReader<T, N, S, R, M>
+where
+    S: Notifier,
+    R: Deref<Target = RingBuffer<T, N, M>>,
+    M: Storage<T>,
+{
+    /// Wrap `rx`, waiting on `data` when empty and signalling `space` on pull.
+    pub fn new(rx: RingBufferReader<T, N, R, M>, data: S, space: S) -> Self {
+        Self {
+            rx: ManuallyDrop::new(rx),
+            data,
+            space,
+        }
+    }
+
+    /// Pull an element, returning `None` if the ring buffer is empty.
+    #[inline]
+    pub fn pull(&mut self) -> Option<T> {
+        let t = self.rx.pull()?;
+        self.space.signal();
+        Some(t)
+    }
+
+    /// Pull an element, waiting on the data notifier while the ring buffer is empty.
+    ///
+    /// Returns `None` once the writer has been dropped and the ring buffer is drained.
+    pub fn pull_blocking(&mut self) -> Option<T> {
+        loop {
+            if let Some(t) = self.pull() {
+                return Some(t);
+            }
+            // The writer might have pushed a last element before being dropped
+            if self.rx.is_writer_dropped() {
+                return self.pull();
+            }
+            self.data.wait();
+        }
+    }
+}
+
+impl<T, const N: usize, S, R, M> Drop for NotifyReader<T, N, S, R, M>
+where
+    S: Notifier,
+    R: Deref<Target = RingBuffer<T, N, M>>,
+    M: Storage<T>,
+{
+    fn drop(&mut self) {
+        // SAFETY: the reader is not used afterwards
+        unsafe { ManuallyDrop::drop(&mut self.rx) };
+        // Let a blocked writer notice that the reader is gone
+        self.space.signal();
+    }
+}
+
+/// A [`Notifier`] latching a single signal, available with the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct Event {
+    signalled: std::sync::Mutex<bool>,
+    cond: std::sync::Condvar,
+}
+
+#[cfg(feature = "std")]
+impl Event {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Notifier for Event {
+    fn signal(&self) {
+        let mut signalled = self.signalled.lock().unwrap();
+        *signalled = true;
+        self.cond.notify_one();
+    }
+
+    fn wait(&self) {
+        let signalled = self.signalled.lock().unwrap();
+        let mut signalled = self.cond.wait_while(signalled, |s| !*s).unwrap();
+        *signalled = false;
+    }
+}
@@ -0,0 +1,50 @@
+//! Owner thread checks, available with the `owner-check` feature.
+//!
+//! The single-producer single-consumer protocol relies on each handle being
+//! used by one thread at a time. The handles are `Send`, so they can move to
+//! another thread before their first push or pull, but any use from another
+//! thread afterwards panics:
+//!
+//! ```should_panic
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, _rx) = RingBuffer::<u8, 4>::init();
+//! tx.push(1);
+//! std::thread::scope(|s| {
+//!     s.spawn(|| tx.push(2)).join().unwrap();
+//! });
+//! ```
+//!
+//! Handles rebuilt with `from_raw_parts` record their owner again, as does
+//! a writer every time it is locked through a `SharedWriter`.
+use std::{
+    sync::OnceLock,
+    thread::{self, ThreadId},
+};
+
+pub(crate) struct Owner(OnceLock<ThreadId>);
+
+impl Owner {
+    pub(crate) const fn new() -> Self {
+        Self(OnceLock::new())
+    }
+
+    /// Record the current thread as the owner on the first call, and check
+    /// that it is still the current thread on the next ones.
+    #[inline]
+    #[track_caller]
+    pub(crate) fn check(&self) {
+        let current = thread::current().id();
+        let owner = *self.0.get_or_init(|| current);
+        assert!(
+            owner == current,
+            "ring buffer handle used from {current:?}, but owned by {owner:?}"
+        );
+    }
+
+    /// Forget the owner, which the next check records again.
+    #[inline]
+    pub(crate) fn reset(&mut self) {
+        self.0.take();
+    }
+}
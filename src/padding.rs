@@ -0,0 +1,64 @@
+//! Padding of values to cache lines of their own.
+//!
+//! The ring buffer keeps its shared indexes in [`CachePadded`]: without it the
+//! read and the write index would share a cache line, which would bounce
+//! between the cores of the writer and the reader on every update. The lines
+//! are 64 bytes long on most targets, while the `cache-line-128` feature pads
+//! to 128 bytes instead, for CPUs prefetching cache lines in pairs like recent
+//! x86_64 ones, or with 128 bytes lines like Apple silicon.
+//!
+//! The same goes for the slots when the reader mutates the elements in place
+//! with [`RingBufferReader::peek_mut`](crate::RingBufferReader::peek_mut)
+//! while the writer fills the next slots. Padding each element opts into a
+//! slot layout without false sharing, at the cost of memory, and grouping
+//! small elements in arrays fills the lines again:
+//!
+//! ```
+//! use ringbuffer_spsc::{CachePadded, RingBuffer};
+//!
+//! let (mut tx, mut rx) = RingBuffer::<CachePadded<[u32; 16]>, 4>::init();
+//! assert!(tx.push(CachePadded::new([0; 16])).is_none());
+//! rx.peek_mut().unwrap()[0] = 1;
+//! assert_eq!(rx.pull().unwrap()[0], 1);
+//! ```
+use core::ops::{Deref, DerefMut};
+
+/// A value aligned to, and thus padded to a multiple of, a cache line.
+#[cfg_attr(not(feature = "cache-line-128"), repr(align(64)))]
+#[cfg_attr(feature = "cache-line-128", repr(align(128)))]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CachePadded<T>(T);
+
+impl<T> CachePadded<T> {
+    /// Pad `t` to a cache line.
+    pub const fn new(t: T) -> Self {
+        Self(t)
+    }
+
+    /// Return the padded value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for CachePadded<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
@@ -0,0 +1,84 @@
+//! Bulk transfers of plain-old-data elements as bytes.
+//!
+//! Elements implementing [`bytemuck::Pod`] have no padding and are valid for
+//! any bit pattern, so the slot storage can be filled from and copied to raw
+//! bytes, e.g. to move sensor packets or network headers without marshaling
+//! them field by field:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u16, 4>::init();
+//! assert_eq!(tx.push_bytes(&[1, 0, 2, 0, 3]), 4);
+//! assert_eq!(rx.pull(), Some(1));
+//!
+//! let mut dst = [0; 8];
+//! assert_eq!(rx.pull_bytes(&mut dst), 2);
+//! assert_eq!(dst[..2], 2u16.to_ne_bytes());
+//! ```
+//!
+//! Only whole elements are transferred, the bytes being in the native layout
+//! of `T`. The byte slices need no particular alignment.
+use crate::{storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use bytemuck::Pod;
+use core::{mem::MaybeUninit, ops::Deref};
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    T: Pod,
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Push as many whole elements of `src` as fit, returning how many bytes have been pushed.
+    pub fn push_bytes(&mut self, src: &[u8]) -> usize {
+        let size = size_of::<T>();
+        if size == 0 {
+            return 0;
+        }
+        let mut chunk = self.write_free_chunk(src.len() / size);
+        let n = chunk.len() * size;
+        let (first, second) = chunk.as_mut_slices();
+        let (head, tail) = src[..n].split_at(first.len() * size);
+        copy_to_slots(head, first);
+        copy_to_slots(tail, second);
+        // SAFETY: every slot of the chunk has been filled with the bytes of a `Pod`
+        unsafe { chunk.commit_all() };
+        n
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    T: Pod,
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Pull as many whole elements as fit into `dst`, returning how many bytes have been pulled.
+    pub fn pull_bytes(&mut self, dst: &mut [u8]) -> usize {
+        let size = size_of::<T>();
+        if size == 0 {
+            return 0;
+        }
+        let chunk = self.read_ready_chunk(dst.len() / size);
+        let (first, second) = chunk.as_slices();
+        let (first, second) = (
+            bytemuck::cast_slice::<T, u8>(first),
+            bytemuck::cast_slice::<T, u8>(second),
+        );
+        let n = first.len() + second.len();
+        dst[..first.len()].copy_from_slice(first);
+        dst[first.len()..n].copy_from_slice(second);
+        chunk.commit_all();
+        n
+    }
+}
+
+/// Copy `src`, holding exactly `dst.len()` elements, into the slots of `dst`.
+fn copy_to_slots<T: Pod>(src: &[u8], dst: &mut [MaybeUninit<T>]) {
+    debug_assert_eq!(src.len(), dst.len() * size_of::<T>());
+    // SAFETY: the lengths match, the slots are byte-addressable whatever their
+    // alignment, and any bytes make up a valid `T`
+    unsafe {
+        core::ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr().cast::<u8>(), src.len());
+    }
+}
@@ -0,0 +1,352 @@
+//! Raw access to the slot storage, e.g. to hand it to a DMA engine.
+//!
+//! A transfer into the ring buffer goes as follows:
+//! 1. [`RingBufferWriter::writable_ptr`] returns the start and the length of
+//!    the contiguous free region following the write index;
+//! 2. the device writes up to that many elements starting at the pointer;
+//! 3. once the transfer is complete, [`RingBufferWriter::publish`] makes the
+//!    written elements available to the reader.
+//!
+//! The other way around, [`RingBufferReader::readable_ptr`] returns the
+//! contiguous region of elements ready to be read and
+//! [`RingBufferReader::release`] hands the slots back to the writer.
+//!
+//! On weakly ordered SoCs, the barriers ordering the transfers of the device
+//! before the index publications go in the hooks set with
+//! [`RingBufferWriter::set_pre_publish_hook`] and
+//! [`RingBufferReader::set_post_consume_hook`].
+//!
+//! The region stays valid as long as the handle is not used otherwise: the
+//! other side never touches it until it is published or released. A region
+//! ending at the end of the buffer is followed by the one at its start,
+//! which is returned once the first one has been completed.
+//!
+//! The handles themselves can be turned into raw parts with `into_raw_parts`,
+//! e.g. to be threaded through the user data of a C callback, and rebuilt
+//! with `from_raw_parts` on the other side, keeping their configuration:
+//!
+//! ```
+//! use ringbuffer_spsc::{RingBuffer, RingBufferWriter};
+//!
+//! let (tx, mut rx) = RingBuffer::<u8, 4>::init();
+//! let (ptr, metadata) = tx.into_raw_parts();
+//! let mut tx = unsafe { RingBufferWriter::from_raw_parts(ptr, metadata) };
+//! assert!(tx.push(1).is_none());
+//! assert_eq!(rx.pull(), Some(1));
+//! ```
+//!
+//! Finally, [`RingBuffer::slot_ptr`] and the index accessors of the shared state
+//! allow building custom protocols, e.g. multi-element records, directly on
+//! top of the storage of a ring buffer that is not split.
+use crate::{index, storage::Storage, Index, RingBuffer, RingBufferReader, RingBufferWriter};
+#[cfg(feature = "alloc")]
+use crate::{CacheHooks, SharedRingBuffer};
+#[cfg(feature = "alloc")]
+use core::{mem::ManuallyDrop, ptr};
+use core::{ops::Deref, sync::atomic::Ordering};
+
+impl<T, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Load the write index, with `Acquire` ordering.
+    ///
+    /// The elements of the slots before it are visible once it is loaded.
+    #[inline]
+    pub fn load_write_index(&self) -> Index {
+        self.idx_w.load(Ordering::Acquire)
+    }
+
+    /// Load the read index, with `Acquire` ordering.
+    #[inline]
+    pub fn load_read_index(&self) -> Index {
+        self.idx_r.load(Ordering::Acquire)
+    }
+
+    /// Store the write index, with `Release` ordering.
+    ///
+    /// No waker is woken: notifying the other side is up to the caller.
+    ///
+    /// # Safety
+    /// The ring buffer must not be split, and the caller must be the only one
+    /// storing the write index. The slots between the read index and `idx`
+    /// must be initialized and `idx` must not be more than `N` past the read
+    /// index: they are dropped with the ring buffer otherwise.
+    #[inline]
+    pub unsafe fn store_write_index(&self, idx: Index) {
+        self.idx_w.store(idx, Ordering::Release);
+    }
+
+    /// Store the read index, with `Release` ordering.
+    ///
+    /// No waker is woken: notifying the other side is up to the caller.
+    ///
+    /// # Safety
+    /// The ring buffer must not be split, and the caller must be the only one
+    /// storing the read index. It must not move past the write index, and the
+    /// elements of the slots it moves past must have been moved out or dropped.
+    #[inline]
+    pub unsafe fn store_read_index(&self, idx: Index) {
+        self.idx_r.store(idx, Ordering::Release);
+    }
+
+    /// Return a pointer to the slot of `idx`, which wraps around the capacity.
+    ///
+    /// Dereferencing the pointer is subject to the protocol built on the
+    /// indexes: a slot may only be written by the side storing the write index
+    /// while it is free, and read by the side storing the read index while it
+    /// holds an element.
+    #[inline]
+    pub fn slot_ptr(&self, idx: Index) -> *mut T {
+        // SAFETY: the mask keeps the offset in bounds
+        unsafe { self.slots().add(index::slot::<N>(idx)).cast() }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return a pointer to the free slots following the write index and how many
+    /// of them are contiguous.
+    ///
+    /// # Safety
+    /// Until completed with [`Self::publish`], the slots must only be accessed
+    /// through the returned pointer and the writer must not be used otherwise.
+    pub unsafe fn writable_ptr(&mut self) -> (*mut T, usize) {
+        self.refresh_idx_r();
+        let free = N - index::len(self.cached_idx_r, self.local_idx_w);
+        let len = free.min(N - index::slot::<N>(self.local_idx_w));
+        if let Some(clean) = self.cache.clean {
+            self.inner.maintain(clean, self.local_idx_w, len);
+        }
+        let slots = self.inner.get_slice_mut(self.local_idx_w, len);
+        (slots.as_mut_ptr().cast(), len)
+    }
+
+    /// Return the current read and write indexes.
+    ///
+    /// The indexes grow indefinitely and wrap around at their maximum value, so that
+    /// the throughput and the backlog can be computed from two snapshots with
+    /// wrapping subtractions. The read index may already be stale.
+    ///
+    /// The ring buffer itself only relies on differences smaller than `N`,
+    /// so the wrap around is harmless whatever the width of [`Index`]. On 32-bit
+    /// targets it happens every 2^32 elements though, so the snapshots have
+    /// to be taken more often than that for their differences to be exact,
+    /// unless the `u64-indexes` feature makes the indexes 64-bit.
+    pub fn raw_indices(&self) -> (Index, Index) {
+        (self.inner.idx_r.load(Ordering::Relaxed), self.local_idx_w)
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return a pointer to the elements following the read index and how many
+    /// of them are contiguous.
+    ///
+    /// # Safety
+    /// Until completed with [`Self::release`], the elements must only be accessed
+    /// through the returned pointer and the reader must not be used otherwise.
+    pub unsafe fn readable_ptr(&mut self) -> (*const T, usize) {
+        self.refresh_idx_w();
+        let ready = index::len(self.local_idx_r, self.cached_idx_w);
+        let len = ready.min(N - index::slot::<N>(self.local_idx_r));
+        if let Some(clean) = self.cache.clean {
+            self.inner.maintain(clean, self.local_idx_r, len);
+        }
+        let slots = self.inner.get_slice(self.local_idx_r, len);
+        (slots.as_ptr().cast(), len)
+    }
+
+    /// Return the current read and write indexes.
+    ///
+    /// See [`RingBufferWriter::raw_indices`], the write index may already be stale.
+    pub fn raw_indices(&self) -> (Index, Index) {
+        (self.local_idx_r, self.inner.idx_w.load(Ordering::Relaxed))
+    }
+}
+
+/// Write index and configuration of a writer turned into raw parts.
+///
+/// See [`RingBufferWriter::into_raw_parts`].
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct WriterMetadata {
+    idx_w: Index,
+    limit: usize,
+    watermark: Option<Index>,
+    overrun: Option<fn()>,
+    pre_publish: Option<fn()>,
+    cache: CacheHooks,
+    nearly_full: usize,
+    #[cfg(feature = "async")]
+    spins: u32,
+    #[cfg(feature = "futures-sink")]
+    sink_vacancy: usize,
+    #[cfg(feature = "stats")]
+    refreshes: u64,
+}
+
+#[cfg(feature = "alloc")]
+impl WriterMetadata {
+    /// Return the write index of the writer
+    pub fn index(&self) -> Index {
+        self.idx_w
+    }
+}
+
+/// Read index and configuration of a reader turned into raw parts.
+///
+/// See [`RingBufferReader::into_raw_parts`].
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct ReaderMetadata {
+    idx_r: Index,
+    underrun: Option<fn()>,
+    post_consume: Option<fn()>,
+    cache: CacheHooks,
+    nearly_empty: usize,
+    #[cfg(feature = "async")]
+    spins: u32,
+    #[cfg(feature = "stats")]
+    refreshes: u64,
+}
+
+#[cfg(feature = "alloc")]
+impl ReaderMetadata {
+    /// Return the read index of the reader
+    pub fn index(&self) -> Index {
+        self.idx_r
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize, S> RingBufferWriter<T, N, SharedRingBuffer<T, N, S>, S>
+where
+    S: Storage<T>,
+{
+    /// Decompose the writer into a pointer to the shared ring buffer and its metadata.
+    ///
+    /// The writer is still alive from the point of view of the reader: it has to be
+    /// rebuilt with [`Self::from_raw_parts`] to be dropped, otherwise the ring buffer leaks.
+    /// The metadata carries the whole configuration of the writer (soft limit, watermark,
+    /// callbacks and hooks, hint threshold, async spins and sink vacancy), and the
+    /// element kept back by [`Self::push_or_merge`] is published.
+    pub fn into_raw_parts(mut self) -> (*const RingBuffer<T, N, S>, WriterMetadata) {
+        self.publish_pending();
+        let this = ManuallyDrop::new(self);
+        // SAFETY: the handle is not dropped, so its reference is moved out exactly once
+        let inner = unsafe { ptr::read(&this.inner) };
+        let metadata = WriterMetadata {
+            idx_w: this.local_idx_w,
+            limit: this.limit,
+            watermark: this.watermark,
+            overrun: this.overrun,
+            pre_publish: this.pre_publish,
+            cache: this.cache,
+            nearly_full: this.nearly_full,
+            #[cfg(feature = "async")]
+            spins: this.spins,
+            #[cfg(feature = "futures-sink")]
+            sink_vacancy: this.sink_vacancy,
+            #[cfg(feature = "stats")]
+            refreshes: this.refreshes,
+        };
+        (SharedRingBuffer::into_raw(inner), metadata)
+    }
+
+    /// Rebuild a writer from the parts returned by [`Self::into_raw_parts`].
+    ///
+    /// # Safety
+    /// The parts must come from a single call to [`Self::into_raw_parts`]
+    /// with the same `T`, `N` and storage.
+    pub unsafe fn from_raw_parts(
+        ptr: *const RingBuffer<T, N, S>,
+        metadata: WriterMetadata,
+    ) -> Self {
+        let inner = SharedRingBuffer::from_raw(ptr);
+        let cached_idx_r = inner.idx_r.load(Ordering::Acquire);
+        let mut tx = Self::new_raw(inner, metadata.idx_w, cached_idx_r);
+        tx.limit = metadata.limit;
+        tx.watermark = metadata.watermark;
+        tx.overrun = metadata.overrun;
+        tx.pre_publish = metadata.pre_publish;
+        tx.cache = metadata.cache;
+        tx.nearly_full = metadata.nearly_full;
+        #[cfg(feature = "async")]
+        {
+            tx.spins = metadata.spins;
+        }
+        #[cfg(feature = "futures-sink")]
+        {
+            tx.sink_vacancy = metadata.sink_vacancy;
+        }
+        #[cfg(feature = "stats")]
+        {
+            tx.refreshes = metadata.refreshes;
+        }
+        tx
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize, S> RingBufferReader<T, N, SharedRingBuffer<T, N, S>, S>
+where
+    S: Storage<T>,
+{
+    /// Decompose the reader into a pointer to the shared ring buffer and its metadata.
+    ///
+    /// The reader is still alive from the point of view of the writer: it has to be
+    /// rebuilt with [`Self::from_raw_parts`] to be dropped, otherwise the ring buffer leaks.
+    /// The metadata carries the whole configuration of the reader (callbacks and hooks,
+    /// hint threshold and async spins).
+    pub fn into_raw_parts(self) -> (*const RingBuffer<T, N, S>, ReaderMetadata) {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: the handle is not dropped, so its reference is moved out exactly once
+        let inner = unsafe { ptr::read(&this.inner) };
+        let metadata = ReaderMetadata {
+            idx_r: this.local_idx_r,
+            underrun: this.underrun,
+            post_consume: this.post_consume,
+            cache: this.cache,
+            nearly_empty: this.nearly_empty,
+            #[cfg(feature = "async")]
+            spins: this.spins,
+            #[cfg(feature = "stats")]
+            refreshes: this.refreshes,
+        };
+        (SharedRingBuffer::into_raw(inner), metadata)
+    }
+
+    /// Rebuild a reader from the parts returned by [`Self::into_raw_parts`].
+    ///
+    /// # Safety
+    /// The parts must come from a single call to [`Self::into_raw_parts`]
+    /// with the same `T`, `N` and storage.
+    pub unsafe fn from_raw_parts(
+        ptr: *const RingBuffer<T, N, S>,
+        metadata: ReaderMetadata,
+    ) -> Self {
+        let inner = SharedRingBuffer::from_raw(ptr);
+        let cached_idx_w = inner.idx_w.load(Ordering::Acquire);
+        let mut rx = Self::new_raw(inner, metadata.idx_r, cached_idx_w);
+        rx.underrun = metadata.underrun;
+        rx.post_consume = metadata.post_consume;
+        rx.cache = metadata.cache;
+        rx.nearly_empty = metadata.nearly_empty;
+        #[cfg(feature = "async")]
+        {
+            rx.spins = metadata.spins;
+        }
+        #[cfg(feature = "stats")]
+        {
+            rx.refreshes = metadata.refreshes;
+        }
+        rx
+    }
+}
@@ -0,0 +1,276 @@
+//! Interoperability with the `ringbuf` crate, available with the `ringbuf` feature.
+//!
+//! [`Producer`] and [`Consumer`] wrap a writer and a reader and implement the
+//! [`Observer`](::ringbuf::traits::Observer), [`Producer`](::ringbuf::traits::Producer)
+//! and [`Consumer`](::ringbuf::traits::Consumer) traits, so that code written
+//! against those traits can use this ring buffer as backend:
+//!
+//! ```
+//! use ringbuf::traits::{Consumer as _, Producer as _};
+//! use ringbuffer_spsc::{ringbuf::{Consumer, Producer}, RingBuffer};
+//!
+//! let (tx, rx) = RingBuffer::<u8, 4>::init();
+//! let (mut tx, mut rx) = (Producer::new(tx), Consumer::new(rx));
+//! assert_eq!(tx.push_slice(&[1, 2, 3, 4, 5]), 4);
+//! let mut buf = [0; 8];
+//! assert_eq!(rx.pop_slice(&mut buf), 4);
+//! assert_eq!(buf[..4], [1, 2, 3, 4]);
+//! ```
+//!
+//! `ringbuf` indexes run modulo twice the capacity while the ones of this
+//! crate run freely: since the capacity is a power of two the former are
+//! just the lower bits of the latter, so no conversion is needed besides masking.
+use crate::{
+    index,
+    storage::{Inline, Storage},
+    DefaultRef, Index, RingBuffer, RingBufferReader, RingBufferWriter, READER_ALIVE, WRITER_ALIVE,
+};
+use ::ringbuf::traits;
+use core::{mem::MaybeUninit, num::NonZeroUsize, ops::Deref, sync::atomic::Ordering};
+
+/// A [`RingBufferWriter`] implementing the `ringbuf` [`Producer`](traits::Producer) trait.
+pub struct Producer<T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    tx: RingBufferWriter<T, N, R, S>,
+}
+
+impl<T, const N: usize, R, S> Producer<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    pub fn new(mut tx: RingBufferWriter<T, N, R, S>) -> Self {
+        // The trait methods only see the published elements
+        tx.publish_pending();
+        Self { tx }
+    }
+
+    /// Get back the wrapped writer
+    pub fn into_inner(self) -> RingBufferWriter<T, N, R, S> {
+        // The trait methods update the shared indexes only
+        let mut tx = self.tx;
+        tx.local_idx_w = tx.inner.idx_w.load(Ordering::Relaxed);
+        tx.cached_idx_r = tx.inner.idx_r.load(Ordering::Acquire);
+        tx
+    }
+}
+
+impl<T, const N: usize, R, S> From<RingBufferWriter<T, N, R, S>> for Producer<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn from(tx: RingBufferWriter<T, N, R, S>) -> Self {
+        Self::new(tx)
+    }
+}
+
+/// A [`RingBufferReader`] implementing the `ringbuf` [`Consumer`](traits::Consumer) trait.
+pub struct Consumer<T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    rx: RingBufferReader<T, N, R, S>,
+}
+
+impl<T, const N: usize, R, S> Consumer<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    pub fn new(rx: RingBufferReader<T, N, R, S>) -> Self {
+        Self { rx }
+    }
+
+    /// Get back the wrapped reader
+    pub fn into_inner(self) -> RingBufferReader<T, N, R, S> {
+        // The trait methods update the shared indexes only
+        let mut rx = self.rx;
+        rx.local_idx_r = rx.inner.idx_r.load(Ordering::Relaxed);
+        rx.cached_idx_w = rx.inner.idx_w.load(Ordering::Acquire);
+        rx
+    }
+}
+
+impl<T, const N: usize, R, S> From<RingBufferReader<T, N, R, S>> for Consumer<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    fn from(rx: RingBufferReader<T, N, R, S>) -> Self {
+        Self::new(rx)
+    }
+}
+
+#[inline]
+fn to_ringbuf<const N: usize>(idx: Index) -> usize {
+    index::len(0, idx) & (2 * N - 1)
+}
+
+#[inline]
+fn from_ringbuf<const N: usize>(current: Index, value: usize) -> Index {
+    index::add(
+        current,
+        value.wrapping_sub(to_ringbuf::<N>(current)) & (2 * N - 1),
+    )
+}
+
+/// Return the slots from `start` to `end`, as ringbuf indexes, for reading.
+unsafe fn slices<T, const N: usize, S: Storage<T>>(
+    rb: &RingBuffer<T, N, S>,
+    start: usize,
+    end: usize,
+) -> (&[MaybeUninit<T>], &[MaybeUninit<T>]) {
+    let len = end.wrapping_sub(start) & (2 * N - 1);
+    let first = len.min(N - (start & (N - 1)));
+    // Only the slot of the start index matters
+    let start = index::add(0, start);
+    (rb.get_slice(start, first), rb.get_slice(0, len - first))
+}
+
+/// Return the slots from `start` to `end`, as ringbuf indexes, for writing.
+///
+/// `ringbuf` hands out mutable slots through a shared observer: the caller
+/// guarantees that nothing else accesses them meanwhile.
+#[allow(clippy::mut_from_ref)]
+unsafe fn slices_mut<T, const N: usize, S: Storage<T>>(
+    rb: &RingBuffer<T, N, S>,
+    start: usize,
+    end: usize,
+) -> (&mut [MaybeUninit<T>], &mut [MaybeUninit<T>]) {
+    let len = end.wrapping_sub(start) & (2 * N - 1);
+    let first = len.min(N - (start & (N - 1)));
+    let start = index::add(0, start);
+    (
+        rb.get_slice_mut(start, first),
+        rb.get_slice_mut(0, len - first),
+    )
+}
+
+impl<T, const N: usize, R, S> traits::Observer for Producer<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn capacity(&self) -> NonZeroUsize {
+        NonZeroUsize::new(N).unwrap()
+    }
+
+    #[inline]
+    fn read_index(&self) -> usize {
+        to_ringbuf::<N>(self.tx.inner.idx_r.load(Ordering::Acquire))
+    }
+
+    #[inline]
+    fn write_index(&self) -> usize {
+        to_ringbuf::<N>(self.tx.inner.idx_w.load(Ordering::Relaxed))
+    }
+
+    unsafe fn unsafe_slices(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> (&[MaybeUninit<T>], &[MaybeUninit<T>]) {
+        slices(&self.tx.inner, start, end)
+    }
+
+    unsafe fn unsafe_slices_mut(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> (&mut [MaybeUninit<T>], &mut [MaybeUninit<T>]) {
+        slices_mut(&self.tx.inner, start, end)
+    }
+
+    #[inline]
+    fn read_is_held(&self) -> bool {
+        self.tx.inner.flags.load(Ordering::Acquire) & READER_ALIVE != 0
+    }
+
+    #[inline]
+    fn write_is_held(&self) -> bool {
+        true
+    }
+}
+
+impl<T, const N: usize, R, S> traits::Producer for Producer<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    #[inline]
+    unsafe fn set_write_index(&self, value: usize) {
+        let current = self.tx.inner.idx_w.load(Ordering::Relaxed);
+        let idx_w = from_ringbuf::<N>(current, value);
+        self.tx.inner.publish_idx_w(idx_w, self.tx.pre_publish);
+    }
+}
+
+impl<T, const N: usize, R, S> traits::Observer for Consumer<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn capacity(&self) -> NonZeroUsize {
+        NonZeroUsize::new(N).unwrap()
+    }
+
+    #[inline]
+    fn read_index(&self) -> usize {
+        to_ringbuf::<N>(self.rx.inner.idx_r.load(Ordering::Relaxed))
+    }
+
+    #[inline]
+    fn write_index(&self) -> usize {
+        to_ringbuf::<N>(self.rx.inner.idx_w.load(Ordering::Acquire))
+    }
+
+    unsafe fn unsafe_slices(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> (&[MaybeUninit<T>], &[MaybeUninit<T>]) {
+        slices(&self.rx.inner, start, end)
+    }
+
+    unsafe fn unsafe_slices_mut(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> (&mut [MaybeUninit<T>], &mut [MaybeUninit<T>]) {
+        slices_mut(&self.rx.inner, start, end)
+    }
+
+    #[inline]
+    fn read_is_held(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn write_is_held(&self) -> bool {
+        self.rx.inner.flags.load(Ordering::Acquire) & WRITER_ALIVE != 0
+    }
+}
+
+impl<T, const N: usize, R, S> traits::Consumer for Consumer<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    #[inline]
+    unsafe fn set_read_index(&self, value: usize) {
+        let current = self.rx.inner.idx_r.load(Ordering::Relaxed);
+        let idx_r = from_ringbuf::<N>(current, value);
+        self.rx.inner.release_idx_r(idx_r, self.rx.post_consume);
+    }
+}
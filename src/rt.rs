@@ -0,0 +1,101 @@
+//! Realtime safety checks, available with the `rt-assert` feature.
+//!
+//! The pushes and pulls of this crate never allocate, but the code they call
+//! back into might: wakers, overrun and underrun callbacks or the `Drop` of
+//! the elements. With the `rt-assert` feature, the push, pull and chunk
+//! paths are marked as realtime sections, and [`RtAllocator`] aborts the
+//! process on any allocation or deallocation happening inside one of them
+//! in debug builds:
+//!
+//! ```
+//! use ringbuffer_spsc::rt::RtAllocator;
+//! use std::alloc::System;
+//!
+//! #[global_allocator]
+//! static ALLOC: RtAllocator<System> = RtAllocator::new(System);
+//! # fn main() {}
+//! ```
+//!
+//! Own realtime code can be checked the same way with [`section`].
+//! Locks and system calls cannot be intercepted this way and are not detected.
+use core::cell::Cell;
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    io::Write,
+};
+
+std::thread_local! {
+    // Number of nested realtime sections of the current thread
+    static DEPTH: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Marks a realtime section for as long as it is alive.
+pub(crate) struct Section(());
+
+impl Section {
+    #[inline]
+    pub(crate) fn enter() -> Self {
+        DEPTH.with(|d| d.set(d.get() + 1));
+        Section(())
+    }
+}
+
+impl Drop for Section {
+    #[inline]
+    fn drop(&mut self) {
+        DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
+/// Run `f` as a realtime section, in which [`RtAllocator`] forbids allocations.
+pub fn section<F: FnOnce() -> R, R>(f: F) -> R {
+    let _section = Section::enter();
+    f()
+}
+
+/// A [`GlobalAlloc`] wrapper aborting on allocations inside realtime sections
+/// in debug builds, and forwarding everything to the wrapped allocator otherwise.
+pub struct RtAllocator<A>(A);
+
+impl<A> RtAllocator<A> {
+    pub const fn new(alloc: A) -> Self {
+        Self(alloc)
+    }
+}
+
+#[inline]
+fn check() {
+    #[cfg(debug_assertions)]
+    if DEPTH.try_with(|d| d.get() > 0).unwrap_or(false) {
+        // Unwinding out of an allocator is undefined behavior, and writing the
+        // message must not allocate either
+        let _ = std::io::stderr().write_all(b"allocation in a realtime section\n");
+        std::process::abort();
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for RtAllocator<A> {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        check();
+        self.0.alloc(layout)
+    }
+
+    #[inline]
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        check();
+        self.0.alloc_zeroed(layout)
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        check();
+        self.0.dealloc(ptr, layout)
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        check();
+        self.0.realloc(ptr, layout, new_size)
+    }
+}
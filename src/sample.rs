@@ -0,0 +1,112 @@
+//! Sampling of the latest element of a ring buffer of `Copy` elements.
+//!
+//! A monitor, e.g. a dashboard showing the current value of a telemetry
+//! stream, can copy the most recently pushed element without pulling it and
+//! without any effect on the reader:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let rb = RingBuffer::<f32, 8>::empty();
+//! let (mut tx, mut rx) = rb.split();
+//! assert_eq!(rx.sample_latest(), None);
+//! tx.push(1.0);
+//! tx.push(2.0);
+//! assert_eq!(rx.sample_latest(), Some(2.0));
+//! assert_eq!(rx.pull(), Some(1.0));
+//! ```
+//!
+//! Through the reader, the copy never races with the writer: the slot of an
+//! element not pulled yet is only reused once the reader releases it, which
+//! the borrowed reader cannot do meanwhile. `None` is returned once the
+//! reader has caught up with the writer.
+//!
+//! The writer and [`RingBuffer::sample_latest`], which samples the shared state
+//! directly while both handles may be in use, cannot rule out the slot being
+//! written during the copy on their own, so they leave it to the caller.
+use crate::{index, storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::{ops::Deref, ptr, sync::atomic::Ordering};
+
+impl<T: Copy, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Copy the latest element pushed, unless it has already been pulled.
+    ///
+    /// As in a seqlock, the element is copied optimistically and the copy is
+    /// only returned if the read index shows that its slot was not released
+    /// meanwhile, retrying with the newer elements otherwise.
+    ///
+    /// # Safety
+    /// The slot must not be written during the copy, which would be a data
+    /// race even though the torn copy is discarded: while this function runs,
+    /// the reader must not update elements in place, e.g. with
+    /// [`RingBufferReader::peek_mut`] or a read chunk, and the writer must not
+    /// push unless the reader does not pull either.
+    /// [`RingBufferReader::sample_latest`] has no such requirement.
+    pub unsafe fn sample_latest(&self) -> Option<T> {
+        let mut idx_w = self.idx_w.load(Ordering::Acquire);
+        loop {
+            let ready = index::len(self.idx_r.load(Ordering::Acquire), idx_w);
+            if ready == 0 {
+                let latest = self.idx_w.load(Ordering::Acquire);
+                if latest == idx_w {
+                    return None;
+                }
+                idx_w = latest;
+                continue;
+            }
+            if ready <= N {
+                // SAFETY: the slot was initialized when the write index was
+                // loaded, and the caller guarantees that it is not reused
+                let t = unsafe { ptr::read(self.slot_ptr(index::sub(idx_w, 1))) };
+                // The element may have been pulled meanwhile
+                let ready = index::len(self.idx_r.load(Ordering::Acquire), idx_w);
+                if ready > 0 && ready <= N {
+                    return Some(t);
+                }
+            }
+            // The reader went past the element: retry with the newer ones
+            idx_w = self.idx_w.load(Ordering::Acquire);
+        }
+    }
+}
+
+impl<T: Copy, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Copy the latest element pushed, unless it has already been pulled.
+    ///
+    /// # Safety
+    /// The reader must not update elements in place, e.g. with
+    /// [`RingBufferReader::peek_mut`] or a read chunk, while this function
+    /// runs: the element could be written during the copy.
+    pub unsafe fn sample_latest(&self) -> Option<T> {
+        if self.inner.idx_r.load(Ordering::Acquire) == self.local_idx_w {
+            return None;
+        }
+        // SAFETY: the slot was initialized by the writer, which is borrowed so
+        // it cannot reuse it, and the caller guarantees that the reader does
+        // not write it
+        Some(unsafe { ptr::read(self.inner.slot_ptr(index::sub(self.local_idx_w, 1))) })
+    }
+}
+
+impl<T: Copy, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Copy the latest element pushed, unless it has already been pulled
+    pub fn sample_latest(&self) -> Option<T> {
+        let idx_w = self.inner.idx_w.load(Ordering::Acquire);
+        if idx_w == self.local_idx_r {
+            return None;
+        }
+        // SAFETY: the slot is initialized and not released, and the reader is
+        // borrowed so it cannot release it meanwhile
+        Some(unsafe { ptr::read(self.inner.slot_ptr(index::sub(idx_w, 1))) })
+    }
+}
@@ -0,0 +1,127 @@
+//! Ownership of a heap-allocated ring buffer shared by its two halves.
+use crate::{
+    storage::{Inline, Storage},
+    weak, RingBuffer, HANDLE_RELEASED,
+};
+use alloc::boxed::Box;
+use core::{marker::PhantomData, ops::Deref, ptr::NonNull, sync::atomic::Ordering};
+
+/// Pointer to a heap-allocated [`RingBuffer`] owned by exactly two handles,
+/// the writer and the reader returned by [`RingBuffer::init`].
+///
+/// The indexes, the flags and the slots live in a single allocation without
+/// any reference count: the flags of the ring buffer tell which handles are
+/// alive, and the last one to release its pointer frees the allocation, the
+/// [weak handles](crate::WeakRingBuffer) only keeping a header of their own.
+pub struct SharedRingBuffer<T, const N: usize, S = Inline<T, N>>
+where
+    S: Storage<T>,
+{
+    ptr: NonNull<RingBuffer<T, N, S>>,
+    _marker: PhantomData<RingBuffer<T, N, S>>,
+}
+
+// Each owner is held by a single handle, whose side of the protocol only
+// moves the elements between threads
+unsafe impl<T: Send, const N: usize, S: Storage<T> + Send> Send for SharedRingBuffer<T, N, S> {}
+unsafe impl<T, const N: usize, S: Storage<T>> Sync for SharedRingBuffer<T, N, S> where
+    RingBuffer<T, N, S>: Sync
+{
+}
+
+impl<T, const N: usize, S> SharedRingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Allocate a ring buffer, returning the pointers of its two owners.
+    pub(crate) fn pair(rb: Box<RingBuffer<T, N, S>>) -> (Self, Self) {
+        let ptr = NonNull::from(Box::leak(rb));
+        (Self::from_ptr(ptr), Self::from_ptr(ptr))
+    }
+
+    /// Take ownership of an allocated ring buffer, returning the pointers of its two owners.
+    ///
+    /// # Safety
+    /// The ring buffer must be initialized and not be owned by anything else.
+    #[cfg(all(feature = "guard-pages", unix))]
+    pub(crate) unsafe fn pair_from_ptr(ptr: NonNull<RingBuffer<T, N, S>>) -> (Self, Self) {
+        (Self::from_ptr(ptr), Self::from_ptr(ptr))
+    }
+
+    fn from_ptr(ptr: NonNull<RingBuffer<T, N, S>>) -> Self {
+        Self {
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the pointer to the ring buffer, keeping the provenance of the allocation.
+    pub(crate) fn as_ptr(&self) -> NonNull<RingBuffer<T, N, S>> {
+        self.ptr
+    }
+
+    /// Consume the pointer without releasing it.
+    pub(crate) fn into_raw(self) -> *const RingBuffer<T, N, S> {
+        let ptr = self.ptr.as_ptr();
+        core::mem::forget(self);
+        ptr
+    }
+
+    /// Take back a pointer returned by [`Self::into_raw`].
+    ///
+    /// # Safety
+    /// The pointer must come from [`Self::into_raw`] and be taken back only once.
+    pub(crate) unsafe fn from_raw(ptr: *const RingBuffer<T, N, S>) -> Self {
+        Self::from_ptr(NonNull::new_unchecked(ptr.cast_mut()))
+    }
+}
+
+impl<T, const N: usize, S> Deref for SharedRingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    type Target = RingBuffer<T, N, S>;
+
+    #[inline]
+    fn deref(&self) -> &RingBuffer<T, N, S> {
+        // SAFETY: the allocation is alive until both owners have been dropped
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T, const N: usize, S> Drop for SharedRingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        // The first owner to be dropped marks the ring buffer as released,
+        // the second one frees it. AcqRel makes every access performed by
+        // the first owner visible to the second one before freeing.
+        let prev = self.flags.fetch_or(HANDLE_RELEASED, Ordering::AcqRel);
+        if prev & HANDLE_RELEASED == 0 {
+            return;
+        }
+        // SAFETY: the other owner is gone, and weak handles can only be
+        // created from an owner or another weak handle
+        unsafe { weak::Header::release(self.ptr) };
+    }
+}
+
+impl<T, const N: usize, S> SharedRingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Free a ring buffer allocated by [`Self::pair`] or mapped by [`Self::pair_from_ptr`].
+    ///
+    /// # Safety
+    /// Nothing may access the ring buffer anymore.
+    pub(crate) unsafe fn free(ptr: NonNull<RingBuffer<T, N, S>>) {
+        #[cfg(all(feature = "guard-pages", unix))]
+        if ptr.as_ref().flags.load(Ordering::Relaxed) & crate::GUARD_PAGES != 0 {
+            // SAFETY: the flag is only set on mapped ring buffers
+            crate::guard::unmap(ptr);
+            return;
+        }
+        drop(Box::from_raw(ptr.as_ptr()));
+    }
+}
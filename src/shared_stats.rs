@@ -0,0 +1,140 @@
+//! Statistics kept in the shared state, available with the `shared-stats` feature.
+//!
+//! Unlike the counters of the `stats` feature, they live next to the indexes,
+//! so that either handle, or anyone holding a reference to the [`RingBuffer`],
+//! can take a snapshot of the traffic without an extra channel:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 2>::init();
+//! tx.push(1);
+//! tx.push(2);
+//! assert!(tx.push(3).is_some());
+//! assert_eq!(rx.pull(), Some(1));
+//!
+//! let stats = rx.stats();
+//! assert_eq!((stats.pushed, stats.pulled, stats.occupancy), (2, 1, 1));
+//! assert_eq!((stats.failed_pushes, stats.failed_pulls), (1, 0));
+//! ```
+//!
+//! The element counts come from the indexes themselves. The failures are
+//! counted where the overrun and underrun callbacks are called, each counter
+//! being updated by a single side with relaxed loads and stores, which keeps
+//! the cost on the hot path to the failing calls. All of them wrap around at
+//! their maximum value, the element counts being [`Index`](crate::Index)es.
+use crate::{
+    atomic::AtomicUsize, index, storage::Storage, Index, RingBuffer, RingBufferReader,
+    RingBufferWriter,
+};
+use core::{ops::Deref, sync::atomic::Ordering};
+
+pub(crate) struct Counters {
+    failed_pushes: Counter,
+    failed_pulls: Counter,
+}
+
+impl Counters {
+    pub(crate) const fn new() -> Self {
+        Self {
+            failed_pushes: Counter::new(),
+            failed_pulls: Counter::new(),
+        }
+    }
+
+    /// Count a push failing because the ring buffer is full, from the writer
+    #[inline]
+    pub(crate) fn failed_push(&self) {
+        self.failed_pushes.increment();
+    }
+
+    /// Count a pull failing because the ring buffer is empty, from the reader
+    #[inline]
+    pub(crate) fn failed_pull(&self) {
+        self.failed_pulls.increment();
+    }
+}
+
+struct Counter(AtomicUsize);
+
+impl Counter {
+    const fn new() -> Self {
+        Self(AtomicUsize::new(0))
+    }
+
+    /// Increment the counter, which must only be updated by one side
+    #[inline]
+    fn increment(&self) {
+        let n = self.0.load(Ordering::Relaxed);
+        self.0.store(n.wrapping_add(1), Ordering::Relaxed);
+    }
+
+    fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Snapshot of the statistics of a ring buffer.
+///
+/// The element counts are loaded together: `pushed - pulled` always equals
+/// `occupancy`, with a wrapping subtraction.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct StatsSnapshot {
+    /// Number of elements pushed since the ring buffer was created
+    pub pushed: Index,
+    /// Number of elements pulled since the ring buffer was created
+    pub pulled: Index,
+    /// Number of pushes that failed because the ring buffer was full
+    pub failed_pushes: usize,
+    /// Number of pulls that failed because the ring buffer was empty
+    pub failed_pulls: usize,
+    /// Number of elements in the ring buffer
+    pub occupancy: usize,
+}
+
+impl<T, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Take a snapshot of the statistics of the ring buffer
+    pub fn stats(&self) -> StatsSnapshot {
+        let (pushed, pulled) = loop {
+            // The read index is loaded first, so it never is past the write
+            // index. Retry if the reader went a whole lap meanwhile.
+            let pulled = self.idx_r.load(Ordering::Acquire);
+            let pushed = self.idx_w.load(Ordering::Acquire);
+            if index::len(pulled, pushed) <= N {
+                break (pushed, pulled);
+            }
+        };
+        StatsSnapshot {
+            pushed,
+            pulled,
+            failed_pushes: self.counters.failed_pushes.get(),
+            failed_pulls: self.counters.failed_pulls.get(),
+            occupancy: index::len(pulled, pushed),
+        }
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Take a snapshot of the statistics of the ring buffer
+    pub fn stats(&self) -> StatsSnapshot {
+        self.inner.stats()
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Take a snapshot of the statistics of the ring buffer
+    pub fn stats(&self) -> StatsSnapshot {
+        self.inner.stats()
+    }
+}
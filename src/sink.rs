@@ -0,0 +1,110 @@
+//! `futures::Sink` implementation for writers, available with the `futures-sink` feature.
+//!
+//! By default [`Sink::poll_ready`] is ready as soon as one slot is free. A
+//! producer sending bursts can require more room with
+//! [`RingBufferWriter::set_sink_vacancy`], so that it is woken once per burst
+//! rather than once per element:
+//!
+//! ```
+//! use futures::SinkExt;
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u32, 8>::init();
+//! tx.set_sink_vacancy(4);
+//! futures::executor::block_on(async {
+//!     tx.feed(1).await.unwrap();
+//!     tx.feed(2).await.unwrap();
+//! });
+//! assert_eq!(rx.pull(), Some(1));
+//! ```
+use crate::{storage::Storage, RingBuffer, RingBufferWriter};
+use core::{
+    fmt,
+    ops::Deref,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use futures_sink::Sink;
+
+/// Error returned by the [`Sink`] implementation once the reader has been dropped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Disconnected;
+
+impl fmt::Display for Disconnected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the reader of the ring buffer has been dropped")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Disconnected {}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Set how many free slots [`Sink::poll_ready`] waits for before
+    /// reporting readiness. It is capped by the soft limit when polled.
+    ///
+    /// # Panics
+    /// This function panics if `n` is zero or greater than the capacity.
+    pub fn set_sink_vacancy(&mut self, n: usize) {
+        assert!(
+            n > 0 && n <= N,
+            "sink vacancy must be between 1 and the capacity"
+        );
+        self.sink_vacancy = n;
+    }
+
+    /// Return how many free slots [`Sink::poll_ready`] waits for
+    #[inline]
+    pub fn sink_vacancy(&self) -> usize {
+        self.sink_vacancy
+    }
+}
+
+impl<T, const N: usize, R, S> Sink<T> for RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>> + Unpin,
+    S: Storage<T>,
+{
+    type Error = Disconnected;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.is_reader_dropped() {
+            return Poll::Ready(Err(Disconnected));
+        }
+        let k = this.sink_vacancy.min(this.limit).max(1);
+        match ready!(this.poll_vacancy(cx, k)) {
+            0 => Poll::Ready(Err(Disconnected)),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+
+    /// Push `item`, which requires a successful [`Sink::poll_ready`] beforehand.
+    ///
+    /// # Panics
+    /// This function panics if the ring buffer is full.
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        if this.is_reader_dropped() {
+            return Err(Disconnected);
+        }
+        assert!(
+            this.push(item).is_none(),
+            "start_send called without a free slot"
+        );
+        Ok(())
+    }
+
+    /// Elements are available to the reader as soon as they are sent, so flushing is a no-op.
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
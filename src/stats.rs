@@ -0,0 +1,45 @@
+//! Index refresh counters, available with the `stats` feature.
+//!
+//! Each handle caches the index published by the other one and only reloads
+//! it when the cached value makes the ring buffer look full or empty. Every
+//! reload is a potential cache miss on the line owned by the other core, so
+//! their count tells how well the capacity and the batch sizes fit the load:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+//! for i in 0..4 {
+//!     tx.push(i);
+//! }
+//! // The writer only looked at the read index when the ring buffer got full
+//! assert!(tx.push(4).is_some());
+//! assert_eq!(tx.index_refreshes(), 1);
+//! assert_eq!(rx.pull_each(drop), 4);
+//! ```
+//!
+//! The counters are local to the handles and do not touch the shared state.
+use crate::{storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::ops::Deref;
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return how many times the writer has reloaded the read index
+    pub fn index_refreshes(&self) -> u64 {
+        self.refreshes
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return how many times the reader has reloaded the write index
+    pub fn index_refreshes(&self) -> u64 {
+        self.refreshes
+    }
+}
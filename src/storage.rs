@@ -0,0 +1,251 @@
+//! Memory holding the slots of a ring buffer.
+//!
+//! The indexes, the flags and the protocol built on them are the same whatever
+//! the slots live in: [`RingBuffer`](crate::RingBuffer) only reaches them
+//! through the pointer returned by its [`Storage`]. By default they are an
+//! [`Inline`] array within the ring buffer. They can also live in a
+//! [`Boxed`] slice, e.g. for capacities too large for the stack, in a
+//! [`Static`] slice handed out at startup, e.g. from a linker section or
+//! a memory pool, or, with the `mmap` feature on Unix, in an [`Mmap`] region
+//! of their own:
+//!
+//! ```
+//! use ringbuffer_spsc::{storage::Boxed, RingBuffer};
+//!
+//! let rb = RingBuffer::<u64, 4096, _>::with_storage(Boxed::new(4096));
+//! let (mut tx, mut rx) = rb.split();
+//! assert!(tx.push(42).is_none());
+//! assert_eq!(rx.pull(), Some(42));
+//! ```
+//!
+//! The capacity is still the const parameter `N` of the ring buffer, so that
+//! the slots are selected with a mask: the storage must hold at least `N`
+//! slots, the ones past `N` being left unused.
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::{cell::UnsafeCell, marker::PhantomData, mem::MaybeUninit, ptr::NonNull};
+
+/// Memory holding the slots of a ring buffer.
+///
+/// # Safety
+/// [`Self::as_ptr`] must return a pointer to [`Self::capacity`] slots valid
+/// for reads and writes, which nothing but this pointer accesses while the
+/// storage is alive. Both methods must return the same values for as long as
+/// the storage is not moved, and must be callable from the threads of the
+/// writer and of the reader at the same time.
+pub unsafe trait Storage<T> {
+    /// Return the number of slots
+    fn capacity(&self) -> usize;
+
+    /// Return a pointer to the first slot, with the provenance of all of them.
+    ///
+    /// The writer and the reader access disjoint slots at the same time, so the
+    /// pointer must not be derived from a reference to the whole storage.
+    fn as_ptr(&self) -> *mut MaybeUninit<T>;
+}
+
+/// Slots laid out inline, as an array within the ring buffer.
+pub struct Inline<T, const N: usize>(UnsafeCell<[MaybeUninit<T>; N]>);
+
+impl<T, const N: usize> Inline<T, N> {
+    /// Create uninitialized slots.
+    pub const fn new() -> Self {
+        // SAFETY: an array of `MaybeUninit` does not require initialization
+        Self(UnsafeCell::new(unsafe {
+            MaybeUninit::uninit().assume_init()
+        }))
+    }
+}
+
+impl<T, const N: usize> Default for Inline<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<T, const N: usize> Storage<T> for Inline<T, N> {
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *mut MaybeUninit<T> {
+        self.0.get().cast()
+    }
+}
+
+/// Slots of a heap-allocated slice.
+#[cfg(feature = "alloc")]
+pub struct Boxed<T> {
+    ptr: NonNull<[MaybeUninit<T>]>,
+    _marker: PhantomData<Box<[MaybeUninit<T>]>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Boxed<T> {
+    /// Allocate `len` uninitialized slots.
+    pub fn new(len: usize) -> Self {
+        Box::<[T]>::new_uninit_slice(len).into()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> From<Box<[MaybeUninit<T>]>> for Boxed<T> {
+    fn from(slots: Box<[MaybeUninit<T>]>) -> Self {
+        // The slice is only accessed through the raw pointer from now on
+        Self {
+            ptr: NonNull::from(Box::leak(slots)),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Drop for Boxed<T> {
+    fn drop(&mut self) {
+        // SAFETY: the pointer comes from `Box::leak` and is not used anymore
+        drop(unsafe { Box::from_raw(self.ptr.as_ptr()) });
+    }
+}
+
+// The slots are owned like the ones of a `Box`
+#[cfg(feature = "alloc")]
+unsafe impl<T: Send> Send for Boxed<T> {}
+#[cfg(feature = "alloc")]
+unsafe impl<T: Sync> Sync for Boxed<T> {}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> Storage<T> for Boxed<T> {
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.ptr.len()
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *mut MaybeUninit<T> {
+        self.ptr.as_ptr().cast()
+    }
+}
+
+/// Slots of a slice borrowed for the rest of the program.
+pub struct Static<T: 'static> {
+    ptr: NonNull<[MaybeUninit<T>]>,
+    _marker: PhantomData<&'static mut [MaybeUninit<T>]>,
+}
+
+impl<T> From<&'static mut [MaybeUninit<T>]> for Static<T> {
+    fn from(slots: &'static mut [MaybeUninit<T>]) -> Self {
+        // The slice is only accessed through the raw pointer from now on
+        Self {
+            ptr: NonNull::from(slots),
+            _marker: PhantomData,
+        }
+    }
+}
+
+// The slots are borrowed like through a `&'static mut`
+unsafe impl<T: Send> Send for Static<T> {}
+unsafe impl<T: Sync> Sync for Static<T> {}
+
+unsafe impl<T> Storage<T> for Static<T> {
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.ptr.len()
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *mut MaybeUninit<T> {
+        self.ptr.as_ptr().cast()
+    }
+}
+
+#[cfg(all(feature = "mmap", unix))]
+pub use self::mmap::Mmap;
+
+#[cfg(all(feature = "mmap", unix))]
+mod mmap {
+    use super::Storage;
+    use core::{marker::PhantomData, mem::MaybeUninit, ptr::NonNull};
+    use std::io;
+
+    /// Slots of an anonymous memory mapping of their own.
+    ///
+    /// The pages are only backed by physical memory once touched, so a large
+    /// capacity costs little until the ring buffer fills up, and they are given
+    /// back to the system as soon as the storage is dropped.
+    pub struct Mmap<T> {
+        ptr: NonNull<MaybeUninit<T>>,
+        len: usize,
+        _marker: PhantomData<[MaybeUninit<T>]>,
+    }
+
+    impl<T> Mmap<T> {
+        /// Map `len` uninitialized slots.
+        pub fn new(len: usize) -> io::Result<Self> {
+            let bytes = Self::bytes(len)?;
+            if bytes == 0 {
+                return Ok(Self {
+                    ptr: NonNull::dangling(),
+                    len,
+                    _marker: PhantomData,
+                });
+            }
+            // SAFETY: an anonymous private mapping does not alias any memory
+            let ptr = unsafe {
+                libc::mmap(
+                    core::ptr::null_mut(),
+                    bytes,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                    -1,
+                    0,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+            assert!(
+                ptr.cast::<MaybeUninit<T>>().is_aligned(),
+                "Mmap alignment exceeds the page size"
+            );
+            Ok(Self {
+                // SAFETY: a successful mapping is not null
+                ptr: unsafe { NonNull::new_unchecked(ptr.cast()) },
+                len,
+                _marker: PhantomData,
+            })
+        }
+
+        fn bytes(len: usize) -> io::Result<usize> {
+            len.checked_mul(size_of::<T>())
+                .ok_or_else(|| io::ErrorKind::OutOfMemory.into())
+        }
+    }
+
+    impl<T> Drop for Mmap<T> {
+        fn drop(&mut self) {
+            let bytes = self.len * size_of::<T>();
+            if bytes > 0 {
+                // SAFETY: the mapping comes from `Mmap::new` and is not used anymore
+                unsafe { libc::munmap(self.ptr.as_ptr().cast(), bytes) };
+            }
+        }
+    }
+
+    // The slots are owned like the ones of a `Box`
+    unsafe impl<T: Send> Send for Mmap<T> {}
+    unsafe impl<T: Sync> Sync for Mmap<T> {}
+
+    unsafe impl<T> Storage<T> for Mmap<T> {
+        #[inline]
+        fn capacity(&self) -> usize {
+            self.len
+        }
+
+        #[inline]
+        fn as_ptr(&self) -> *mut MaybeUninit<T> {
+            self.ptr.as_ptr()
+        }
+    }
+}
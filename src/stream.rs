@@ -0,0 +1,144 @@
+//! `futures::Stream` implementation for readers, available with the
+//! `futures-stream` feature.
+//!
+//! The stream yields the elements one by one and ends once the writer has
+//! been dropped and the ring buffer drained. Consumers processing batches can
+//! rather drain everything ready per wakeup, with
+//! [`RingBufferReader::poll_next_many`] or the [`ReadyChunks`] stream:
+//!
+//! ```
+//! use futures::StreamExt;
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, rx) = RingBuffer::<u32, 8>::init();
+//! for i in 0..5 {
+//!     assert!(tx.push(i).is_none());
+//! }
+//! drop(tx);
+//! let chunks: Vec<Vec<u32>> = futures::executor::block_on(rx.ready_chunks(3).collect());
+//! assert_eq!(chunks, [vec![0, 1, 2], vec![3, 4]]);
+//! ```
+use crate::{
+    storage::{Inline, Storage},
+    DefaultRef, RingBuffer, RingBufferReader,
+};
+use alloc::vec::Vec;
+use core::{
+    ops::Deref,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use futures_core::{FusedStream, Stream};
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Poll for pulling up to `limit` elements into `dst`, registering the
+    /// current task for a wakeup if the ring buffer is empty.
+    ///
+    /// All the elements are made available to the writer with a single update
+    /// of the read index. Returns the number of elements pulled, which is only
+    /// `Poll::Ready(0)` once the writer has been dropped and the ring buffer
+    /// is drained.
+    ///
+    /// # Panics
+    /// This function panics if `limit` is zero.
+    pub fn poll_next_many(
+        &mut self,
+        cx: &mut Context<'_>,
+        dst: &mut Vec<T>,
+        limit: usize,
+    ) -> Poll<usize> {
+        assert!(limit > 0, "poll_next_many called with a zero limit");
+        if ready!(self.poll_available(cx)) == 0 {
+            return Poll::Ready(0);
+        }
+        let chunk = self.read_ready_chunk(limit);
+        let n = chunk.len();
+        dst.extend(chunk);
+        Poll::Ready(n)
+    }
+
+    /// Turn the reader into a stream yielding the elements ready per wakeup,
+    /// in vectors of up to `capacity` elements.
+    ///
+    /// # Panics
+    /// This function panics if `capacity` is zero.
+    pub fn ready_chunks(self, capacity: usize) -> ReadyChunks<T, N, R, S> {
+        assert!(capacity > 0, "ready_chunks called with a zero capacity");
+        ReadyChunks {
+            reader: self,
+            capacity,
+        }
+    }
+}
+
+impl<T, const N: usize, R, S> Stream for RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>> + Unpin,
+    S: Storage<T>,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.get_mut().poll_pull(cx)
+    }
+}
+
+impl<T, const N: usize, R, S> FusedStream for RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>> + Unpin,
+    S: Storage<T>,
+{
+    fn is_terminated(&self) -> bool {
+        self.is_writer_dropped() && self.is_empty()
+    }
+}
+
+/// Stream returned by [`RingBufferReader::ready_chunks`].
+///
+/// The reader stays accessible through [`Self::get_mut`].
+#[must_use = "streams do nothing unless polled"]
+pub struct ReadyChunks<T, const N: usize, R = DefaultRef<T, N>, S = Inline<T, N>>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    reader: RingBufferReader<T, N, R, S>,
+    capacity: usize,
+}
+
+impl<T, const N: usize, R, S> ReadyChunks<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return a mutable reference to the reader
+    pub fn get_mut(&mut self) -> &mut RingBufferReader<T, N, R, S> {
+        &mut self.reader
+    }
+
+    /// Unwrap the reader
+    pub fn into_inner(self) -> RingBufferReader<T, N, R, S> {
+        self.reader
+    }
+}
+
+impl<T, const N: usize, R, S> Stream for ReadyChunks<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>> + Unpin,
+    S: Storage<T>,
+{
+    type Item = Vec<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Vec<T>>> {
+        let this = self.get_mut();
+        let mut chunk = Vec::new();
+        match ready!(this.reader.poll_next_many(cx, &mut chunk, this.capacity)) {
+            0 => Poll::Ready(None),
+            _ => Poll::Ready(Some(chunk)),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Per-element enqueue timestamps, available with the `std` feature.
+//!
+//! [`TimedWriter`] and [`TimedReader`] wrap the halves of a ring buffer of
+//! `(Instant, T)` pairs: every element is stamped when pushed, and the time
+//! it spent in the queue is returned when pulled:
+//!
+//! ```
+//! use ringbuffer_spsc::{timed::{TimedReader, TimedWriter}, RingBuffer};
+//!
+//! let (tx, rx) = RingBuffer::init();
+//! let (mut tx, mut rx) = (TimedWriter::<u8, 4>::new(tx), TimedReader::new(rx));
+//! assert!(tx.push(1).is_none());
+//! let (latency, t) = rx.pull_timed().unwrap();
+//! assert_eq!(t, 1);
+//! println!("queued for {latency:?}");
+//! ```
+use crate::{
+    storage::{Inline, Storage},
+    RingBuffer, RingBufferReader, RingBufferWriter, SharedRingBuffer,
+};
+use core::ops::Deref;
+use std::time::{Duration, Instant};
+
+/// A [`RingBufferWriter`] stamping every element with its enqueue time.
+pub struct TimedWriter<
+    T,
+    const N: usize,
+    R = SharedRingBuffer<(Instant, T), N>,
+    S = Inline<(Instant, T), N>,
+> where
+    R: Deref<Target = RingBuffer<(Instant, T), N, S>>,
+    S: Storage<(Instant, T)>,
+{
+    tx: RingBufferWriter<(Instant, T), N, R, S>,
+}
+
+impl<T, const N: usize, R, S> TimedWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<(Instant, T), N, S>>,
+    S: Storage<(Instant, T)>,
+{
+    pub fn new(tx: RingBufferWriter<(Instant, T), N, R, S>) -> Self {
+        Self { tx }
+    }
+
+    /// Get back the wrapped writer
+    pub fn into_inner(self) -> RingBufferWriter<(Instant, T), N, R, S> {
+        self.tx
+    }
+
+    /// Push an element stamped with the current time, handing it back if the ring buffer is full
+    #[inline]
+    pub fn push(&mut self, t: T) -> Option<T> {
+        self.tx.push((Instant::now(), t)).map(|(_, t)| t)
+    }
+}
+
+/// A [`RingBufferReader`] returning how long every element has been queued.
+pub struct TimedReader<
+    T,
+    const N: usize,
+    R = SharedRingBuffer<(Instant, T), N>,
+    S = Inline<(Instant, T), N>,
+> where
+    R: Deref<Target = RingBuffer<(Instant, T), N, S>>,
+    S: Storage<(Instant, T)>,
+{
+    rx: RingBufferReader<(Instant, T), N, R, S>,
+}
+
+impl<T, const N: usize, R, S> TimedReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<(Instant, T), N, S>>,
+    S: Storage<(Instant, T)>,
+{
+    pub fn new(rx: RingBufferReader<(Instant, T), N, R, S>) -> Self {
+        Self { rx }
+    }
+
+    /// Get back the wrapped reader
+    pub fn into_inner(self) -> RingBufferReader<(Instant, T), N, R, S> {
+        self.rx
+    }
+
+    /// Pull the next element along with the time elapsed since it was pushed
+    #[inline]
+    pub fn pull_timed(&mut self) -> Option<(Duration, T)> {
+        self.rx.pull().map(|(at, t)| (at.elapsed(), t))
+    }
+
+    /// Pull the next element, discarding its timestamp
+    #[inline]
+    pub fn pull(&mut self) -> Option<T> {
+        self.rx.pull().map(|(_, t)| t)
+    }
+}
@@ -0,0 +1,208 @@
+//! Traits abstracting over the writing and reading halves of a ring buffer.
+//!
+//! Libraries can be generic over [`Producer`] and [`Consumer`] instead of
+//! naming a concrete handle, and be handed any flavor of ring buffer:
+//!
+//! ```
+//! use ringbuffer_spsc::{static_ringbuffer, Consumer, Producer, RingBuffer};
+//!
+//! fn forward<T>(tx: &mut impl Producer<T>, rx: &mut impl Consumer<T>) -> usize {
+//!     let mut n = 0;
+//!     while tx.slots() > 0 {
+//!         match rx.pull() {
+//!             Some(t) => assert!(tx.push(t).is_none()),
+//!             None => break,
+//!         }
+//!         n += 1;
+//!     }
+//!     n
+//! }
+//!
+//! static_ringbuffer!(STATIC: u8, 4);
+//!
+//! let (mut tx, _rx) = STATIC.split();
+//! let (mut src, mut rx) = RingBuffer::<u8, 8>::init();
+//! for i in 0..6 {
+//!     src.push(i);
+//! }
+//! assert_eq!(forward(&mut tx, &mut rx), 4);
+//! assert_eq!(rx.pull(), Some(4));
+//! ```
+#[cfg(feature = "alloc")]
+use crate::local;
+use crate::{heapless, storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::ops::Deref;
+
+/// The writing half of a ring buffer.
+pub trait Producer<T> {
+    /// Push an element, handing it back if the ring buffer is full
+    fn push(&mut self, t: T) -> Option<T>;
+
+    /// Return the number of free slots
+    fn slots(&self) -> usize;
+
+    /// Return the maximum number of elements the ring buffer can hold
+    fn capacity(&self) -> usize;
+
+    /// Check whether the ring buffer is currently full
+    #[inline]
+    fn is_full(&self) -> bool {
+        self.slots() == 0
+    }
+}
+
+/// The reading half of a ring buffer.
+pub trait Consumer<T> {
+    /// Pull the next element, if any
+    fn pull(&mut self) -> Option<T>;
+
+    /// Return a reference to the next element to be pulled without removing it
+    fn peek(&self) -> Option<&T>;
+
+    /// Return the number of elements ready to be pulled
+    fn slots(&self) -> usize;
+
+    /// Return the maximum number of elements the ring buffer can hold
+    fn capacity(&self) -> usize;
+
+    /// Check whether the ring buffer is currently empty
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.slots() == 0
+    }
+}
+
+impl<T, const N: usize, R, S> Producer<T> for RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    #[inline]
+    fn push(&mut self, t: T) -> Option<T> {
+        RingBufferWriter::push(self, t)
+    }
+
+    /// Return the number of free slots below the [soft limit](RingBufferWriter::set_soft_limit)
+    #[inline]
+    fn slots(&self) -> usize {
+        let len = N - RingBufferWriter::slots(self);
+        self.soft_limit().saturating_sub(len)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize, R, S> Consumer<T> for RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    #[inline]
+    fn pull(&mut self) -> Option<T> {
+        RingBufferReader::pull(self)
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<&T> {
+        RingBufferReader::peek(self)
+    }
+
+    #[inline]
+    fn slots(&self) -> usize {
+        RingBufferReader::slots(self)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize, S> Producer<T> for local::RingBufferWriter<T, N, S>
+where
+    S: Storage<T>,
+{
+    #[inline]
+    fn push(&mut self, t: T) -> Option<T> {
+        local::RingBufferWriter::push(self, t)
+    }
+
+    #[inline]
+    fn slots(&self) -> usize {
+        local::RingBufferWriter::slots(self)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize, S> Consumer<T> for local::RingBufferReader<T, N, S>
+where
+    S: Storage<T>,
+{
+    #[inline]
+    fn pull(&mut self) -> Option<T> {
+        local::RingBufferReader::pull(self)
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<&T> {
+        local::RingBufferReader::peek(self)
+    }
+
+    #[inline]
+    fn slots(&self) -> usize {
+        local::RingBufferReader::len(self)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> Producer<T> for heapless::Producer<'_, T, N> {
+    #[inline]
+    fn push(&mut self, t: T) -> Option<T> {
+        self.enqueue(t).err()
+    }
+
+    #[inline]
+    fn slots(&self) -> usize {
+        N - heapless::Producer::len(self)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> Consumer<T> for heapless::Consumer<'_, T, N> {
+    #[inline]
+    fn pull(&mut self) -> Option<T> {
+        self.dequeue()
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<&T> {
+        heapless::Consumer::peek(self)
+    }
+
+    #[inline]
+    fn slots(&self) -> usize {
+        heapless::Consumer::len(self)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
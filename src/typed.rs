@@ -0,0 +1,278 @@
+//! Typed messages serialized with postcard over byte ring buffers.
+//!
+//! Each message is pushed as a little-endian `u32` length followed by its
+//! postcard serialization, written straight into the free slots. A message is
+//! pushed as a whole or not at all, and is only pulled once all its bytes have
+//! been received. Sending an enum allows carrying heterogeneous messages, and
+//! versioned ones by only ever appending variants:
+//!
+//! ```
+//! use ringbuffer_spsc::{RingBuffer, TypedReceiver, TypedSender};
+//!
+//! #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+//! enum Message {
+//!     Ping(u32),
+//!     Text(String),
+//! }
+//!
+//! let (tx, rx) = RingBuffer::<u8, 64>::init();
+//! let (mut tx, mut rx) = (TypedSender::new(tx), TypedReceiver::new(rx));
+//! tx.send(&Message::Ping(7)).unwrap();
+//! tx.send(&Message::Text("hello".into())).unwrap();
+//! assert_eq!(rx.recv(), Ok(Some(Message::Ping(7))));
+//! assert_eq!(rx.recv(), Ok(Some(Message::Text("hello".into()))));
+//! assert_eq!(rx.recv(), Ok(None));
+//! ```
+//!
+//! The wrappers work with any handle, whatever the storage of the ring buffer.
+//! Messages wrapping around the end of the buffer are copied to a scratch
+//! buffer of the receiver before being deserialized.
+use crate::{
+    storage::{Inline, Storage},
+    DefaultRef, RingBuffer, RingBufferReader, RingBufferWriter,
+};
+use alloc::vec::Vec;
+use core::{fmt, marker::PhantomData, mem::MaybeUninit, ops::Deref};
+use postcard::ser_flavors::{Flavor, Size};
+use serde::{de::DeserializeOwned, Serialize};
+
+const PREFIX: usize = size_of::<u32>();
+
+/// Error returned when sending or receiving a typed message fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypedError {
+    /// The free slots cannot hold the message yet. Nothing has been pushed.
+    Full,
+    /// The message can never fit in the ring buffer. When receiving, the
+    /// length prefix is corrupted and the ready bytes have been discarded.
+    TooLarge,
+    /// The message could not be serialized, or could not be deserialized in
+    /// which case it has been discarded.
+    Postcard(postcard::Error),
+}
+
+impl From<postcard::Error> for TypedError {
+    fn from(e: postcard::Error) -> Self {
+        TypedError::Postcard(e)
+    }
+}
+
+impl fmt::Display for TypedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypedError::Full => write!(f, "ring buffer full"),
+            TypedError::TooLarge => write!(f, "message larger than the ring buffer"),
+            TypedError::Postcard(e) => write!(f, "postcard error: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TypedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TypedError::Postcard(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Postcard flavor writing into the two parts of a chunk of free slots.
+struct ChunkFlavor<'a> {
+    first: &'a mut [MaybeUninit<u8>],
+    second: &'a mut [MaybeUninit<u8>],
+    pos: usize,
+}
+
+impl Flavor for ChunkFlavor<'_> {
+    type Output = usize;
+
+    fn try_push(&mut self, b: u8) -> postcard::Result<()> {
+        let slot = match self.first.get_mut(self.pos) {
+            Some(slot) => slot,
+            None => self
+                .second
+                .get_mut(self.pos - self.first.len())
+                .ok_or(postcard::Error::SerializeBufferFull)?,
+        };
+        slot.write(b);
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn finalize(self) -> postcard::Result<usize> {
+        Ok(self.pos)
+    }
+}
+
+/// Writer of a byte ring buffer sending values of type `T` as postcard messages.
+///
+/// The read-only operations of the writer are available through [`Deref`].
+pub struct TypedSender<T, const N: usize, R = DefaultRef<u8, N>, S = Inline<u8, N>>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    writer: RingBufferWriter<u8, N, R, S>,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T, const N: usize, R, S> TypedSender<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Wrap a byte writer.
+    ///
+    /// # Panics
+    /// This function panics if `N` cannot hold a length prefix and a byte.
+    pub fn new(writer: RingBufferWriter<u8, N, R, S>) -> Self {
+        assert!(N > PREFIX, "TypedSender requires more than {PREFIX} slots");
+        Self {
+            writer,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Unwrap the byte writer.
+    pub fn into_inner(self) -> RingBufferWriter<u8, N, R, S> {
+        self.writer
+    }
+}
+
+impl<T, const N: usize, R, S> TypedSender<T, N, R, S>
+where
+    T: Serialize,
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Serialize and push `value` with its length prefix.
+    pub fn send(&mut self, value: &T) -> Result<(), TypedError> {
+        let len = postcard::serialize_with_flavor(value, Size::default())?;
+        let total = PREFIX + len;
+        if total > N || u32::try_from(len).is_err() {
+            return Err(TypedError::TooLarge);
+        }
+        let mut chunk = self.writer.write_free_chunk(total);
+        if chunk.len() < total {
+            return Err(TypedError::Full);
+        }
+        let (first, second) = chunk.as_mut_slices();
+        let mut flavor = ChunkFlavor {
+            first,
+            second,
+            pos: 0,
+        };
+        flavor.try_extend(&(len as u32).to_le_bytes())?;
+        let written = postcard::serialize_with_flavor(value, flavor)?;
+        debug_assert_eq!(written, total);
+        // SAFETY: the prefix and the serialization filled the whole chunk
+        unsafe { chunk.commit_all() };
+        Ok(())
+    }
+}
+
+impl<T, const N: usize, R, S> Deref for TypedSender<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    type Target = RingBufferWriter<u8, N, R, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.writer
+    }
+}
+
+/// Reader of a byte ring buffer receiving values of type `T` sent by a [`TypedSender`].
+///
+/// The read-only operations of the reader are available through [`Deref`].
+pub struct TypedReceiver<T, const N: usize, R = DefaultRef<u8, N>, S = Inline<u8, N>>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    reader: RingBufferReader<u8, N, R, S>,
+    scratch: Vec<u8>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, const N: usize, R, S> TypedReceiver<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Wrap a byte reader.
+    ///
+    /// # Panics
+    /// This function panics if `N` cannot hold a length prefix and a byte.
+    pub fn new(reader: RingBufferReader<u8, N, R, S>) -> Self {
+        assert!(
+            N > PREFIX,
+            "TypedReceiver requires more than {PREFIX} slots"
+        );
+        Self {
+            reader,
+            scratch: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Unwrap the byte reader.
+    pub fn into_inner(self) -> RingBufferReader<u8, N, R, S> {
+        self.reader
+    }
+}
+
+impl<T, const N: usize, R, S> TypedReceiver<T, N, R, S>
+where
+    T: DeserializeOwned,
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    /// Pull and deserialize the next message.
+    ///
+    /// Returns `Ok(None)` if no message is complete yet.
+    pub fn recv(&mut self) -> Result<Option<T>, TypedError> {
+        let chunk = self.reader.read_ready_chunk(usize::MAX);
+        if chunk.len() < PREFIX {
+            return Ok(None);
+        }
+        let (first, second) = chunk.as_slices();
+        let mut prefix = [0; PREFIX];
+        for (d, s) in prefix.iter_mut().zip(first.iter().chain(second)) {
+            *d = *s;
+        }
+        let len = u32::from_le_bytes(prefix) as usize;
+        if len > N - PREFIX {
+            chunk.commit_all();
+            return Err(TypedError::TooLarge);
+        }
+        if chunk.len() < PREFIX + len {
+            return Ok(None);
+        }
+        let res = match first.get(PREFIX..PREFIX + len) {
+            Some(bytes) => postcard::from_bytes(bytes),
+            None => {
+                self.scratch.clear();
+                let bytes = first.iter().chain(second).skip(PREFIX).take(len);
+                self.scratch.extend(bytes);
+                postcard::from_bytes(&self.scratch)
+            }
+        };
+        chunk.commit(PREFIX + len);
+        Ok(Some(res?))
+    }
+}
+
+impl<T, const N: usize, R, S> Deref for TypedReceiver<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    type Target = RingBufferReader<u8, N, R, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.reader
+    }
+}
@@ -0,0 +1,98 @@
+use crate::atomic::AtomicUsize;
+use core::{cell::UnsafeCell, sync::atomic::Ordering, task::Waker};
+
+const WAITING: usize = 0;
+const REGISTERING: usize = 0b01;
+const WAKING: usize = 0b10;
+
+/// A slot holding at most one [`Waker`], safe to register from one side and
+/// to wake from the other one concurrently.
+///
+/// This follows the same protocol as `futures::task::AtomicWaker`: a small
+/// state machine guards the cell so that `register` and `wake` never access
+/// it at the same time, and a wake racing with a registration is never lost.
+pub(crate) struct AtomicWaker {
+    state: AtomicUsize,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+unsafe impl Send for AtomicWaker {}
+unsafe impl Sync for AtomicWaker {}
+
+impl AtomicWaker {
+    pub(crate) const fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(WAITING),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Register `waker` to be notified on the next call to [`Self::wake`].
+    ///
+    /// The caller must re-check its wakeup condition after registering.
+    pub(crate) fn register(&self, waker: &Waker) {
+        match self
+            .state
+            .compare_exchange(WAITING, REGISTERING, Ordering::Acquire, Ordering::Acquire)
+            .unwrap_or_else(|x| x)
+        {
+            WAITING => unsafe {
+                // Avoid cloning the waker if the stored one wakes the same task
+                match &*self.waker.get() {
+                    Some(old) if old.will_wake(waker) => {}
+                    _ => *self.waker.get() = Some(waker.clone()),
+                }
+                if let Err(actual) = self.state.compare_exchange(
+                    REGISTERING,
+                    WAITING,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                ) {
+                    // A concurrent wake happened while registering: it could not
+                    // take the waker, so it is our job to notify the task.
+                    debug_assert_eq!(actual, REGISTERING | WAKING);
+                    let waker = (*self.waker.get()).take();
+                    self.state.swap(WAITING, Ordering::AcqRel);
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            },
+            WAKING => {
+                // A wake is in progress: notify the task right away so that the
+                // condition is polled again.
+                waker.wake_by_ref();
+                core::hint::spin_loop();
+            }
+            state => {
+                // There is a single registering side per slot.
+                debug_assert!(state == REGISTERING || state == REGISTERING | WAKING);
+            }
+        }
+    }
+
+    /// Wake the registered task, if any.
+    pub(crate) fn wake(&self) {
+        if let Some(waker) = self.take() {
+            waker.wake();
+        }
+    }
+
+    fn take(&self) -> Option<Waker> {
+        match self.state.fetch_or(WAKING, Ordering::AcqRel) {
+            WAITING => {
+                let waker = unsafe { (*self.waker.get()).take() };
+                self.state.fetch_and(!WAKING, Ordering::Release);
+                waker
+            }
+            state => {
+                // Either a registration is in progress and will take care of
+                // waking the task, or another wake is already in progress.
+                debug_assert!(
+                    state == REGISTERING || state == REGISTERING | WAKING || state == WAKING
+                );
+                None
+            }
+        }
+    }
+}
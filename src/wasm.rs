@@ -0,0 +1,71 @@
+//! Blocking waits for WebAssembly threads, available on `wasm32` with the
+//! `atomics` target feature.
+//!
+//! The ring buffer can be shared by threads backed by web workers and a
+//! `SharedArrayBuffer` memory. Waiting relies on `memory.atomic.wait32`,
+//! the instruction behind `Atomics.wait`: the writer and the reader notify
+//! the index they update, and the other side sleeps on it. Browsers forbid
+//! blocking the main thread, so the waits must be called from workers.
+use crate::{
+    atomic::AtomicIndex, index, storage::Storage, Index, RingBuffer, RingBufferReader,
+    RingBufferWriter,
+};
+use core::{arch::wasm32, ops::Deref, sync::atomic::Ordering};
+
+/// Wake every thread waiting on `atomic`.
+#[inline]
+pub(crate) fn notify(atomic: &AtomicIndex) {
+    // SAFETY: the pointer is valid and aligned for a 32-bit wait on wasm32
+    unsafe { wasm32::memory_atomic_notify(atomic.as_ptr().cast(), u32::MAX) };
+}
+
+/// Sleep while `atomic` holds `expected`, for up to `timeout_ns` nanoseconds
+/// or forever if negative.
+#[inline]
+fn wait(atomic: &AtomicIndex, expected: Index, timeout_ns: i64) {
+    // SAFETY: the pointer is valid and aligned for a 32-bit wait on wasm32.
+    // With 64-bit indexes, the wait is on their low half, which changes with
+    // every update since wasm32 is little-endian.
+    unsafe { wasm32::memory_atomic_wait32(atomic.as_ptr().cast(), expected as i32, timeout_ns) };
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Block the current thread while the ring buffer is empty, for up to
+    /// `timeout_ns` nanoseconds or forever if negative.
+    ///
+    /// Returns whether elements are ready to be pulled. Spurious wakeups,
+    /// timeouts, and the writer being dropped all return `false` on an empty
+    /// ring buffer.
+    pub fn wait_readable(&mut self, timeout_ns: i64) -> bool {
+        let idx_w = self.inner.idx_w.load(Ordering::Acquire);
+        if idx_w == self.local_idx_r && !self.is_writer_dropped() {
+            wait(&self.inner.idx_w, idx_w, timeout_ns);
+        }
+        self.refresh_idx_w();
+        self.cached_idx_w != self.local_idx_r
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Block the current thread while the ring buffer is full, for up to
+    /// `timeout_ns` nanoseconds or forever if negative.
+    ///
+    /// Returns whether there are free slots. Spurious wakeups, timeouts,
+    /// and the reader being dropped all return `false` on a full ring buffer.
+    pub fn wait_writable(&mut self, timeout_ns: i64) -> bool {
+        let idx_r = self.inner.idx_r.load(Ordering::Acquire);
+        if index::len(idx_r, self.local_idx_w) == N && !self.is_reader_dropped() {
+            wait(&self.inner.idx_r, idx_r, timeout_ns);
+        }
+        self.refresh_idx_r();
+        index::len(self.cached_idx_r, self.local_idx_w) < N
+    }
+}
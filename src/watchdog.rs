@@ -0,0 +1,122 @@
+//! Activity timestamps, available with the `watchdog` feature.
+//!
+//! The time of the last successful push and pull is recorded in the shared
+//! state, so that a supervisor can detect a stalled writer or reader and
+//! trigger a recovery:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//! use std::time::Duration;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+//! assert_eq!(tx.time_since_last_pull(), None);
+//! tx.push(1);
+//! rx.pull();
+//! assert!(tx.time_since_last_pull().unwrap() < Duration::from_secs(60));
+//! ```
+//!
+//! Every push and pull reads the clock, which has a cost on the hot path.
+use crate::{storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::{
+    ops::Deref,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+// Reference of the timestamps, which are stored as nanoseconds since it
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+pub(crate) struct Activity {
+    pub(crate) push: Timestamp,
+    pub(crate) pull: Timestamp,
+}
+
+impl Activity {
+    pub(crate) const fn new() -> Self {
+        Self {
+            push: Timestamp::new(),
+            pull: Timestamp::new(),
+        }
+    }
+}
+
+pub(crate) struct Timestamp {
+    // Zero until the first activity
+    nanos: AtomicU64,
+}
+
+impl Timestamp {
+    const fn new() -> Self {
+        Self {
+            nanos: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn touch(&self) {
+        let epoch = EPOCH.get_or_init(Instant::now);
+        // Offset by one so that an activity at the epoch is not taken for none at all
+        let nanos = epoch.elapsed().as_nanos() as u64 + 1;
+        self.nanos.store(nanos, Ordering::Relaxed);
+    }
+
+    fn elapsed(&self) -> Option<Duration> {
+        match self.nanos.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => {
+                let at = *EPOCH.get()? + Duration::from_nanos(nanos - 1);
+                Some(at.elapsed())
+            }
+        }
+    }
+}
+
+impl<T, const N: usize, S> RingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Return the time elapsed since the last push, if any
+    pub fn time_since_last_push(&self) -> Option<Duration> {
+        self.watchdog.push.elapsed()
+    }
+
+    /// Return the time elapsed since the last pull, if any
+    pub fn time_since_last_pull(&self) -> Option<Duration> {
+        self.watchdog.pull.elapsed()
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the time elapsed since the last push, if any
+    pub fn time_since_last_push(&self) -> Option<Duration> {
+        self.inner.time_since_last_push()
+    }
+
+    /// Return the time elapsed since the reader last pulled, if any
+    pub fn time_since_last_pull(&self) -> Option<Duration> {
+        self.inner.time_since_last_pull()
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Return the time elapsed since the writer last pushed, if any
+    pub fn time_since_last_push(&self) -> Option<Duration> {
+        self.inner.time_since_last_push()
+    }
+
+    /// Return the time elapsed since the last pull, if any
+    pub fn time_since_last_pull(&self) -> Option<Duration> {
+        self.inner.time_since_last_pull()
+    }
+}
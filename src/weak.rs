@@ -0,0 +1,344 @@
+//! Weak handles observing a heap-allocated ring buffer.
+//!
+//! [`RingBufferWriter::downgrade`] and [`RingBufferReader::downgrade`] return
+//! a [`WeakRingBuffer`], which reports the liveness of both halves and the
+//! number of elements in the ring buffer, e.g. for a supervisor or a metrics
+//! exporter, without keeping the elements alive:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, rx) = RingBuffer::<u32, 8>::init();
+//! let weak = tx.downgrade();
+//! assert!(tx.push(1).is_none());
+//! assert_eq!(weak.len(), 1);
+//!
+//! drop((tx, rx));
+//! assert!(weak.is_writer_dropped() && weak.is_reader_dropped());
+//! ```
+//!
+//! Once both halves are dropped, the elements left are dropped and the ring
+//! buffer is freed, slots included, and the weak handles keep reporting the
+//! state it was left in. They only keep alive a small header, allocated apart
+//! from the ring buffer on the first downgrade.
+use crate::{
+    atomic::{AtomicIndex, AtomicUsize},
+    index,
+    storage::{Inline, Storage},
+    RingBuffer, RingBufferReader, RingBufferWriter, SharedRingBuffer, READER_ALIVE, WRITER_ALIVE,
+};
+use alloc::boxed::Box;
+use core::{
+    marker::PhantomData,
+    ptr::{self, NonNull},
+    sync::atomic::Ordering,
+};
+
+// State of the header telling that the ring buffer is still allocated
+const RB_ALIVE: usize = 0b1;
+// Unit of the count of weak handles accessing the ring buffer, above the flag
+const PIN_UNIT: usize = 0b10;
+
+/// Header shared by the weak handles of a heap-allocated ring buffer.
+///
+/// It is allocated on the first downgrade, and its address is kept by the
+/// ring buffer for the halves to find it. A weak handle pins the ring buffer
+/// while accessing it, and whoever sees it released and unpinned frees it.
+pub(crate) struct Header {
+    // Weak handles, plus one for the ring buffer until it is freed
+    refs: AtomicUsize,
+    // `RB_ALIVE` until the last half is dropped, plus the pins
+    state: AtomicUsize,
+    // State the ring buffer was left in, recorded before clearing `RB_ALIVE`
+    idx_r: AtomicIndex,
+    idx_w: AtomicIndex,
+    #[cfg(feature = "shared-stats")]
+    failed_pushes: AtomicUsize,
+    #[cfg(feature = "shared-stats")]
+    failed_pulls: AtomicUsize,
+}
+
+impl Header {
+    /// Return the header whose address is kept by a ring buffer.
+    ///
+    /// # Safety
+    /// The address must be the non-zero one exposed by [`Self::get_or_alloc`].
+    unsafe fn from_addr(addr: usize) -> NonNull<Self> {
+        NonNull::new_unchecked(ptr::with_exposed_provenance_mut(addr))
+    }
+
+    /// Return the header of an allocated ring buffer, allocating it if needed.
+    fn get_or_alloc<T, const N: usize, S: Storage<T>>(rb: &RingBuffer<T, N, S>) -> NonNull<Self> {
+        let addr = rb.weak.load(Ordering::Acquire);
+        if addr != 0 {
+            // SAFETY: the address is the one of a header kept by the ring buffer
+            return unsafe { Self::from_addr(addr) };
+        }
+        let header = Box::into_raw(Box::new(Self {
+            refs: AtomicUsize::new(1),
+            state: AtomicUsize::new(RB_ALIVE),
+            idx_r: AtomicIndex::new(0),
+            idx_w: AtomicIndex::new(0),
+            #[cfg(feature = "shared-stats")]
+            failed_pushes: AtomicUsize::new(0),
+            #[cfg(feature = "shared-stats")]
+            failed_pulls: AtomicUsize::new(0),
+        }));
+        // Both halves may downgrade at the same time
+        match rb.weak.compare_exchange(
+            0,
+            header.expose_provenance(),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            // SAFETY: the header has just been allocated
+            Ok(_) => unsafe { NonNull::new_unchecked(header) },
+            Err(addr) => {
+                // SAFETY: the header has not been shared, and the other one is kept
+                unsafe {
+                    drop(Box::from_raw(header));
+                    Self::from_addr(addr)
+                }
+            }
+        }
+    }
+
+    /// Drop and free a ring buffer whose halves are both gone, recording the
+    /// state it is left in for its weak handles if any.
+    ///
+    /// # Safety
+    /// The ring buffer must have been allocated by [`SharedRingBuffer::pair`]
+    /// or mapped by [`SharedRingBuffer::pair_from_ptr`], and its halves must
+    /// be gone.
+    pub(crate) unsafe fn release<T, const N: usize, S: Storage<T>>(
+        ptr: NonNull<RingBuffer<T, N, S>>,
+    ) {
+        let rb = ptr.as_ref();
+        let addr = rb.weak.load(Ordering::Acquire);
+        if addr == 0 {
+            SharedRingBuffer::free(ptr);
+            return;
+        }
+        let header = Self::from_addr(addr);
+        let h = header.as_ref();
+        h.idx_r
+            .store(rb.idx_r.load(Ordering::Relaxed), Ordering::Relaxed);
+        h.idx_w
+            .store(rb.idx_w.load(Ordering::Relaxed), Ordering::Relaxed);
+        #[cfg(feature = "shared-stats")]
+        {
+            let stats = rb.stats();
+            h.failed_pushes
+                .store(stats.failed_pushes, Ordering::Relaxed);
+            h.failed_pulls.store(stats.failed_pulls, Ordering::Relaxed);
+        }
+        // Release orders the state above before the weak handles seeing the
+        // ring buffer gone, Acquire their last accesses before freeing it
+        if h.state.fetch_and(!RB_ALIVE, Ordering::AcqRel) == RB_ALIVE {
+            SharedRingBuffer::free(ptr);
+        }
+        Self::unref(header);
+    }
+
+    /// Drop a reference to the header, freeing it if it was the last one.
+    ///
+    /// # Safety
+    /// The reference must be owned by the caller.
+    unsafe fn unref(header: NonNull<Self>) {
+        if header.as_ref().refs.fetch_sub(1, Ordering::AcqRel) == 1 {
+            drop(Box::from_raw(header.as_ptr()));
+        }
+    }
+}
+
+/// Weak handle of a heap-allocated ring buffer, returned by
+/// [`RingBufferWriter::downgrade`] or [`RingBufferReader::downgrade`].
+///
+/// It can neither push nor pull, and keeps neither the elements nor the
+/// slots alive.
+pub struct WeakRingBuffer<T, const N: usize, S = Inline<T, N>>
+where
+    S: Storage<T>,
+{
+    header: NonNull<Header>,
+    ptr: NonNull<RingBuffer<T, N, S>>,
+    _marker: PhantomData<RingBuffer<T, N, S>>,
+}
+
+unsafe impl<T: Send, const N: usize, S: Storage<T> + Send> Send for WeakRingBuffer<T, N, S> {}
+unsafe impl<T, const N: usize, S: Storage<T>> Sync for WeakRingBuffer<T, N, S> where
+    RingBuffer<T, N, S>: Sync
+{
+}
+
+impl<T, const N: usize, S> WeakRingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    /// Count a new weak handle of an allocated ring buffer.
+    ///
+    /// # Safety
+    /// The header must be kept alive by the ring buffer or another weak handle.
+    unsafe fn new(header: NonNull<Header>, ptr: NonNull<RingBuffer<T, N, S>>) -> Self {
+        header.as_ref().refs.fetch_add(1, Ordering::Relaxed);
+        Self {
+            header,
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Create a weak handle of a ring buffer kept alive by one of its halves.
+    fn downgrade(inner: &SharedRingBuffer<T, N, S>) -> Self {
+        let header = Header::get_or_alloc(inner);
+        // SAFETY: the half keeps the ring buffer, and so the header, alive
+        unsafe { Self::new(header, inner.as_ptr()) }
+    }
+
+    #[inline]
+    fn header(&self) -> &Header {
+        // SAFETY: the header is kept until the last weak handle is dropped
+        unsafe { self.header.as_ref() }
+    }
+
+    /// Call `alive` on the ring buffer if it is still allocated, pinning it
+    /// meanwhile, or `released` on the header with the state it was left in.
+    fn inspect<R>(
+        &self,
+        alive: impl FnOnce(&RingBuffer<T, N, S>) -> R,
+        released: impl FnOnce(&Header) -> R,
+    ) -> R {
+        let state = &self.header().state;
+        let mut current = state.load(Ordering::Acquire);
+        while current & RB_ALIVE != 0 {
+            match state.compare_exchange(
+                current,
+                current + PIN_UNIT,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    // SAFETY: the ring buffer is not freed while pinned, and
+                    // access to the slots is left to the halves
+                    let r = alive(unsafe { self.ptr.as_ref() });
+                    if state.fetch_sub(PIN_UNIT, Ordering::AcqRel) == PIN_UNIT {
+                        // SAFETY: the halves are gone and this was the last pin
+                        unsafe { SharedRingBuffer::free(self.ptr) };
+                    }
+                    return r;
+                }
+                Err(new) => current = new,
+            }
+        }
+        released(self.header())
+    }
+
+    /// Return the maximum number of elements the ring buffer can hold
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Return the number of elements in the ring buffer, or left in it when
+    /// both halves have been dropped
+    pub fn len(&self) -> usize {
+        self.inspect(
+            |rb| loop {
+                // The read index is loaded first, so it never is past the write
+                // index. Retry if the reader went a whole lap meanwhile.
+                let idx_r = rb.idx_r.load(Ordering::Acquire);
+                let idx_w = rb.idx_w.load(Ordering::Acquire);
+                let len = index::len(idx_r, idx_w);
+                if len <= N {
+                    return len;
+                }
+            },
+            |h| {
+                index::len(
+                    h.idx_r.load(Ordering::Relaxed),
+                    h.idx_w.load(Ordering::Relaxed),
+                )
+            },
+        )
+    }
+
+    /// Check whether the ring buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check whether the writer has been dropped
+    pub fn is_writer_dropped(&self) -> bool {
+        self.inspect(
+            |rb| rb.flags.load(Ordering::Acquire) & WRITER_ALIVE == 0,
+            |_| true,
+        )
+    }
+
+    /// Check whether the reader has been dropped
+    pub fn is_reader_dropped(&self) -> bool {
+        self.inspect(
+            |rb| rb.flags.load(Ordering::Acquire) & READER_ALIVE == 0,
+            |_| true,
+        )
+    }
+
+    /// Take a snapshot of the statistics of the ring buffer
+    #[cfg(feature = "shared-stats")]
+    pub fn stats(&self) -> crate::StatsSnapshot {
+        self.inspect(
+            |rb| rb.stats(),
+            |h| {
+                let pushed = h.idx_w.load(Ordering::Relaxed);
+                let pulled = h.idx_r.load(Ordering::Relaxed);
+                crate::StatsSnapshot {
+                    pushed,
+                    pulled,
+                    failed_pushes: h.failed_pushes.load(Ordering::Relaxed),
+                    failed_pulls: h.failed_pulls.load(Ordering::Relaxed),
+                    occupancy: index::len(pulled, pushed),
+                }
+            },
+        )
+    }
+}
+
+impl<T, const N: usize, S> Clone for WeakRingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    fn clone(&self) -> Self {
+        // SAFETY: this weak handle keeps the header alive
+        unsafe { Self::new(self.header, self.ptr) }
+    }
+}
+
+impl<T, const N: usize, S> Drop for WeakRingBuffer<T, N, S>
+where
+    S: Storage<T>,
+{
+    fn drop(&mut self) {
+        // SAFETY: the reference of this weak handle is given up
+        unsafe { Header::unref(self.header) };
+    }
+}
+
+impl<T, const N: usize, S> RingBufferWriter<T, N, SharedRingBuffer<T, N, S>, S>
+where
+    S: Storage<T>,
+{
+    /// Create a weak handle observing the ring buffer
+    pub fn downgrade(&self) -> WeakRingBuffer<T, N, S> {
+        WeakRingBuffer::downgrade(&self.inner)
+    }
+}
+
+impl<T, const N: usize, S> RingBufferReader<T, N, SharedRingBuffer<T, N, S>, S>
+where
+    S: Storage<T>,
+{
+    /// Create a weak handle observing the ring buffer
+    pub fn downgrade(&self) -> WeakRingBuffer<T, N, S> {
+        WeakRingBuffer::downgrade(&self.inner)
+    }
+}
@@ -0,0 +1,79 @@
+//! Low-power waits for ARM targets, available on `arm` and `aarch64` with the
+//! `wfe` feature.
+//!
+//! The writer and the reader signal an event with `sev` after updating their
+//! index, and the other side sleeps with `wfe` until then instead of spinning.
+//! The event register is sticky, so an event signalled between the check of the
+//! index and `wfe` makes it return right away and no wakeup is lost. On
+//! Cortex-M, interrupts also wake the core, which makes a ring buffer fed from
+//! an interrupt handler sleep-friendly on a single core.
+//!
+//! ```no_run
+//! # use ringbuffer_spsc::RingBuffer;
+//! # let (_tx, mut rx) = RingBuffer::<u8, 4>::init();
+//! let b = loop {
+//!     match rx.pull() {
+//!         Some(b) => break b,
+//!         None => {
+//!             rx.wait_readable();
+//!         }
+//!     }
+//! };
+//! ```
+use crate::{index, storage::Storage, RingBuffer, RingBufferReader, RingBufferWriter};
+use core::{arch::asm, ops::Deref, sync::atomic::Ordering};
+
+/// Signal an event to the cores waiting with `wfe`.
+#[inline]
+pub(crate) fn notify() {
+    // SAFETY: the barrier makes the index update visible before the event
+    unsafe { asm!("dsb sy", "sev", options(nostack, preserves_flags)) };
+}
+
+/// Sleep until the next event, which may be spurious.
+#[inline]
+fn wait() {
+    // SAFETY: waiting for an event has no other effect; the memory clobber
+    // keeps the index loads on their side of the wait
+    unsafe { asm!("wfe", options(nostack, preserves_flags)) };
+}
+
+impl<T, const N: usize, R, S> RingBufferReader<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Sleep in a low-power state while the ring buffer is empty, until the
+    /// next event.
+    ///
+    /// Returns whether elements are ready to be pulled. Unrelated events and
+    /// the writer being dropped return `false` on an empty ring buffer.
+    pub fn wait_readable(&mut self) -> bool {
+        let idx_w = self.inner.idx_w.load(Ordering::Acquire);
+        if idx_w == self.local_idx_r && !self.is_writer_dropped() {
+            wait();
+        }
+        self.refresh_idx_w();
+        self.cached_idx_w != self.local_idx_r
+    }
+}
+
+impl<T, const N: usize, R, S> RingBufferWriter<T, N, R, S>
+where
+    R: Deref<Target = RingBuffer<T, N, S>>,
+    S: Storage<T>,
+{
+    /// Sleep in a low-power state while the ring buffer is full, until the
+    /// next event.
+    ///
+    /// Returns whether there are free slots. Unrelated events and the reader
+    /// being dropped return `false` on a full ring buffer.
+    pub fn wait_writable(&mut self) -> bool {
+        let idx_r = self.inner.idx_r.load(Ordering::Acquire);
+        if index::len(idx_r, self.local_idx_w) == N && !self.is_reader_dropped() {
+            wait();
+        }
+        self.refresh_idx_r();
+        index::len(self.cached_idx_r, self.local_idx_w) < N
+    }
+}
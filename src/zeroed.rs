@@ -0,0 +1,136 @@
+//! Byte ring buffers zero-initialized up front.
+//!
+//! Since every slot holds an initialized byte from the start and pulling
+//! never de-initializes it, the free region can be borrowed as a plain
+//! `&mut [u8]`, e.g. to hand it to a DMA engine or an FFI call:
+//!
+//! ```
+//! use ringbuffer_spsc::RingBuffer;
+//!
+//! let (mut tx, mut rx) = RingBuffer::<u8, 8>::init_zeroed();
+//! let free = tx.free_bytes();
+//! assert_eq!(free, &[0; 8]);
+//! free[..3].copy_from_slice(b"abc");
+//! tx.commit_bytes(3);
+//! assert_eq!(rx.pull(), Some(b'a'));
+//! ```
+use crate::{
+    index,
+    storage::{Inline, Storage},
+    DefaultRef, RingBuffer, RingBufferReader, RingBufferWriter,
+};
+use core::ops::Deref;
+
+impl<const N: usize> RingBuffer<u8, N> {
+    /// Create a zero-initialized byte ring buffer and return its two halves.
+    #[cfg(feature = "alloc")]
+    pub fn init_zeroed() -> (ZeroedWriter<N>, RingBufferReader<u8, N>) {
+        let (tx, rx) = Self::init();
+        (ZeroedWriter::new(tx), rx)
+    }
+}
+
+impl<const N: usize, S> RingBuffer<u8, N, S>
+where
+    S: Storage<u8>,
+{
+    /// Zero-initialize the free slots of the ring buffer and split it into its two halves
+    /// borrowing it.
+    ///
+    /// # Panics
+    /// This function panics if the halves of a previous split are still alive.
+    pub fn split_zeroed(&self) -> (ZeroedWriter<N, &Self, S>, RingBufferReader<u8, N, &Self, S>) {
+        let (tx, rx) = self.split();
+        (ZeroedWriter::new(tx), rx)
+    }
+}
+
+/// Writer of a zero-initialized byte ring buffer, created with
+/// [`RingBuffer::init_zeroed`] or [`RingBuffer::split_zeroed`].
+///
+/// It only exposes the operations that never leave a slot uninitialized.
+/// The read-only ones are available through [`Deref`].
+pub struct ZeroedWriter<const N: usize, R = DefaultRef<u8, N>, S = Inline<u8, N>>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    writer: RingBufferWriter<u8, N, R, S>,
+}
+
+impl<const N: usize, R, S> ZeroedWriter<N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    fn new(writer: RingBufferWriter<u8, N, R, S>) -> Self {
+        // Elements left over by a previous split are already initialized
+        let start = index::slot::<N>(writer.local_idx_w);
+        let free = N - index::len(writer.cached_idx_r, writer.local_idx_w);
+        let first = free.min(N - start);
+        // SAFETY: the reader does not touch the free slots until they are published
+        unsafe {
+            let slots = writer.inner.slots();
+            slots.add(start).write_bytes(0, first);
+            slots.write_bytes(0, free - first);
+        }
+        Self { writer }
+    }
+
+    /// Push a byte, handing it back if the ring buffer is full.
+    #[inline]
+    pub fn push(&mut self, b: u8) -> Option<u8> {
+        self.writer.push(b)
+    }
+
+    /// Push as many bytes of `src` as fit, returning how many have been pushed.
+    #[inline]
+    pub fn push_slice(&mut self, src: &[u8]) -> usize {
+        self.writer.push_slice(src)
+    }
+
+    /// Return the contiguous free region following the write index.
+    ///
+    /// The bytes written to it are made available to the reader with
+    /// [`Self::commit_bytes`]. They hold stale data from previous laps,
+    /// or zeros on the first one.
+    pub fn free_bytes(&mut self) -> &mut [u8] {
+        // SAFETY: the slots are only accessed through the returned slice until
+        // the next use of the writer, and all of them are initialized
+        unsafe {
+            let (ptr, len) = self.writer.writable_ptr();
+            core::slice::from_raw_parts_mut(ptr, len)
+        }
+    }
+
+    /// Make the first `n` bytes of the free region available to the reader.
+    ///
+    /// # Panics
+    /// This function panics if `n` is larger than the number of free slots.
+    pub fn commit_bytes(&mut self, n: usize) {
+        let free = self.writer.refresh_read_index();
+        assert!(
+            n <= free,
+            "committed {n} bytes but only {free} slots are free"
+        );
+        // SAFETY: the slots are free and already initialized
+        unsafe { self.writer.publish(n) };
+    }
+
+    /// Unwrap the writer, giving up the byte view of the free slots.
+    pub fn into_inner(self) -> RingBufferWriter<u8, N, R, S> {
+        self.writer
+    }
+}
+
+impl<const N: usize, R, S> Deref for ZeroedWriter<N, R, S>
+where
+    R: Deref<Target = RingBuffer<u8, N, S>>,
+    S: Storage<u8>,
+{
+    type Target = RingBufferWriter<u8, N, R, S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.writer
+    }
+}
@@ -0,0 +1,79 @@
+//! Shared references handed out through `&self` must stay valid while more are
+//! created, checked under Miri with `cargo +nightly miri test --test aliasing`.
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{heapless::Queue, RingBuffer};
+
+#[test]
+fn peek_twice() {
+    let (mut tx, rx) = RingBuffer::<u32, 4>::init();
+    assert!(tx.push(1).is_none());
+    let a = rx.peek().unwrap();
+    let b = rx.peek().unwrap();
+    let c = rx.front().unwrap();
+    assert_eq!((*a, *b, *c), (1, 1, 1));
+}
+
+#[test]
+fn peek_slice_twice() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    // Wrap around the end of the buffer
+    for i in 0..3 {
+        assert!(tx.push(i).is_none());
+    }
+    assert_eq!(rx.pull(), Some(0));
+    assert_eq!(rx.pull(), Some(1));
+    for i in 3..6 {
+        assert!(tx.push(i).is_none());
+    }
+    let a = rx.peek_slice();
+    let b = rx.peek_slice();
+    let c = rx.peek().unwrap();
+    assert_eq!(a, ([2, 3].as_slice(), [4, 5].as_slice()));
+    assert_eq!((a, *c), (b, 2));
+}
+
+#[test]
+fn chunk_as_slices_twice() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    for i in 0..4 {
+        assert!(tx.push(i).is_none());
+    }
+    let chunk = rx.read_chunk(4).unwrap();
+    let a = chunk.as_slices();
+    let b = chunk.as_slices();
+    assert_eq!(a, b);
+    assert_eq!(a.0, [0, 1, 2, 3]);
+}
+
+#[test]
+fn mark_pull() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    assert!(tx.push(1).is_none());
+    let peeked = *rx.peek().unwrap();
+    let mut mark = rx.mark();
+    assert_eq!(mark.pull(), Some(&peeked));
+}
+
+#[test]
+fn heapless_peek_twice() {
+    let mut queue: Queue<u32, 4> = Queue::new();
+    assert_eq!(queue.enqueue(1), Ok(()));
+    let a = queue.peek().unwrap();
+    let b = queue.peek().unwrap();
+    assert_eq!((*a, *b), (1, 1));
+}
+
+#[cfg(feature = "ringbuf")]
+#[test]
+fn ringbuf_occupied_slices_twice() {
+    use ::ringbuf::traits::{Consumer, Producer};
+    use ringbuffer_spsc::ringbuf;
+
+    let (tx, rx) = RingBuffer::<u32, 4>::init();
+    let (mut tx, rx) = (ringbuf::Producer::new(tx), ringbuf::Consumer::new(rx));
+    assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+    let a = rx.as_slices();
+    let b = rx.as_slices();
+    assert_eq!(a, b);
+    assert_eq!(rx.iter().chain(rx.iter()).sum::<u32>(), 12);
+}
@@ -0,0 +1,250 @@
+#![cfg(all(feature = "async", feature = "alloc"))]
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn push_pull_async() {
+    const N: usize = 100_000;
+    let (mut tx, mut rx) = RingBuffer::<usize, 16>::init();
+
+    let p = async move {
+        for i in 0..N {
+            tx.push_async(i).await.unwrap();
+        }
+    };
+    let c = async move {
+        let mut current: usize = 0;
+        while let Some(c) = rx.pull_async().await {
+            assert_eq!(c, current);
+            current += 1;
+        }
+        current
+    };
+    let ((), n) = futures::executor::block_on(futures::future::join(p, c));
+    assert_eq!(n, N);
+}
+
+#[test]
+fn push_async_reader_dropped() {
+    let (mut tx, rx) = RingBuffer::<usize, 2>::init();
+    drop(rx);
+    assert_eq!(futures::executor::block_on(tx.push_async(7)), Err(7));
+}
+
+#[test]
+fn wait_watermark() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 8>::init();
+    assert!(!futures::executor::block_on(tx.wait_watermark()));
+
+    let t = std::thread::spawn(move || {
+        for i in 0..8 {
+            while tx.push(i).is_some() {
+                std::thread::yield_now();
+            }
+        }
+        tx.set_watermark(2);
+        assert!(futures::executor::block_on(tx.wait_watermark()));
+        // Reached watermarks are disarmed
+        assert!(!tx.watermark_reached());
+    });
+
+    let mut pulled = 0;
+    while pulled < 6 {
+        if rx.pull().is_some() {
+            pulled += 1;
+        }
+    }
+    t.join().unwrap();
+}
+
+#[test]
+fn poll_peek() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 4>::init();
+    let waker = futures::task::noop_waker();
+    let mut cx = std::task::Context::from_waker(&waker);
+    assert!(rx.poll_peek(&mut cx).is_pending());
+
+    assert!(tx.push(1).is_none());
+    assert_eq!(rx.poll_peek(&mut cx), std::task::Poll::Ready(Some(&1)));
+    assert_eq!(rx.poll_peek(&mut cx), std::task::Poll::Ready(Some(&1)));
+    drop(tx);
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(rx.poll_peek(&mut cx), std::task::Poll::Ready(None));
+
+    // Wait for the head element from another thread
+    let (mut tx, mut rx) = RingBuffer::<usize, 4>::init();
+    let p = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(tx.push(7).is_none());
+    });
+    let head = futures::executor::block_on(futures::future::poll_fn(|cx| {
+        rx.poll_peek(cx).map(|t| t.copied())
+    }));
+    assert_eq!(head, Some(7));
+    assert_eq!(rx.pull(), Some(7));
+    p.join().unwrap();
+}
+
+#[test]
+fn wait_drained_async() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 8>::init();
+    assert!(futures::executor::block_on(tx.wait_drained_async()));
+    for i in 0..5 {
+        assert!(tx.push(i).is_none());
+    }
+
+    let c = std::thread::spawn(move || {
+        for _ in 0..5 {
+            while rx.pull().is_none() {
+                std::thread::yield_now();
+            }
+        }
+        rx
+    });
+    assert!(futures::executor::block_on(tx.wait_drained_async()));
+    assert!(tx.push(5).is_none());
+    drop(c.join().unwrap());
+    assert!(!futures::executor::block_on(tx.wait_drained_async()));
+}
+
+#[test]
+fn register_wakers() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    struct Counter(AtomicUsize);
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 1>::init();
+    let data = Arc::new(Counter(AtomicUsize::new(0)));
+    let space = Arc::new(Counter(AtomicUsize::new(0)));
+
+    rx.register_data_waker(&Waker::from(data.clone()));
+    tx.push(1);
+    tx.push(2);
+    // The waker is taken by the first push
+    assert_eq!(data.0.load(Ordering::Relaxed), 1);
+
+    tx.register_space_waker(&Waker::from(space.clone()));
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(space.0.load(Ordering::Relaxed), 1);
+
+    rx.register_data_waker(&Waker::from(data.clone()));
+    drop(tx);
+    assert_eq!(data.0.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn async_spins() {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct Noop;
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let noop = Arc::new(Noop);
+    let waker = Waker::from(noop.clone());
+    let mut cx = Context::from_waker(&waker);
+    let (mut tx, mut rx) = RingBuffer::<usize, 4>::init();
+
+    // Without spinning, the waker is registered right away
+    let mut pull = Box::pin(rx.pull_async());
+    assert!(pull.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(Arc::strong_count(&noop), 3);
+    drop(pull);
+
+    // Spinning catches the element pushed meanwhile without registering the waker again
+    let (mut tx2, mut rx2) = RingBuffer::<usize, 4>::init();
+    let t = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        tx2.push(1);
+    });
+    rx2.set_async_spins(u32::MAX);
+    let mut pull = Box::pin(rx2.pull_async());
+    assert_eq!(pull.as_mut().poll(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(Arc::strong_count(&noop), 3);
+    t.join().unwrap();
+
+    tx.push(2);
+    assert_eq!(rx.pull(), Some(2));
+}
+
+#[test]
+fn poll_push_pull() {
+    use std::task::{Context, Poll, Waker};
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let (mut tx, mut rx) = RingBuffer::<usize, 2>::init();
+
+    assert_eq!(rx.poll_pull(&mut cx), Poll::Pending);
+    for i in 0..2 {
+        let mut item = Some(i);
+        assert_eq!(tx.poll_push(&mut cx, &mut item), Poll::Ready(Ok(())));
+        assert_eq!(item, None);
+    }
+
+    // The element stays in place while the ring buffer is full
+    let mut item = Some(2);
+    assert_eq!(tx.poll_push(&mut cx, &mut item), Poll::Pending);
+    assert_eq!(item, Some(2));
+    assert_eq!(rx.poll_pull(&mut cx), Poll::Ready(Some(0)));
+    assert_eq!(tx.poll_push(&mut cx, &mut item), Poll::Ready(Ok(())));
+
+    drop(tx);
+    assert_eq!(rx.poll_pull(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(rx.poll_pull(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(rx.poll_pull(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn coalesced_wakeups() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    struct Counter(AtomicUsize);
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    let data = Arc::new(Counter(AtomicUsize::new(0)));
+    let space = Arc::new(Counter(AtomicUsize::new(0)));
+
+    // Pushing into a non-empty ring buffer does not wake the reader
+    tx.push(1);
+    rx.register_data_waker(&Waker::from(data.clone()));
+    tx.push(2);
+    assert_eq!(data.0.load(Ordering::Relaxed), 0);
+
+    // Pulling from a non-full ring buffer does not wake the writer
+    tx.register_space_waker(&Waker::from(space.clone()));
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(space.0.load(Ordering::Relaxed), 0);
+
+    // Only the transitions do
+    assert_eq!(rx.pull(), Some(2));
+    tx.push(3);
+    assert_eq!(data.0.load(Ordering::Relaxed), 1);
+    assert_eq!(tx.push_slice(&[4, 5, 6]), 3);
+    tx.register_space_waker(&Waker::from(space.clone()));
+    assert_eq!(rx.pull(), Some(3));
+    assert_eq!(space.0.load(Ordering::Relaxed), 1);
+
+    // The soft limit sets the level the writer waits for
+    tx.set_soft_limit(2);
+    tx.register_space_waker(&Waker::from(space.clone()));
+    assert_eq!(rx.pull(), Some(4));
+    assert_eq!(space.0.load(Ordering::Relaxed), 1);
+    assert_eq!(rx.pull(), Some(5));
+    assert_eq!(space.0.load(Ordering::Relaxed), 2);
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{RingBuffer, RingBufferReader, RingBufferWriter};
+use std::{cell::Cell, rc::Rc};
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn send_elements() {
+    // `Cell` is `Send` but not `Sync`, which is enough to move it between threads
+    assert_send::<RingBufferWriter<Cell<u32>, 4>>();
+    assert_send::<RingBufferReader<Cell<u32>, 4>>();
+    assert_send::<RingBufferWriter<String, 4, &RingBuffer<String, 4>>>();
+    assert_sync::<RingBufferReader<String, 4>>();
+
+    let (mut tx, mut rx) = RingBuffer::<Cell<u32>, 4>::init();
+    let p = std::thread::spawn(move || assert!(tx.push(Cell::new(1)).is_none()));
+    p.join().unwrap();
+    assert_eq!(rx.pull().map(Cell::into_inner), Some(1));
+}
+
+#[test]
+fn non_send_elements_on_one_thread() {
+    let shared = Rc::new(Cell::new(0));
+    let (mut tx, mut rx) = RingBuffer::<Rc<Cell<u32>>, 4>::init();
+    for _ in 0..3 {
+        assert!(tx.push(shared.clone()).is_none());
+    }
+    while let Some(rc) = rx.pull() {
+        rc.set(rc.get() + 1);
+    }
+    assert_eq!(shared.get(), 3);
+    assert_eq!(Rc::strong_count(&shared), 1);
+
+    // Also through a borrowed ring buffer, with elements left over on drop
+    let rb = RingBuffer::<Rc<Cell<u32>>, 4>::empty();
+    let (mut tx, rx) = rb.split();
+    assert!(tx.push(shared.clone()).is_none());
+    drop(tx);
+    drop(rx);
+    drop(rb);
+    assert_eq!(Rc::strong_count(&shared), 1);
+}
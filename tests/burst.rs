@@ -0,0 +1,89 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{BurstWriter, RingBuffer};
+
+#[test]
+fn burst_threads() {
+    const N: usize = 1_000_000;
+    let (tx, mut rx) = RingBuffer::<usize, 16>::init();
+    let mut tx = BurstWriter::<_, 16, 4>::new(tx);
+
+    let p = std::thread::spawn(move || {
+        let mut current: usize = 0;
+        while current < N {
+            if tx.push(current).is_none() {
+                current = current.wrapping_add(1);
+            } else {
+                std::thread::yield_now();
+            }
+        }
+        while tx.staged() > 0 {
+            tx.flush();
+        }
+    });
+
+    let c = std::thread::spawn(move || {
+        let mut current: usize = 0;
+        while current < N {
+            if let Some(c) = rx.pull() {
+                assert_eq!(c, current);
+                current = current.wrapping_add(1);
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    });
+
+    p.join().unwrap();
+    c.join().unwrap();
+}
+
+#[test]
+fn burst_full() {
+    let (tx, mut rx) = RingBuffer::<String, 4>::init();
+    let mut tx = BurstWriter::<_, 4, 3>::new(tx);
+    for s in ["a", "b", "c", "d", "e"] {
+        assert!(tx.push(s.to_string()).is_none());
+    }
+    // The first burst has been transferred when the local buffer filled up
+    assert_eq!(rx.pull().as_deref(), Some("a"));
+    assert_eq!(tx.staged(), 2);
+    assert!(tx.push("f".to_string()).is_none());
+    // Only two slots are left in the ring buffer
+    assert_eq!(tx.staged(), 1);
+    assert_eq!(tx.push("g".to_string()), None);
+    assert_eq!(tx.push("h".to_string()), None);
+    assert_eq!(tx.push("i".to_string()).as_deref(), Some("i"));
+
+    let Err(tx) = tx.into_inner() else {
+        panic!("flushed with a full ring buffer");
+    };
+    for s in ["b", "c", "d"] {
+        assert_eq!(rx.pull().as_deref(), Some(s));
+    }
+    let Ok(mut tx) = tx.into_inner() else {
+        panic!("not flushed");
+    };
+    let rest: Vec<_> = std::iter::from_fn(|| rx.pull()).collect();
+    assert_eq!(rest, ["e", "f", "g", "h"]);
+    assert!(tx.push("j".to_string()).is_none());
+}
+
+#[test]
+fn push_or_merge() {
+    let (tx, mut rx) = RingBuffer::<u32, 2>::init();
+    let mut tx = BurstWriter::<_, 2, 2>::new(tx);
+    for i in 1..=6 {
+        assert!(tx.push_or_merge(i, |last, t| *last += t).is_none());
+    }
+    // 1 and 2 have been transferred, 3 is staged and 4 + 5 + 6 merged into the last staged element
+    assert_eq!(tx.staged(), 2);
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(rx.pull(), Some(2));
+    assert_eq!(tx.flush(), 2);
+    assert_eq!(rx.pull(), Some(3));
+    assert_eq!(rx.pull(), Some(15));
+
+    let (tx, _rx) = RingBuffer::<u32, 2>::init();
+    let mut tx = BurstWriter::<_, 2, 0>::new(tx);
+    assert_eq!(tx.push_or_merge(1, |_, _| unreachable!()), Some(1));
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "bytes")]
+use bytes::{Buf, BufMut};
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn buf() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    assert_eq!(rx.remaining(), 0);
+    assert!(rx.chunk().is_empty());
+
+    tx.push_slice(&[1, 2, 3, 4, 5, 6]);
+    rx.advance(4);
+    tx.push_slice(&[7, 8, 9]);
+    // The readable region wraps around the end of the buffer
+    assert_eq!(rx.remaining(), 5);
+    assert_eq!(rx.chunk(), [5, 6, 7, 8]);
+    let mut dst = [0; 5];
+    rx.copy_to_slice(&mut dst);
+    assert_eq!(dst, [5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn buf_mut() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    tx.set_soft_limit(6);
+    assert_eq!(tx.remaining_mut(), 6);
+    tx.put_u32(0x01020304);
+    assert_eq!(tx.chunk_mut().len(), 2);
+    assert_eq!(rx.get_u32(), 0x01020304);
+
+    // The free region wraps around the end of the buffer
+    tx.put_slice(&[5, 6, 7, 8, 9]);
+    assert_eq!(tx.remaining_mut(), 1);
+    let mut dst = [0; 5];
+    rx.copy_to_slice(&mut dst);
+    assert_eq!(dst, [5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn push_buf() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    let mut src = (&[1, 2][..]).chain(&[3, 4, 5][..]);
+    assert_eq!(tx.push_buf(&mut src), 4);
+    assert_eq!(src.remaining(), 1);
+    assert_eq!(rx.copy_to_bytes(4), &[1, 2, 3, 4][..]);
+
+    assert_eq!(tx.push_buf(&mut src), 1);
+    assert!(!src.has_remaining());
+    assert_eq!(rx.get_u8(), 5);
+}
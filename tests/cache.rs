@@ -0,0 +1,78 @@
+#![cfg(feature = "std")]
+use ringbuffer_spsc::{CacheHooks, RingBuffer};
+use std::sync::Mutex;
+
+#[derive(Debug, PartialEq)]
+enum Op {
+    Clean(usize, usize),
+    Invalidate(usize, usize),
+}
+
+static OPS: Mutex<Vec<Op>> = Mutex::new(Vec::new());
+static BASE: Mutex<usize> = Mutex::new(0);
+
+fn clean(addr: *const u8, len: usize) {
+    let base = *BASE.lock().unwrap();
+    OPS.lock()
+        .unwrap()
+        .push(Op::Clean(addr as usize - base, len));
+}
+
+fn invalidate(addr: *const u8, len: usize) {
+    let base = *BASE.lock().unwrap();
+    OPS.lock()
+        .unwrap()
+        .push(Op::Invalidate(addr as usize - base, len));
+}
+
+fn take_ops() -> Vec<Op> {
+    std::mem::take(&mut *OPS.lock().unwrap())
+}
+
+#[test]
+fn lifecycle() {
+    let rb = RingBuffer::<u32, 8>::empty();
+    *BASE.lock().unwrap() = rb.slot_ptr(0) as usize;
+    let (mut tx, mut rx) = rb.split();
+    let hooks = CacheHooks {
+        clean: Some(clean),
+        invalidate: Some(invalidate),
+    };
+    tx.set_cache_hooks(hooks);
+    rx.set_cache_hooks(CacheHooks {
+        clean: None,
+        ..hooks
+    });
+
+    // A device fills the granted region
+    let (ptr, len) = unsafe { tx.writable_ptr() };
+    assert_eq!(len, 8);
+    assert_eq!(take_ops(), [Op::Clean(0, 32)]);
+    unsafe {
+        for i in 0..6 {
+            ptr.add(i).write(i as u32);
+        }
+        tx.publish(6);
+    }
+    assert_eq!(rx.peek(), Some(&0));
+    assert_eq!(take_ops(), [Op::Invalidate(0, 4)]);
+    assert_eq!(rx.pull(), Some(0));
+    assert_eq!(take_ops(), [Op::Invalidate(0, 24)]);
+    assert_eq!(rx.pull_slice(&mut [0; 5]), 5);
+    assert_eq!(take_ops(), []);
+
+    // The next regions wrap around
+    assert_eq!(unsafe { tx.writable_ptr() }.1, 2);
+    assert_eq!(take_ops(), [Op::Clean(24, 8)]);
+    tx.push_slice(&[6, 7, 8, 9]);
+    assert_eq!(rx.pull(), Some(6));
+    assert_eq!(take_ops(), [Op::Invalidate(24, 8), Op::Invalidate(0, 8)]);
+
+    // The reader cleans the regions a device drains
+    rx.set_cache_hooks(CacheHooks {
+        clean: Some(clean),
+        ..CacheHooks::NONE
+    });
+    assert_eq!(unsafe { rx.readable_ptr() }.1, 1);
+    assert_eq!(take_ops(), [Op::Clean(28, 4)]);
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "std")]
+use ringbuffer_spsc::channel::{
+    channel, RecvError, RecvTimeoutError, SendError, TryRecvError, TrySendError,
+};
+use std::time::{Duration, Instant};
+
+#[test]
+fn threads() {
+    const N: usize = 1_000_000;
+    let (mut tx, rx) = channel::<usize, 16>();
+
+    let p = std::thread::spawn(move || {
+        for i in 0..N {
+            tx.send(i).unwrap();
+        }
+    });
+
+    let c = std::thread::spawn(move || {
+        let mut current = 0;
+        for c in rx {
+            assert_eq!(c, current);
+            current += 1;
+        }
+        assert_eq!(current, N);
+    });
+
+    p.join().unwrap();
+    c.join().unwrap();
+}
+
+#[test]
+fn try_send_recv() {
+    let (mut tx, mut rx) = channel::<u8, 2>();
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(tx.try_send(1), Ok(()));
+    assert_eq!(tx.try_send(2), Ok(()));
+    assert_eq!(tx.try_send(3), Err(TrySendError::Full(3)));
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn disconnected() {
+    let (mut tx, mut rx) = channel::<u8, 2>();
+    tx.send(1).unwrap();
+    drop(tx);
+    // Values sent before the disconnection are still received
+    assert_eq!(rx.recv(), Ok(1));
+    assert_eq!(rx.recv(), Err(RecvError));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+
+    let (mut tx, rx) = channel::<u8, 2>();
+    drop(rx);
+    assert_eq!(tx.send(1), Err(SendError(1)));
+    assert_eq!(tx.try_send(2), Err(TrySendError::Disconnected(2)));
+}
+
+#[test]
+fn recv_timeout() {
+    let (mut tx, mut rx) = channel::<u8, 2>();
+    let start = Instant::now();
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+    assert!(start.elapsed() >= Duration::from_millis(10));
+
+    tx.send(1).unwrap();
+    drop(tx);
+    assert_eq!(rx.recv_timeout(Duration::ZERO), Ok(1));
+    assert_eq!(
+        rx.recv_timeout(Duration::MAX),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
@@ -0,0 +1,315 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{ChunkError, RingBuffer};
+use std::rc::Rc;
+
+#[test]
+fn chunk_threads() {
+    const N: usize = 1_000_000;
+    let (mut tx, mut rx) = RingBuffer::<usize, 16>::init();
+
+    let p = std::thread::spawn(move || {
+        let mut current: usize = 0;
+        while current < N {
+            let n = tx.slots().clamp(1, 5).min(N - current);
+            match tx.write_chunk_uninit(n) {
+                Ok(chunk) => current += chunk.fill_from_iter(current..),
+                Err(_) => std::thread::yield_now(),
+            }
+        }
+    });
+
+    let c = std::thread::spawn(move || {
+        let mut current: usize = 0;
+        while current < N {
+            let n = rx.slots().clamp(1, 7);
+            match rx.read_chunk(n) {
+                Ok(chunk) => {
+                    for c in chunk {
+                        assert_eq!(c, current);
+                        current += 1;
+                    }
+                }
+                Err(_) => std::thread::yield_now(),
+            }
+        }
+    });
+
+    p.join().unwrap();
+    c.join().unwrap();
+}
+
+#[test]
+fn too_few_slots() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    assert_eq!(rx.read_chunk(1).err(), Some(ChunkError::TooFewSlots(0)));
+    tx.write_chunk(3).unwrap().commit_all();
+    assert_eq!(tx.slots(), 1);
+    assert_eq!(tx.write_chunk(2).err(), Some(ChunkError::TooFewSlots(1)));
+    assert_eq!(rx.slots(), 3);
+    assert_eq!(rx.read_chunk(4).err(), Some(ChunkError::TooFewSlots(3)));
+}
+
+#[test]
+fn wrap_around() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    tx.write_chunk(3).unwrap().commit_all();
+    rx.read_chunk(3).unwrap().commit_all();
+
+    let mut chunk = tx.write_chunk(4).unwrap();
+    let (first, second) = chunk.as_mut_slices();
+    assert_eq!((first.len(), second.len()), (1, 3));
+    first.copy_from_slice(&[1]);
+    second.copy_from_slice(&[2, 3, 4]);
+    chunk.commit_all();
+
+    let chunk = rx.read_chunk(4).unwrap();
+    assert_eq!(chunk.as_slices(), (&[1][..], &[2, 3, 4][..]));
+    chunk.commit(2);
+    assert_eq!(rx.pull(), Some(3));
+    assert_eq!(rx.pull(), Some(4));
+}
+
+#[test]
+fn commit_drops() {
+    let item = Rc::new(());
+    let (mut tx, mut rx) = RingBuffer::<Rc<()>, 8>::init();
+
+    // Uncommitted slots of a write chunk are dropped
+    let mut chunk = tx.write_chunk(4).unwrap();
+    let (first, _) = chunk.as_mut_slices();
+    first.fill(item.clone());
+    chunk.commit(1);
+    assert_eq!(Rc::strong_count(&item), 2);
+    drop(tx.write_chunk(2).unwrap());
+    assert_eq!(rx.slots(), 1);
+
+    // Committed slots of a read chunk are dropped
+    let chunk = tx.write_chunk_uninit(3).unwrap();
+    assert_eq!(chunk.fill_from_iter(std::iter::repeat(item.clone())), 3);
+    assert_eq!(Rc::strong_count(&item), 5);
+    rx.read_chunk(2).unwrap().commit_all();
+    assert_eq!(Rc::strong_count(&item), 3);
+
+    // Only iterated elements are consumed
+    let mut iter = rx.read_chunk(2).unwrap().into_iter();
+    assert!(iter.next().is_some());
+    assert_eq!(iter.iterated(), 1);
+    drop(iter);
+    assert_eq!(rx.slots(), 1);
+    drop((tx, rx));
+    assert_eq!(Rc::strong_count(&item), 1);
+}
+
+#[test]
+fn push_slice() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+    assert_eq!(rx.pull(), Some(1));
+    // Wraps around the end of the buffer
+    assert_eq!(tx.push_slice(&[4, 5, 6]), 2);
+    assert_eq!(tx.push_slice(&[7]), 0);
+    let chunk = rx.read_chunk(4).unwrap();
+    assert_eq!(chunk.as_slices(), (&[2, 3, 4][..], &[5][..]));
+}
+
+#[test]
+fn push_slice_cloned() {
+    let (mut tx, mut rx) = RingBuffer::<String, 2>::init();
+    let src = ["a".to_string(), "b".to_string(), "c".to_string()];
+    assert_eq!(tx.push_slice_cloned(&src), 2);
+    assert_eq!(tx.push_slice_cloned(&src), 0);
+    assert_eq!(rx.pull().as_deref(), Some("a"));
+    assert_eq!(tx.push_slice_cloned(&src[2..]), 1);
+    assert_eq!(rx.pull().as_deref(), Some("b"));
+    assert_eq!(rx.pull().as_deref(), Some("c"));
+}
+
+#[test]
+fn push_repeat() {
+    let (mut tx, mut rx) = RingBuffer::<String, 4>::init();
+    let silence = "-".to_string();
+    assert_eq!(tx.push_repeat(&silence, 3), 3);
+    assert_eq!(tx.push_repeat(&silence, 3), 1);
+    assert_eq!(tx.push_repeat(&silence, 3), 0);
+    assert_eq!(
+        std::iter::from_fn(|| rx.pull())
+            .filter(|s| *s == silence)
+            .count(),
+        4
+    );
+    assert_eq!(tx.push_repeat(&silence, 0), 0);
+    assert!(rx.is_empty());
+}
+
+#[test]
+fn pump() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    let (mut relay_tx, mut relay_rx) = RingBuffer::<u8, 4>::init();
+    assert_eq!(tx.push_slice(&[1, 2, 3, 4, 5, 6]), 6);
+
+    assert_eq!(ringbuffer_spsc::pump(&mut rx, &mut relay_tx, 2), 2);
+    // Limited by the free slots of the destination
+    assert_eq!(ringbuffer_spsc::pump(&mut rx, &mut relay_tx, 8), 2);
+    assert_eq!(
+        relay_rx
+            .read_chunk(4)
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        [1, 2, 3, 4]
+    );
+    // Limited by the elements of the source
+    assert_eq!(ringbuffer_spsc::pump(&mut rx, &mut relay_tx, 8), 2);
+    assert_eq!(ringbuffer_spsc::pump(&mut rx, &mut relay_tx, 8), 0);
+    assert_eq!(relay_rx.pull(), Some(5));
+    assert_eq!(relay_rx.pull(), Some(6));
+}
+
+#[test]
+fn pull_slice() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    let mut dst = [0; 4];
+    assert_eq!(rx.pull_slice(&mut dst), 0);
+    assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+    assert_eq!(rx.pull_slice(&mut dst[..2]), 2);
+    assert_eq!(dst[..2], [1, 2]);
+    // Wraps around the end of the buffer
+    assert_eq!(tx.push_slice(&[4, 5, 6]), 3);
+    assert_eq!(rx.pull_slice(&mut dst), 4);
+    assert_eq!(dst, [3, 4, 5, 6]);
+}
+
+#[test]
+fn pull_each() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    assert_eq!(rx.pull_each(|_| unreachable!()), 0);
+    assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+    rx.pull();
+    assert_eq!(tx.push_slice(&[4, 5]), 2);
+
+    let mut pulled = Vec::new();
+    assert_eq!(rx.pull_each(|t| pulled.push(t)), 4);
+    assert_eq!(pulled, [2, 3, 4, 5]);
+    assert_eq!(tx.slots(), 4);
+}
+
+#[test]
+fn push_all() {
+    let (mut tx, mut rx) = RingBuffer::<String, 4>::init();
+    let mut rest = tx.push_all(["a", "b", "c", "d", "e", "f"].map(String::from));
+    assert_eq!(rest.next().as_deref(), Some("e"));
+    assert_eq!(rx.pull().as_deref(), Some("a"));
+
+    let mut rest = tx.push_all(rest);
+    assert_eq!(rest.next(), None);
+    let pulled: Vec<_> = std::iter::from_fn(|| rx.pull()).collect();
+    assert_eq!(pulled, ["b", "c", "d", "f"]);
+}
+
+#[test]
+fn peek_slice() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    assert_eq!(rx.peek_slice(), (&[][..], &[][..]));
+
+    tx.push_slice(&[1, 2, 3, 4, 5, 6]);
+    let mut dst = [0; 4];
+    rx.pull_slice(&mut dst);
+    tx.push_slice(&[7, 8, 9]);
+    assert_eq!(rx.peek_slice(), (&[5, 6, 7, 8][..], &[9][..]));
+
+    // Peeking does not consume anything
+    assert_eq!(rx.pull_slice(&mut dst), 4);
+    assert_eq!(dst, [5, 6, 7, 8]);
+    assert_eq!(rx.peek_slice(), (&[9][..], &[][..]));
+}
+
+#[test]
+fn write_chunk_contiguous() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    tx.push_slice(&[1, 2, 3, 4, 5, 6]);
+    let mut dst = [0; 4];
+    rx.pull_slice(&mut dst);
+
+    // 2 slots before the end and 4 after the wrap around
+    assert_eq!(
+        tx.write_chunk_contiguous(5).err(),
+        Some(ChunkError::TooFewSlots(4))
+    );
+    let mut chunk = tx.write_chunk_contiguous(3).unwrap();
+    let (first, second) = chunk.as_mut_slices();
+    assert_eq!((first.len(), second.len()), (3, 0));
+    chunk.fill_from_iter([7, 8, 9]);
+
+    let mut dst = [0; 8];
+    assert_eq!(rx.pull_slice(&mut dst), 7);
+    assert_eq!(dst[..7], [5, 6, 0, 0, 7, 8, 9]);
+
+    // Chunks fitting before the end are never padded
+    let chunk = tx.write_chunk_contiguous(5).unwrap();
+    assert_eq!(chunk.fill_from_iter(0..5), 5);
+    assert_eq!(rx.len(), 5);
+}
+
+#[test]
+fn pull_min() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    tx.push_slice(&[1, 2, 3]);
+    assert!(rx.pull_min(4).is_none());
+
+    tx.push_slice(&[4, 5]);
+    let chunk = rx.pull_min(4).unwrap();
+    assert_eq!(chunk.as_slices(), (&[1, 2, 3, 4, 5][..], &[][..]));
+    chunk.commit(4);
+    assert!(rx.pull_min(4).is_none());
+    assert_eq!(rx.pull(), Some(5));
+}
+
+#[test]
+fn try_push_n() {
+    let (mut tx, mut rx) = RingBuffer::<String, 4>::init();
+    assert!(tx.try_push_n(["a", "b", "c"].map(String::from)).is_ok());
+
+    let rest = tx.try_push_n(["d", "e"].map(String::from)).unwrap_err();
+    assert_eq!(rest.collect::<Vec<_>>(), ["d", "e"]);
+    assert_eq!(rx.len(), 3);
+
+    assert_eq!(rx.pull().as_deref(), Some("a"));
+    assert!(tx.try_push_n(["d", "e"].map(String::from)).is_ok());
+    let pulled: Vec<_> = std::iter::from_fn(|| rx.pull()).collect();
+    assert_eq!(pulled, ["b", "c", "d", "e"]);
+}
+
+#[test]
+fn pull_array() {
+    let (mut tx, mut rx) = RingBuffer::<String, 4>::init();
+    tx.push_slice_cloned(&["a".to_string(), "b".to_string(), "c".to_string()]);
+    assert_eq!(rx.pull_array::<4>(), None);
+    assert_eq!(rx.pull_array::<2>().unwrap(), ["a", "b"]);
+    assert_eq!(tx.slots(), 3);
+
+    // The array may wrap around the end of the buffer
+    tx.push_slice_cloned(&["d".to_string(), "e".to_string()]);
+    assert_eq!(rx.pull_array::<3>().unwrap(), ["c", "d", "e"]);
+    assert_eq!(rx.pull_array::<0>(), Some([]));
+    assert_eq!(rx.pull_array::<8>(), None);
+}
+
+#[test]
+fn push_array() {
+    let (mut tx, mut rx) = RingBuffer::<String, 4>::init();
+    assert!(tx
+        .push_array(["a".to_string(), "b".to_string(), "c".to_string()])
+        .is_ok());
+    let rejected = tx
+        .push_array(["d".to_string(), "e".to_string()])
+        .unwrap_err();
+    assert_eq!(rejected, ["d", "e"]);
+    assert_eq!(rx.len(), 3);
+
+    // The soft limit is honored
+    assert_eq!(rx.pull_array::<3>().unwrap(), ["a", "b", "c"]);
+    tx.set_soft_limit(1);
+    assert!(tx.push_array(rejected).is_err());
+    assert!(tx.push_array(["f".to_string()]).is_ok());
+    assert_eq!(rx.pull().as_deref(), Some("f"));
+}
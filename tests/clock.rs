@@ -0,0 +1,59 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{Clock, RingBuffer};
+use std::cell::Cell;
+
+struct FakeClock(Cell<u64>);
+
+impl Clock for FakeClock {
+    fn now(&self) -> u64 {
+        let now = self.0.get();
+        self.0.set(now.wrapping_add(1));
+        now
+    }
+}
+
+#[test]
+fn timeouts() {
+    let clock = FakeClock(Cell::new(u64::MAX - 2));
+    let (mut tx, mut rx) = RingBuffer::<u8, 1>::init();
+    assert_eq!(tx.push_timeout(1, &clock, 0), Ok(()));
+    // The ticks wrap around
+    assert_eq!(tx.push_timeout(2, &clock, 5), Err(2));
+    assert_eq!(clock.0.get(), 4);
+
+    assert_eq!(rx.pull_timeout(&clock, 0), Some(1));
+    assert_eq!(rx.pull_timeout(&clock, 5), None);
+    assert_eq!(clock.0.get(), 11);
+}
+
+#[test]
+fn dropped() {
+    let clock = FakeClock(Cell::new(0));
+    let (mut tx, rx) = RingBuffer::<u8, 1>::init();
+    assert_eq!(tx.push_timeout(1, &clock, u64::MAX), Ok(()));
+    drop(rx);
+    assert_eq!(tx.push_timeout(2, &clock, u64::MAX), Err(2));
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 1>::init();
+    tx.push(1);
+    drop(tx);
+    assert_eq!(rx.pull_timeout(&clock, u64::MAX), Some(1));
+    assert_eq!(rx.pull_timeout(&clock, u64::MAX), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn std_clock() {
+    use ringbuffer_spsc::StdClock;
+
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    let t = std::thread::spawn(move || {
+        for i in 0..1000 {
+            tx.push_timeout(i, &StdClock::new(), u64::MAX).unwrap();
+        }
+    });
+    for i in 0..1000 {
+        assert_eq!(rx.pull_timeout(&StdClock::new(), u64::MAX), Some(i));
+    }
+    t.join().unwrap();
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{FrameError, RingBuffer};
+
+fn ready(rx: &ringbuffer_spsc::RingBufferReader<u8, 512>) -> Vec<u8> {
+    let (first, second) = rx.peek_slice();
+    [first, second].concat()
+}
+
+#[test]
+fn cobs_vectors() {
+    let long: Vec<u8> = (1..=255).collect();
+    let vectors: [(&[u8], Vec<u8>); 7] = [
+        (&[], vec![0x01, 0x00]),
+        (&[0x00], vec![0x01, 0x01, 0x00]),
+        (&[0x00, 0x00], vec![0x01, 0x01, 0x01, 0x00]),
+        (
+            &[0x11, 0x22, 0x00, 0x33],
+            vec![0x03, 0x11, 0x22, 0x02, 0x33, 0x00],
+        ),
+        (
+            &[0x11, 0x00, 0x00, 0x00],
+            vec![0x02, 0x11, 0x01, 0x01, 0x01, 0x00],
+        ),
+        (&long[..254], [&[0xFF], &long[..254], &[0x00]].concat()),
+        (&long, [&[0xFF], &long[..254], &[0x02, 0xFF, 0x00]].concat()),
+    ];
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 512>::init();
+    for (data, encoded) in vectors {
+        assert!(tx.push_frame_cobs(data));
+        assert_eq!(ready(&rx), encoded);
+        let mut frame = vec![0xAA];
+        assert_eq!(rx.pull_frame_cobs(&mut frame), Ok(Some(data.len())));
+        assert_eq!(frame[1..], *data);
+        assert!(rx.is_empty());
+    }
+}
+
+#[test]
+fn slip_vectors() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 512>::init();
+    assert!(tx.push_frame_slip(&[0x01, 0xC0, 0xDB, 0x02]));
+    assert_eq!(ready(&rx), [0x01, 0xDB, 0xDC, 0xDB, 0xDD, 0x02, 0xC0]);
+    let mut frame = Vec::new();
+    assert_eq!(rx.pull_frame_slip(&mut frame), Ok(Some(4)));
+    assert_eq!(frame, [0x01, 0xC0, 0xDB, 0x02]);
+
+    // Empty frames are skipped
+    tx.push_slice(&[0xC0, 0xC0, 0x03, 0xC0]);
+    frame.clear();
+    assert_eq!(rx.pull_frame_slip(&mut frame), Ok(Some(1)));
+    assert_eq!(frame, [0x03]);
+    assert_eq!(rx.pull_frame_slip(&mut frame), Ok(None));
+}
+
+#[test]
+fn partial_and_wrapping() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    let mut frame = Vec::new();
+    // Does not fit with its overhead and delimiter
+    assert!(!tx.push_frame_cobs(&[1; 7]));
+
+    for i in 0..10u8 {
+        assert!(tx.push_frame_cobs(&[i, 0, i]));
+        frame.clear();
+        assert_eq!(rx.pull_frame_cobs(&mut frame), Ok(Some(3)));
+        assert_eq!(frame, [i, 0, i]);
+    }
+
+    // The frame is only pulled once complete
+    tx.push_slice(&[0x02, 0x05]);
+    assert_eq!(rx.pull_frame_cobs(&mut frame), Ok(None));
+    tx.push(0x00);
+    frame.clear();
+    assert_eq!(rx.pull_frame_cobs(&mut frame), Ok(Some(1)));
+    assert_eq!(frame, [0x05]);
+}
+
+#[test]
+fn errors() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    let mut frame = vec![1, 2];
+    tx.push_slice(&[0x05, 0x01, 0x00]);
+    assert_eq!(rx.pull_frame_cobs(&mut frame), Err(FrameError::Malformed));
+    assert_eq!(frame, [1, 2]);
+    assert!(rx.is_empty());
+
+    tx.push_slice(&[0xDB, 0x01, 0xC0]);
+    assert_eq!(rx.pull_frame_slip(&mut frame), Err(FrameError::Malformed));
+    assert_eq!(frame, [1, 2]);
+
+    tx.push_slice(&[1; 8]);
+    assert_eq!(rx.pull_frame_cobs(&mut frame), Err(FrameError::Overflow));
+    assert!(rx.is_empty());
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{DoubleBuffer, RingBuffer};
+
+fn fill(tx: &mut DoubleBuffer<u32, 8>, start: u32) {
+    let half = tx.current_half_mut().unwrap();
+    assert_eq!(half.len(), 4);
+    for (i, slot) in half.iter_mut().enumerate() {
+        slot.write(start + i as u32);
+    }
+}
+
+#[test]
+fn alternate_halves() {
+    let (tx, mut rx) = RingBuffer::<u32, 8>::init();
+    let mut tx = DoubleBuffer::new(tx);
+
+    fill(&mut tx, 0);
+    assert!(unsafe { tx.swap() });
+    fill(&mut tx, 4);
+    // The reader has not drained the first half yet
+    assert!(!unsafe { tx.swap() });
+    assert!(tx.current_half_mut().is_none());
+
+    assert_eq!(rx.pull_slice(&mut [0; 3]), 3);
+    assert!(tx.current_half_mut().is_none());
+    assert_eq!(rx.pull(), Some(3));
+    fill(&mut tx, 8);
+    assert!(!unsafe { tx.swap() });
+
+    let mut dst = [0; 8];
+    assert_eq!(rx.pull_slice(&mut dst), 8);
+    assert_eq!(dst, [4, 5, 6, 7, 8, 9, 10, 11]);
+    assert!(tx.current_half_mut().is_some());
+}
+
+#[test]
+#[should_panic(expected = "middle of a half")]
+fn misaligned() {
+    let (mut tx, _rx) = RingBuffer::<u32, 8>::init();
+    tx.push(0);
+    DoubleBuffer::new(tx);
+}
@@ -0,0 +1,70 @@
+#![cfg(all(feature = "eventfd", target_os = "linux"))]
+use ringbuffer_spsc::RingBuffer;
+use std::os::fd::RawFd;
+
+fn readable(fd: RawFd, timeout_ms: i32) -> bool {
+    let mut pfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    unsafe { libc::poll(&mut pfd, 1, timeout_ms) == 1 }
+}
+
+#[test]
+fn doorbell() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    let fd = rx.eventfd().unwrap();
+    assert_eq!(rx.eventfd().unwrap(), fd);
+
+    // Not armed, the writer does not signal
+    tx.push(1);
+    assert!(!readable(fd, 0));
+    // Elements are available, waiting would block forever
+    assert!(!rx.arm_eventfd());
+    assert_eq!(rx.pull(), Some(1));
+
+    assert!(rx.arm_eventfd());
+    assert!(!readable(fd, 0));
+    tx.push(2);
+    assert!(readable(fd, 0));
+    assert_eq!(rx.pull(), Some(2));
+
+    // Re-arming resets the eventfd
+    assert!(rx.arm_eventfd());
+    assert!(!readable(fd, 0));
+    drop(tx);
+    assert!(readable(fd, 0));
+    assert!(!rx.arm_eventfd());
+}
+
+#[test]
+fn threads() {
+    const N: u32 = 10_000;
+    let (mut tx, mut rx) = RingBuffer::<u32, 16>::init();
+    let fd = rx.eventfd().unwrap();
+
+    let p = std::thread::spawn(move || {
+        for i in 0..N {
+            while tx.push(i).is_some() {
+                std::thread::yield_now();
+            }
+        }
+    });
+
+    let mut current = 0;
+    loop {
+        while let Some(i) = rx.pull() {
+            assert_eq!(i, current);
+            current += 1;
+        }
+        if rx.arm_eventfd() {
+            // A lost wake up would hang here
+            assert!(readable(fd, 10_000));
+        } else if rx.is_empty() {
+            break;
+        }
+    }
+    assert_eq!(current, N);
+    p.join().unwrap();
+}
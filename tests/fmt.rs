@@ -0,0 +1,30 @@
+#![cfg(feature = "alloc")]
+use core::fmt::Write;
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn write_fmt() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    let mut buf = [0; 8];
+    write!(tx, "ab").unwrap();
+    // Wrap around the end of the buffer
+    assert_eq!(rx.pull_slice(&mut buf[..2]), 2);
+    write!(tx, "{}-x", 123).unwrap();
+    let n = rx.pull_slice(&mut buf);
+    assert_eq!(&buf[..n], b"123-x");
+}
+
+#[test]
+fn truncate_between_fragments() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    let (n, c) = (1234567, 'é');
+    assert!(write!(tx, "{n}{c}").is_err());
+    let mut buf = [0; 8];
+    let n = rx.pull_slice(&mut buf);
+    assert_eq!(core::str::from_utf8(&buf[..n]).unwrap(), "1234567");
+
+    // The soft limit is honored
+    tx.set_soft_limit(2);
+    assert!(tx.write_str("abc").is_err());
+    assert!(tx.write_str("ab").is_ok());
+}
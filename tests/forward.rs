@@ -0,0 +1,101 @@
+#![cfg(feature = "std")]
+use ringbuffer_spsc::{FlushPolicy, Forwarder, RingBuffer};
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+/// Sink recording the writes and the flushes, failing every other write
+/// with `WouldBlock` if `flaky`.
+#[derive(Default)]
+struct Sink {
+    data: Vec<u8>,
+    writes: usize,
+    flushes: usize,
+    flaky: bool,
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writes += 1;
+        if self.flaky && self.writes.is_multiple_of(2) {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        self.data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+fn forward(policy: FlushPolicy, flaky: bool) -> Sink {
+    let (mut tx, rx) = RingBuffer::<u8, 16>::init();
+    let sink = Sink {
+        flaky,
+        ..Sink::default()
+    };
+    let forwarder = Forwarder::spawn(rx, sink, policy);
+    let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+    let mut src = &data[..];
+    while !src.is_empty() {
+        match tx.write(src) {
+            Ok(n) => src = &src[n..],
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => std::thread::yield_now(),
+            Err(e) => panic!("{e}"),
+        }
+    }
+    drop(tx);
+    let sink = forwarder.join().unwrap();
+    assert_eq!(sink.data, data);
+    sink
+}
+
+#[test]
+fn policies() {
+    let sink = forward(FlushPolicy::Always, false);
+    assert!(sink.flushes >= sink.writes);
+    let sink = forward(FlushPolicy::Never, false);
+    assert_eq!(sink.flushes, 1);
+    let sink = forward(FlushPolicy::OnIdle, false);
+    assert!(sink.flushes >= 1);
+    forward(FlushPolicy::Interval(Duration::from_millis(1)), false);
+}
+
+#[test]
+fn would_block_retried() {
+    forward(FlushPolicy::OnIdle, true);
+}
+
+#[test]
+fn stop_with_writer_alive() {
+    let (mut tx, rx) = RingBuffer::<u8, 16>::init();
+    let forwarder = Forwarder::spawn(rx, Vec::new(), FlushPolicy::Never);
+    tx.write_all(b"abc").unwrap();
+    assert!(!forwarder.is_finished());
+    assert_eq!(forwarder.stop().unwrap(), b"abc");
+    assert_eq!(
+        tx.write(b"d").unwrap_err().kind(),
+        io::ErrorKind::BrokenPipe
+    );
+}
+
+#[test]
+fn sink_error() {
+    struct Broken;
+    impl Write for Broken {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    let (mut tx, rx) = RingBuffer::<u8, 16>::init();
+    let forwarder = Forwarder::spawn(rx, Broken, FlushPolicy::Always);
+    tx.write_all(b"abc").unwrap();
+    let err = forwarder.join().err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+}
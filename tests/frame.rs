@@ -0,0 +1,33 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn frames() {
+    let (mut tx, mut rx) = RingBuffer::<i16, 8>::init();
+    assert_eq!(tx.push_frames(&[[1, 2, 3], [4, 5, 6], [7, 8, 9]]), 2);
+    // Only 2 slots are left, the frame is not split
+    assert_eq!(tx.push_frame([7, 8, 9]), Some([7, 8, 9]));
+    assert!(tx.push(0).is_none());
+
+    assert_eq!(rx.pull_frame(), Some([1, 2, 3]));
+    let mut dst = [[0; 3]; 2];
+    assert_eq!(rx.pull_frames(&mut dst), 1);
+    assert_eq!(dst[0], [4, 5, 6]);
+    // Only 1 element is left
+    assert_eq!(rx.pull_frame::<3>(), None);
+    assert_eq!(rx.pull(), Some(0));
+}
+
+#[test]
+fn wrap_around() {
+    let (mut tx, mut rx) = RingBuffer::<String, 4>::init();
+    // Shift the frames by one slot, so that every other one wraps around
+    assert!(tx.push("x".to_string()).is_none());
+    assert!(rx.pull().is_some());
+    for i in 0..8 {
+        let frame = [i.to_string(), (i + 1).to_string()];
+        assert!(tx.push_frame(frame).is_none());
+        let frame = rx.pull_frame::<2>().unwrap();
+        assert_eq!(frame, [i.to_string(), (i + 1).to_string()]);
+    }
+}
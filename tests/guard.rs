@@ -0,0 +1,65 @@
+#![cfg(all(feature = "guard-pages", unix))]
+use ringbuffer_spsc::RingBuffer;
+use std::os::unix::process::ExitStatusExt;
+
+const CHILD: &str = "RINGBUFFER_SPSC_GUARD_CHILD";
+
+#[test]
+fn push_pull() {
+    const N: usize = 100_000;
+    let (mut tx, mut rx) = RingBuffer::<String, 16>::init_guarded();
+    let p = std::thread::spawn(move || {
+        for i in 0..N {
+            let mut s = i.to_string();
+            while let Some(back) = tx.push(s) {
+                s = back;
+                std::thread::yield_now();
+            }
+        }
+    });
+    let mut current = 0;
+    while current < N {
+        match rx.pull() {
+            Some(s) => {
+                assert_eq!(s, current.to_string());
+                current += 1;
+            }
+            None => std::thread::yield_now(),
+        }
+    }
+    p.join().unwrap();
+
+    // The elements left over are dropped with the mapping
+    let (mut tx, rx) = RingBuffer::<String, 16>::init_guarded();
+    assert!(tx.push("left over".to_string()).is_none());
+    drop(rx);
+    drop(tx);
+}
+
+/// Write past the slots, beyond the padding of the ring buffer to its
+/// alignment, only when run by `out_of_bounds_faults`
+#[test]
+fn out_of_bounds_child() {
+    if std::env::var_os(CHILD).is_none() {
+        return;
+    }
+    let (mut tx, _rx) = RingBuffer::<u8, 4096>::init_guarded();
+    unsafe {
+        let (ptr, len) = tx.writable_ptr();
+        assert_eq!(len, 4096);
+        ptr.add(len + 128).write_volatile(1);
+    }
+    unreachable!("the write past the slots did not fault");
+}
+
+#[test]
+fn out_of_bounds_faults() {
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "out_of_bounds_child", "--nocapture"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap()
+        .status;
+    // Killed by SIGSEGV, or SIGBUS on some platforms, rather than exiting after a panic
+    assert!(status.signal().is_some(), "{status}");
+}
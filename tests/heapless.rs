@@ -0,0 +1,52 @@
+use ringbuffer_spsc::heapless::Queue;
+
+#[test]
+fn split_threads() {
+    const N: usize = 1_000_000;
+    let mut queue: Queue<usize, 16> = Queue::new();
+    let (mut producer, mut consumer) = queue.split();
+
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            let mut current: usize = 0;
+            while current < N {
+                if producer.enqueue(current).is_ok() {
+                    current = current.wrapping_add(1);
+                } else {
+                    std::thread::yield_now();
+                }
+            }
+        });
+
+        s.spawn(move || {
+            let mut current: usize = 0;
+            while current < N {
+                if let Some(c) = consumer.dequeue() {
+                    assert_eq!(c, current);
+                    current = current.wrapping_add(1);
+                } else {
+                    std::thread::yield_now();
+                }
+            }
+        });
+    });
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn unsplit() {
+    let mut queue: Queue<usize, 2> = Queue::new();
+    assert_eq!(queue.capacity(), 2);
+    assert_eq!(queue.enqueue(1), Ok(()));
+    assert_eq!(queue.enqueue(2), Ok(()));
+    assert!(queue.is_full());
+    assert_eq!(queue.enqueue(3), Err(3));
+    assert_eq!(queue.peek(), Some(&1));
+    assert_eq!(queue.dequeue(), Some(1));
+
+    let (producer, consumer) = queue.split();
+    assert!(producer.ready());
+    assert!(consumer.ready());
+    assert_eq!(producer.len(), 1);
+    assert_eq!(consumer.len(), 1);
+}
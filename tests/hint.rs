@@ -0,0 +1,42 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{Hint, RingBuffer};
+
+#[test]
+fn default_thresholds() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 16>::init();
+    for i in 0..13 {
+        assert_eq!(tx.push_hinted(i), Ok(Hint::Normal));
+    }
+    // From 14 elements out of 16
+    assert_eq!(tx.push_hinted(13), Ok(Hint::NearlyFull));
+    assert_eq!(tx.push_hinted(14), Ok(Hint::NearlyFull));
+    assert_eq!(tx.push_hinted(15), Ok(Hint::NearlyFull));
+    assert_eq!(tx.push_hinted(16), Err(16));
+
+    for i in 0..13 {
+        assert_eq!(rx.pull_hinted(), Some((i, Hint::Normal)));
+    }
+    // Down to 2 elements out of 16
+    assert_eq!(rx.pull_hinted(), Some((13, Hint::NearlyEmpty)));
+    assert_eq!(tx.push_hinted(16), Ok(Hint::Normal));
+    assert_eq!(rx.pull_hinted(), Some((14, Hint::NearlyEmpty)));
+    assert_eq!(rx.pull_hinted(), Some((15, Hint::NearlyEmpty)));
+    assert_eq!(rx.pull_hinted(), Some((16, Hint::NearlyEmpty)));
+    assert_eq!(rx.pull_hinted(), None);
+}
+
+#[test]
+fn stale_indexes() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 4>::init();
+    tx.set_nearly_full(2);
+    rx.set_nearly_empty(0);
+    assert_eq!(tx.push_hinted(0), Ok(Hint::Normal));
+    assert_eq!(rx.pull_hinted(), Some((0, Hint::NearlyEmpty)));
+    // The cached read index is refreshed before reporting
+    assert_eq!(tx.push_hinted(1), Ok(Hint::Normal));
+    assert_eq!(tx.push_hinted(2), Ok(Hint::NearlyFull));
+    assert_eq!(tx.push_hinted(3), Ok(Hint::NearlyFull));
+    assert_eq!(rx.pull_hinted(), Some((1, Hint::Normal)));
+    assert_eq!(rx.pull_hinted(), Some((2, Hint::Normal)));
+    assert_eq!(rx.pull_hinted(), Some((3, Hint::NearlyEmpty)));
+}
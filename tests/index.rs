@@ -0,0 +1,17 @@
+#![cfg(all(feature = "alloc", feature = "u64-indexes"))]
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn past_u32_max() {
+    let start = u64::from(u32::MAX) - 1;
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init_at(start);
+    for i in 0..6 {
+        assert!(tx.push(i).is_none());
+        assert_eq!(rx.pull_indexed(), Some((start + u64::from(i), i)));
+    }
+    // The indexes keep counting instead of wrapping around at 2^32
+    assert_eq!(rx.raw_indices(), (start + 6, start + 6));
+    assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+    let (r, w) = tx.raw_indices();
+    assert_eq!((r, w - r), (start + 6, 3));
+}
@@ -0,0 +1,143 @@
+#![cfg(any(feature = "tokio", feature = "futures-io"))]
+use ringbuffer_spsc::{RingBuffer, RingBufferWriter};
+
+const N: usize = 100_000;
+
+fn produce(mut tx: RingBufferWriter<u8, 16>) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut current: usize = 0;
+        while current < N {
+            if tx.push(current as u8).is_none() {
+                current = current.wrapping_add(1);
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    })
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn tokio_buf_read() {
+    use tokio::io::AsyncBufReadExt;
+
+    let (tx, mut rx) = RingBuffer::<u8, 16>::init();
+    let p = produce(tx);
+
+    let mut current: usize = 0;
+    loop {
+        let buf = rx.fill_buf().await.unwrap();
+        if buf.is_empty() {
+            break;
+        }
+        for b in buf {
+            assert_eq!(*b, current as u8);
+            current = current.wrapping_add(1);
+        }
+        let amt = buf.len();
+        rx.consume(amt);
+    }
+    assert_eq!(current, N);
+    p.join().unwrap();
+}
+
+#[cfg(feature = "futures-io")]
+#[test]
+fn futures_read_to_end() {
+    use futures::AsyncReadExt;
+
+    let (tx, mut rx) = RingBuffer::<u8, 16>::init();
+    let p = produce(tx);
+
+    let mut data = Vec::new();
+    futures::executor::block_on(rx.read_to_end(&mut data)).unwrap();
+    assert_eq!(data.len(), N);
+    assert!(data.iter().enumerate().all(|(i, b)| *b == i as u8));
+    p.join().unwrap();
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn tokio_pipe() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 16>::init();
+    let data: Vec<u8> = (0..N).map(|i| i as u8).collect();
+
+    let w = async {
+        tx.write_all(&data).await.unwrap();
+        drop(tx);
+    };
+    let r = async {
+        let mut out = Vec::new();
+        rx.read_to_end(&mut out).await.unwrap();
+        out
+    };
+    let ((), out) = tokio::join!(w, r);
+    assert_eq!(out, data);
+}
+
+#[cfg(feature = "futures-io")]
+#[test]
+fn futures_pipe() {
+    use futures::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 16>::init();
+    let data: Vec<u8> = (0..N).map(|i| i as u8).collect();
+
+    let w = async {
+        tx.write_all(&data).await.unwrap();
+        drop(tx);
+    };
+    let r = async {
+        let mut out = Vec::new();
+        rx.read_to_end(&mut out).await.unwrap();
+        out
+    };
+    let ((), out) = futures::executor::block_on(futures::future::join(w, r));
+    assert_eq!(out, data);
+
+    let (mut tx, rx) = RingBuffer::<u8, 16>::init();
+    drop(rx);
+    let err = futures::executor::block_on(tx.write(&[0])).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn tokio_write_vectored() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    let writer = tokio::spawn(async move {
+        for _ in 0..100 {
+            let bufs = [std::io::IoSlice::new(b"ab"), std::io::IoSlice::new(b"cde")];
+            let n = tx.write_vectored(&bufs).await.unwrap();
+            assert!(n > 0);
+            tx.write_all(&b"abcde"[n..]).await.unwrap();
+        }
+    });
+    let mut out = Vec::new();
+    rx.read_to_end(&mut out).await.unwrap();
+    writer.await.unwrap();
+    assert_eq!(out, b"abcde".repeat(100));
+}
+
+#[cfg(feature = "futures-io")]
+#[test]
+fn futures_vectored() {
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    futures::executor::block_on(async {
+        let bufs = [std::io::IoSlice::new(b"ab"), std::io::IoSlice::new(b"cde")];
+        assert_eq!(tx.write_vectored(&bufs).await.unwrap(), 4);
+        let (mut a, mut b) = ([0; 1], [0; 8]);
+        let mut bufs = [
+            std::io::IoSliceMut::new(&mut a),
+            std::io::IoSliceMut::new(&mut b),
+        ];
+        assert_eq!(rx.read_vectored(&mut bufs).await.unwrap(), 4);
+        assert_eq!((a, &b[..3]), (*b"a", &b"bcd"[..]));
+    });
+}
@@ -0,0 +1,39 @@
+use core::cell::RefCell;
+use critical_section::Mutex;
+use ringbuffer_spsc::{static_ringbuffer, IsrWriter};
+
+static_ringbuffer!(RB: u8, 8);
+static WRITER: Mutex<RefCell<Option<IsrWriter<u8, 8>>>> = Mutex::new(RefCell::new(None));
+
+fn isr(bytes: &[u8]) -> usize {
+    critical_section::with(|cs| {
+        let mut tx = WRITER.borrow_ref_mut(cs);
+        let tx = tx.as_mut().unwrap();
+        match bytes {
+            [b] => usize::from(tx.push(*b)),
+            _ => tx.push_slice(bytes),
+        }
+    })
+}
+
+#[test]
+fn drops() {
+    let (tx, mut rx) = RB.split();
+    critical_section::with(|cs| WRITER.replace(cs, Some(IsrWriter::new(tx))));
+
+    assert_eq!(isr(b"hello"), 5);
+    assert_eq!(isr(b" world"), 3);
+    assert_eq!(isr(b"!"), 0);
+    let (first, second) = rx.peek_slice();
+    assert_eq!([first, second].concat(), b"hello wo");
+    assert_eq!(rx.pull(), Some(b'h'));
+    assert_eq!(isr(b"!"), 1);
+
+    let mut tx = critical_section::with(|cs| WRITER.take(cs)).unwrap();
+    assert_eq!(tx.dropped(), 4);
+    assert_eq!(tx.take_dropped(), 4);
+    assert_eq!(tx.dropped(), 0);
+    let (first, second) = rx.peek_slice();
+    assert_eq!([first, second].concat(), b"ello wo!");
+    drop(tx.into_inner());
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::local::RingBuffer;
+use std::rc::Rc;
+
+#[test]
+fn push_pull() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 4>::init();
+    for lap in 0..10 {
+        for i in 0..4 {
+            assert!(tx.push(lap * 4 + i).is_none());
+        }
+        assert_eq!(tx.push(0), Some(0));
+        assert_eq!(rx.len(), 4);
+        for i in 0..4 {
+            assert_eq!(rx.pull(), Some(lap * 4 + i));
+        }
+        assert!(rx.is_empty());
+        assert_eq!(tx.slots(), 4);
+    }
+}
+
+#[test]
+fn generator() {
+    // Producer and consumer interleaved on the same thread
+    let (mut tx, mut rx) = RingBuffer::<u64, 2>::init();
+    let (mut a, mut b) = (0, 1);
+    let mut fib = Vec::new();
+    while fib.len() < 10 {
+        while tx.push(a).is_none() {
+            (a, b) = (b, a + b);
+        }
+        while let Some(x) = rx.pull() {
+            fib.push(x);
+        }
+    }
+    assert_eq!(fib[..10], [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+}
+
+#[test]
+fn drop_elements() {
+    let item = Rc::new(());
+    let (mut tx, rx) = RingBuffer::<Rc<()>, 4>::init();
+    tx.push(item.clone());
+    tx.push(item.clone());
+    drop(rx);
+    assert!(tx.is_reader_dropped());
+    assert_eq!(Rc::strong_count(&item), 3);
+    drop(tx);
+    assert_eq!(Rc::strong_count(&item), 1);
+}
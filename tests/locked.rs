@@ -0,0 +1,57 @@
+#![cfg(feature = "std")]
+use ringbuffer_spsc::{RingBuffer, SharedWriter};
+use std::sync::Arc;
+
+#[test]
+fn two_producers() {
+    const N: usize = 10_000;
+    let (tx, mut rx) = RingBuffer::<(u8, usize), 16>::init();
+    let tx = Arc::new(SharedWriter::new(tx));
+
+    let producers: Vec<_> = (0..2u8)
+        .map(|p| {
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                for i in 0..N {
+                    while tx.push((p, i)).is_some() {
+                        std::thread::yield_now();
+                    }
+                }
+            })
+        })
+        .collect();
+    drop(tx);
+
+    // Each producer's elements come out in order
+    let mut next = [0; 2];
+    while next != [N; 2] {
+        match rx.pull() {
+            Some((p, i)) => {
+                assert_eq!(i, next[p as usize]);
+                next[p as usize] += 1;
+            }
+            None => std::thread::yield_now(),
+        }
+    }
+    for p in producers {
+        p.join().unwrap();
+    }
+}
+
+#[test]
+fn lock_and_unwrap() {
+    let (tx, mut rx) = RingBuffer::<u32, 4>::init();
+    let tx = SharedWriter::from(tx);
+    {
+        let mut tx = tx.lock();
+        assert!(tx.push(1).is_none());
+        assert!(tx.push(2).is_none());
+    }
+    assert_eq!(tx.push_slice(&[3, 4, 5]), 2);
+    assert_eq!(tx.push(6), Some(6));
+
+    let mut tx = tx.into_inner();
+    assert_eq!(rx.pull_slice(&mut [0; 4]), 4);
+    assert!(tx.push(7).is_none());
+    drop(rx);
+}
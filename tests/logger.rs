@@ -0,0 +1,68 @@
+#![cfg(feature = "log")]
+use log::{Level, LevelFilter, Log, Record};
+use ringbuffer_spsc::logger::logger;
+use std::time::Duration;
+
+fn log(logger: &impl Log, level: Level, msg: &str) {
+    logger.log(
+        &Record::builder()
+            .level(level)
+            .target("app")
+            .args(format_args!("{msg}"))
+            .build(),
+    );
+}
+
+#[test]
+fn drain() {
+    let (logger, mut drain) = logger::<64>(LevelFilter::Info);
+    log(&logger, Level::Info, "one");
+    log(&logger, Level::Debug, "filtered");
+    log(&logger, Level::Warn, "two");
+
+    let mut out = Vec::new();
+    assert_eq!(drain.drain(&mut out).unwrap(), 2);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "INFO app: one\nWARN app: two\n"
+    );
+
+    // Records not fitting are dropped as a whole
+    log(&logger, Level::Error, &"x".repeat(40));
+    log(&logger, Level::Error, &"y".repeat(40));
+    assert_eq!(logger.dropped(), 1);
+    let mut out = Vec::new();
+    assert_eq!(drain.drain(&mut out).unwrap(), 1);
+    assert!(out.ends_with(b"xxx\n"));
+}
+
+#[test]
+fn spawn() {
+    let (logger, drain) = logger::<4096>(LevelFilter::Trace);
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    struct Out(std::sync::mpsc::Sender<Vec<u8>>);
+    impl std::io::Write for Out {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.send(buf.to_vec()).unwrap();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let t = drain.spawn(Out(tx), Duration::from_millis(1));
+    for i in 0..100 {
+        log(&logger, Level::Trace, &i.to_string());
+        std::thread::sleep(Duration::from_micros(100));
+    }
+    assert_eq!(logger.dropped(), 0);
+    drop(logger);
+    t.join().unwrap().unwrap();
+
+    let out: Vec<u8> = rx.iter().flatten().collect();
+    let lines = String::from_utf8(out).unwrap();
+    let expected: String = (0..100).map(|i| format!("TRACE app: {i}\n")).collect();
+    assert_eq!(lines, expected);
+}
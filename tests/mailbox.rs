@@ -0,0 +1,99 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{static_ringbuffer, ChunkError, Hint, Index, RingBuffer};
+
+static_ringbuffer!(RB: usize, 1);
+
+#[test]
+fn single_slot() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 1>::init();
+    assert_eq!(RingBuffer::<usize, 1>::MASK, 0);
+    for i in 0..10 {
+        assert_eq!(tx.slots(), 1);
+        assert!(tx.push(i).is_none());
+        assert_eq!(tx.push(i + 1), Some(i + 1));
+        assert_eq!(tx.slots(), 0);
+        assert_eq!(rx.len(), 1);
+        assert_eq!(rx.peek(), Some(&i));
+        assert_eq!(rx.pull(), Some(i));
+        assert_eq!(rx.pull(), None);
+        assert!(rx.is_empty());
+    }
+}
+
+#[test]
+fn wrap_around() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 1>::init_at(Index::MAX - 2);
+    for i in 0..6 {
+        assert!(tx.push(i).is_none());
+        assert!(tx.push(i).is_some());
+        assert_eq!(rx.pull(), Some(i));
+    }
+}
+
+#[test]
+fn chunks() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 1>::init();
+    assert_eq!(tx.write_chunk(2).err(), Some(ChunkError::TooFewSlots(1)));
+    let mut chunk = tx.write_chunk(1).unwrap();
+    chunk.as_mut_slices().0[0] = 7;
+    chunk.commit_all();
+    assert_eq!(tx.write_chunk(1).err(), Some(ChunkError::TooFewSlots(0)));
+    assert_eq!(
+        rx.read_chunk(1).unwrap().into_iter().collect::<Vec<_>>(),
+        [7]
+    );
+    assert_eq!(rx.read_chunk(1).err(), Some(ChunkError::TooFewSlots(0)));
+}
+
+#[test]
+fn hints() {
+    // A single element makes the mailbox both nearly full and nearly empty
+    let (mut tx, mut rx) = RingBuffer::<usize, 1>::init();
+    assert_eq!(tx.push_hinted(1), Ok(Hint::NearlyFull));
+    assert_eq!(rx.pull_hinted(), Some((1, Hint::NearlyEmpty)));
+}
+
+#[test]
+fn static_mailbox() {
+    let (mut tx, mut rx) = RB.split();
+    assert!(tx.push(1).is_none());
+    assert_eq!(tx.push(2), Some(2));
+    assert_eq!(rx.pull(), Some(1));
+}
+
+#[test]
+fn local_mailbox() {
+    let (mut tx, mut rx) = ringbuffer_spsc::local::RingBuffer::<usize, 1>::init();
+    assert!(tx.push(1).is_none());
+    assert_eq!(tx.push(2), Some(2));
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(rx.pull(), None);
+}
+
+#[test]
+fn ping_pong() {
+    const N: usize = 10_000;
+    let (mut tx, mut rx) = RingBuffer::<usize, 1>::init();
+
+    let p = std::thread::spawn(move || {
+        let mut current = 0;
+        while current < N {
+            if tx.push(current).is_none() {
+                current += 1;
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    });
+
+    let mut current = 0;
+    while current < N {
+        if let Some(c) = rx.pull() {
+            assert_eq!(c, current);
+            current += 1;
+        } else {
+            std::thread::yield_now();
+        }
+    }
+    p.join().unwrap();
+}
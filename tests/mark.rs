@@ -0,0 +1,41 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{Index, RingBuffer};
+
+#[test]
+fn rollback() {
+    let (mut tx, mut rx) = RingBuffer::<String, 4>::init();
+    for s in ["a", "b", "c"] {
+        tx.push(s.to_string());
+    }
+
+    let mut mark = rx.mark();
+    assert_eq!(mark.pull().map(String::as_str), Some("a"));
+    assert_eq!(mark.pull().map(String::as_str), Some("b"));
+    assert_eq!(mark.pulled(), 2);
+    mark.rollback();
+    assert_eq!(mark.pulled(), 0);
+    assert_eq!(mark.pull().map(String::as_str), Some("a"));
+
+    // Nothing has been consumed without committing
+    assert_eq!(rx.len(), 3);
+    assert!(tx.push("d".to_string()).is_none());
+    assert!(tx.push("e".to_string()).is_some());
+
+    let mut mark = rx.mark();
+    mark.pull();
+    mark.pull();
+    mark.commit();
+    assert_eq!(rx.pull().as_deref(), Some("c"));
+    assert!(tx.push("e".to_string()).is_none());
+}
+
+#[test]
+fn wrap_around() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init_at(Index::MAX - 1);
+    tx.push_slice(&[1, 2, 3, 4]);
+    let mut mark = rx.mark();
+    let pulled: Vec<_> = std::iter::from_fn(|| mark.pull().copied()).collect();
+    assert_eq!(pulled, [1, 2, 3, 4]);
+    mark.commit();
+    assert_eq!(tx.push_slice(&[5, 6, 7, 8]), 4);
+}
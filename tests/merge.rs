@@ -0,0 +1,94 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn merge_when_full() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 2>::init();
+    for i in 1..=5 {
+        assert!(tx.push_or_merge(i, |last, t| *last += t).is_none());
+    }
+    // 1 is published, 2 + 3 + 4 + 5 merged into the last slot
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(rx.pull(), None);
+    assert!(tx.publish_pending());
+    assert!(!tx.publish_pending());
+    assert_eq!(rx.pull(), Some(14));
+
+    // Published elements are never merged into
+    for i in 0..2 {
+        assert!(tx.push(i).is_none());
+    }
+    assert_eq!(tx.push_or_merge(7, |_, _| unreachable!()), Some(7));
+}
+
+#[test]
+fn published_by_next_write() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 2>::init();
+    assert!(tx.push_or_merge(1, |_, _| unreachable!()).is_none());
+    assert!(tx.push_or_merge(2, |_, _| unreachable!()).is_none());
+    assert_eq!(rx.pull(), Some(1));
+    // The pending element is published along with the new one
+    assert!(tx.push(3).is_none());
+    assert_eq!(rx.pull(), Some(2));
+    assert_eq!(rx.pull(), Some(3));
+
+    assert!(tx.push_or_merge(4, |_, _| unreachable!()).is_none());
+    assert!(tx.push_or_merge(5, |_, _| unreachable!()).is_none());
+    assert_eq!(rx.pull(), Some(4));
+    // A new element taking the last slot publishes the previous one
+    assert!(tx.push_or_merge(6, |_, _| unreachable!()).is_none());
+    assert_eq!(rx.pull(), Some(5));
+    assert_eq!(rx.pull(), None);
+}
+
+#[test]
+fn published_on_drop() {
+    let (mut tx, mut rx) = RingBuffer::<String, 1>::init();
+    assert!(tx
+        .push_or_merge("a".into(), |_, _| unreachable!())
+        .is_none());
+    assert!(tx
+        .push_or_merge("b".into(), |last, t| *last += &t)
+        .is_none());
+    assert_eq!(rx.pull(), None);
+    drop(tx);
+    assert_eq!(rx.pull().as_deref(), Some("ab"));
+}
+
+#[test]
+fn merge_threads() {
+    const N: u64 = 100_000;
+    let (mut tx, mut rx) = RingBuffer::<u64, 4>::init();
+
+    let p = std::thread::spawn(move || {
+        for i in 1..=N {
+            assert!(tx.push_or_merge(i, |last, t| *last += t).is_none());
+        }
+    });
+
+    // Every element is pulled once the writer is dropped
+    let mut sum = 0;
+    while sum < N * (N + 1) / 2 {
+        if let Some(t) = rx.pull() {
+            sum += t;
+        } else {
+            std::thread::yield_now();
+        }
+    }
+    p.join().unwrap();
+    assert_eq!(rx.pull(), None);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn publish_pending_wakes_reader() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 1>::init();
+    let t = std::thread::spawn(move || {
+        assert!(tx.push_or_merge(1, |_, _| unreachable!()).is_none());
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(tx.publish_pending());
+        tx
+    });
+    assert_eq!(futures::executor::block_on(rx.pull_async()), Some(1));
+    t.join().unwrap();
+}
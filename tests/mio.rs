@@ -0,0 +1,42 @@
+#![cfg(all(feature = "mio", target_os = "linux"))]
+use mio::{Events, Interest, Poll, Token};
+use ringbuffer_spsc::RingBuffer;
+use std::time::Duration;
+
+#[test]
+fn poll() {
+    const N: u32 = 10_000;
+    let (mut tx, mut rx) = RingBuffer::<u32, 16>::init();
+    let mut poll = Poll::new().unwrap();
+    poll.registry()
+        .register(&mut rx, Token(7), Interest::READABLE)
+        .unwrap();
+
+    let p = std::thread::spawn(move || {
+        for i in 0..N {
+            while tx.push(i).is_some() {
+                std::thread::yield_now();
+            }
+        }
+    });
+
+    let mut events = Events::with_capacity(4);
+    let mut current = 0;
+    loop {
+        while let Some(i) = rx.pull() {
+            assert_eq!(i, current);
+            current += 1;
+        }
+        if rx.arm_eventfd() {
+            poll.poll(&mut events, Some(Duration::from_secs(10)))
+                .unwrap();
+            // A lost wake up would time out
+            assert_eq!(events.iter().next().map(|e| e.token()), Some(Token(7)));
+        } else if rx.is_empty() {
+            break;
+        }
+    }
+    assert_eq!(current, N);
+    p.join().unwrap();
+    poll.registry().deregister(&mut rx).unwrap();
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "std")]
+use ringbuffer_spsc::{
+    notify::{Event, Notifier, NotifyReader, NotifyWriter},
+    RingBuffer,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+#[test]
+fn threads() {
+    const N: usize = 100_000;
+    let (data, space) = (Arc::new(Event::new()), Arc::new(Event::new()));
+    let (tx, rx) = RingBuffer::<usize, 8>::init();
+    let mut tx = NotifyWriter::new(tx, data.clone(), space.clone());
+    let mut rx = NotifyReader::new(rx, data, space);
+
+    let t = std::thread::spawn(move || {
+        for i in 0..N {
+            tx.push_blocking(i).unwrap();
+        }
+    });
+    for i in 0..N {
+        assert_eq!(rx.pull_blocking(), Some(i));
+    }
+    assert_eq!(rx.pull_blocking(), None);
+    t.join().unwrap();
+}
+
+#[test]
+fn dropped() {
+    let (data, space) = (Arc::new(Event::new()), Arc::new(Event::new()));
+    let (tx, rx) = RingBuffer::<usize, 1>::init();
+    let mut tx = NotifyWriter::new(tx, data.clone(), space.clone());
+    let rx = NotifyReader::new(rx, data, space);
+
+    assert_eq!(tx.push(0), None);
+    let t = std::thread::spawn(move || drop(rx));
+    // Woken up by the reader being dropped
+    assert_eq!(tx.push_blocking(1), Err(1));
+    t.join().unwrap();
+}
+
+/// Counts the signals, and never actually blocks.
+#[derive(Default)]
+struct Counter {
+    signals: AtomicUsize,
+}
+
+impl Notifier for Counter {
+    fn signal(&self) {
+        self.signals.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn wait(&self) {
+        std::thread::yield_now();
+    }
+}
+
+#[test]
+fn signals() {
+    let (data, space) = (Counter::default(), Counter::default());
+    let (tx, rx) = RingBuffer::<usize, 2>::init();
+    let mut tx = NotifyWriter::new(tx, &data, &space);
+    let mut rx = NotifyReader::new(rx, &data, &space);
+
+    assert_eq!(tx.push(0), None);
+    assert_eq!(tx.push(1), None);
+    assert_eq!(tx.push(2), Some(2));
+    assert_eq!(data.signals.load(Ordering::Relaxed), 2);
+    assert_eq!(rx.pull(), Some(0));
+    assert_eq!(space.signals.load(Ordering::Relaxed), 1);
+
+    drop(tx);
+    assert_eq!(data.signals.load(Ordering::Relaxed), 3);
+    assert_eq!(rx.pull_blocking(), Some(1));
+    assert_eq!(rx.pull_blocking(), None);
+    drop(rx);
+    assert_eq!(space.signals.load(Ordering::Relaxed), 3);
+}
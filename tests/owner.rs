@@ -0,0 +1,22 @@
+#![cfg(feature = "owner-check")]
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn moved_before_use() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    std::thread::spawn(move || assert!(tx.push(1).is_none()))
+        .join()
+        .unwrap();
+    assert_eq!(rx.pull(), Some(1));
+}
+
+#[test]
+fn used_from_another_thread() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    assert_eq!(rx.pull(), None);
+    tx.push(1);
+    let r = std::thread::spawn(move || rx.pull()).join();
+    assert!(r.is_err());
+    // The writer is still fine on its own thread
+    assert!(tx.push(2).is_none());
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "alloc")]
+use core::mem::{align_of, size_of};
+use ringbuffer_spsc::{CachePadded, RingBuffer};
+
+#[test]
+fn cache_lines() {
+    let line = if cfg!(feature = "cache-line-128") {
+        128
+    } else {
+        64
+    };
+    assert_eq!(align_of::<RingBuffer<u8, 1>>(), line);
+    // Each index has a line of its own
+    assert!(size_of::<RingBuffer<u8, 1>>() >= 2 * line);
+}
+
+#[test]
+fn padded_slots() {
+    let line = align_of::<CachePadded<u8>>();
+    assert_eq!(size_of::<CachePadded<u8>>(), line);
+    assert_eq!(
+        size_of::<CachePadded<[u8; 65]>>(),
+        65usize.next_multiple_of(line)
+    );
+
+    let (mut tx, mut rx) = RingBuffer::<CachePadded<u32>, 4>::init();
+    assert_eq!(rx.peek_mut(), None);
+    assert!(tx.push(1.into()).is_none());
+    assert!(tx.push(2.into()).is_none());
+    **rx.peek_mut().unwrap() += 10;
+    assert_eq!(rx.pull().map(CachePadded::into_inner), Some(11));
+    assert_eq!(rx.pull().map(CachePadded::into_inner), Some(2));
+}
@@ -0,0 +1,147 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::RingBuffer;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+#[test]
+fn push_with() {
+    let (mut tx, mut rx) = RingBuffer::<String, 2>::init();
+    let r = catch_unwind(AssertUnwindSafe(|| tx.push_with(|| panic!("boom"))));
+    assert!(r.is_err());
+    assert!(rx.pull().is_none());
+
+    // The slot is still free
+    assert!(tx.push_with(|| "a".to_string()).is_ok());
+    assert!(tx.push_with(|| "b".to_string()).is_ok());
+    assert!(tx.push_with(|| unreachable!()).is_err());
+    assert_eq!(rx.pull().as_deref(), Some("a"));
+    assert_eq!(rx.pull().as_deref(), Some("b"));
+}
+
+#[test]
+fn fill_from_iter() {
+    let rc = Rc::new(());
+    let (mut tx, mut rx) = RingBuffer::<Rc<()>, 4>::init();
+    let r = catch_unwind(AssertUnwindSafe(|| {
+        let chunk = tx.write_chunk_uninit(4).unwrap();
+        chunk.fill_from_iter((0..4).map(|i| match i {
+            2 => panic!("boom"),
+            _ => rc.clone(),
+        }))
+    }));
+    assert!(r.is_err());
+    assert!(rx.pull().is_none());
+    // The elements moved into the chunk before the panic have been dropped
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    assert_eq!(tx.push_all((0..4).map(|_| rc.clone())).count(), 0);
+    drop((tx, rx));
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn write_chunk() {
+    thread_local!(static DEFAULTS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) });
+
+    struct Flaky(#[allow(dead_code)] String);
+
+    impl Default for Flaky {
+        fn default() -> Self {
+            let n = DEFAULTS.with(|d| d.replace(d.get() + 1));
+            assert!(n != 2, "boom");
+            Flaky(n.to_string())
+        }
+    }
+
+    let (mut tx, mut rx) = RingBuffer::<Flaky, 4>::init();
+    assert!(catch_unwind(AssertUnwindSafe(|| tx.write_chunk(4).map(drop))).is_err());
+    assert!(rx.pull().is_none());
+
+    tx.write_chunk(4).unwrap().commit(1);
+    assert_eq!(rx.len(), 1);
+}
+
+#[test]
+fn unwind_safe() {
+    use ringbuffer_spsc::{RingBufferReader, RingBufferWriter};
+    use std::panic::UnwindSafe;
+
+    fn assert_unwind_safe<T: UnwindSafe>() {}
+    assert_unwind_safe::<RingBufferWriter<String, 4>>();
+    assert_unwind_safe::<RingBufferReader<String, 4>>();
+    assert_unwind_safe::<RingBufferWriter<String, 4, &RingBuffer<String, 4>>>();
+
+    // The handles can be moved into a closure without AssertUnwindSafe
+    let (mut tx, mut rx) = RingBuffer::<String, 4>::init();
+    let r = catch_unwind(move || {
+        tx.push("a".to_string());
+        tx.push_with(|| -> String { panic!("boom") }).ok();
+    });
+    assert!(r.is_err());
+    assert_eq!(rx.pull().as_deref(), Some("a"));
+    assert!(rx.pull().is_none());
+}
+
+#[test]
+fn panicking_thread() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 4>::init();
+    let t = std::thread::spawn(move || {
+        tx.push(1);
+        tx.push(2);
+        panic!("boom");
+    });
+    assert!(t.join().is_err());
+
+    // The elements pushed before the panic can still be pulled
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(rx.pull(), Some(2));
+    assert!(rx.pull().is_none());
+}
+
+#[test]
+fn push_slice_cloned() {
+    struct Bomb(bool);
+
+    impl Clone for Bomb {
+        fn clone(&self) -> Self {
+            assert!(!self.0, "boom");
+            Bomb(false)
+        }
+    }
+
+    let (mut tx, mut rx) = RingBuffer::<Bomb, 4>::init();
+    let src = [Bomb(false), Bomb(true), Bomb(false)];
+    assert!(catch_unwind(AssertUnwindSafe(|| tx.push_slice_cloned(&src))).is_err());
+    assert!(rx.pull().is_none());
+    assert_eq!(tx.push_slice_cloned(&src[..1]), 1);
+    assert!(rx.pull().is_some());
+}
+
+#[test]
+fn push_slice_cloned_drops_clones() {
+    thread_local!(static DROPS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) });
+
+    struct Bomb(bool);
+
+    impl Clone for Bomb {
+        fn clone(&self) -> Self {
+            assert!(!self.0, "boom");
+            Bomb(false)
+        }
+    }
+
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            DROPS.with(|d| d.set(d.get() + 1));
+        }
+    }
+
+    let (mut tx, mut rx) = RingBuffer::<Bomb, 4>::init();
+    let src = [Bomb(false), Bomb(false), Bomb(true)];
+    assert!(catch_unwind(AssertUnwindSafe(|| tx.push_slice_cloned(&src))).is_err());
+    // The two clones made before the panic have been dropped
+    assert_eq!(DROPS.with(|d| d.get()), 2);
+    assert!(rx.pull().is_none());
+    drop(src);
+    assert_eq!(DROPS.with(|d| d.get()), 5);
+}
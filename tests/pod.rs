@@ -0,0 +1,52 @@
+#![cfg(feature = "bytemuck")]
+use bytemuck::{Pod, Zeroable};
+use ringbuffer_spsc::RingBuffer;
+
+#[derive(Debug, Copy, Clone, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+struct Header {
+    id: u32,
+    len: u16,
+    flags: u16,
+}
+
+#[test]
+fn headers_wrap_around() {
+    let (mut tx, mut rx) = RingBuffer::<Header, 4>::init();
+    let headers: Vec<Header> = (0..10)
+        .map(|i| Header {
+            id: i,
+            len: i as u16 * 3,
+            flags: 0xA5A5,
+        })
+        .collect();
+    let bytes: &[u8] = bytemuck::cast_slice(&headers);
+
+    // Unaligned source and destination
+    let mut src = vec![0; bytes.len() + 1];
+    src[1..].copy_from_slice(bytes);
+    let mut dst = vec![0; bytes.len() + 1];
+    let (mut pushed, mut pulled) = (0, 0);
+    while pulled < bytes.len() {
+        pushed += tx.push_bytes(&src[1 + pushed..]);
+        let end = dst.len().min(1 + pulled + 12);
+        pulled += rx.pull_bytes(&mut dst[1 + pulled..end]);
+    }
+    assert_eq!(&dst[1..], bytes);
+}
+
+#[test]
+fn whole_elements_only() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    assert_eq!(tx.push_bytes(&[1; 3]), 0);
+    assert_eq!(tx.push_bytes(&[1; 7]), 4);
+    assert_eq!(tx.push_bytes(&[2; 32]), 12);
+    assert_eq!(tx.push_bytes(&[3; 4]), 0);
+
+    let mut dst = [0; 7];
+    assert_eq!(rx.pull_bytes(&mut dst[..3]), 0);
+    assert_eq!(rx.pull_bytes(&mut dst), 4);
+    assert_eq!(dst[..4], [1; 4]);
+    assert_eq!(rx.pull(), Some(u32::from_ne_bytes([2; 4])));
+    assert_eq!(rx.len(), 2);
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "alloc")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+use ringbuffer_spsc::{
+    Hint, Index, RingBuffer, RingBufferReader, RingBufferWriter, WriterMetadata,
+};
+
+#[test]
+fn dma() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+
+    // Simulate a device filling the free region
+    let (ptr, len) = unsafe { tx.writable_ptr() };
+    assert_eq!(len, 8);
+    unsafe {
+        core::ptr::copy_nonoverlapping([1, 2, 3, 4, 5, 6].as_ptr(), ptr, 6);
+        tx.publish(6);
+    }
+
+    let (ptr, len) = unsafe { rx.readable_ptr() };
+    assert_eq!(len, 6);
+    assert_eq!(unsafe { core::slice::from_raw_parts(ptr, 4) }, [1, 2, 3, 4]);
+    unsafe { rx.release(4) };
+
+    // The free region stops at the end of the buffer, then restarts at its beginning
+    let (ptr, len) = unsafe { tx.writable_ptr() };
+    assert_eq!(len, 2);
+    unsafe {
+        ptr.write_bytes(0, 2);
+        tx.publish(2);
+    }
+    assert_eq!(unsafe { tx.writable_ptr() }.1, 4);
+    assert_eq!(unsafe { rx.readable_ptr() }.1, 4);
+}
+
+#[test]
+fn raw_parts() {
+    let (tx, rx) = RingBuffer::<String, 4>::init();
+
+    // Cross the thread boundary as an opaque pointer, like a C callback user data
+    struct UserData(*const (), WriterMetadata);
+    unsafe impl Send for UserData {}
+
+    let p = std::thread::spawn(move || {
+        let (ptr, metadata) = tx.into_raw_parts();
+        UserData(ptr.cast(), metadata)
+    });
+    let UserData(ptr, metadata) = p.join().unwrap();
+    assert_eq!(metadata.index(), 0);
+    let mut tx = unsafe { RingBufferWriter::<String, 4>::from_raw_parts(ptr.cast(), metadata) };
+    assert!(tx.push("a".to_string()).is_none());
+
+    let (ptr, metadata) = rx.into_raw_parts();
+    let mut rx = unsafe { RingBufferReader::<String, 4>::from_raw_parts(ptr, metadata) };
+    assert_eq!(rx.pull().as_deref(), Some("a"));
+    assert!(tx.push("b".to_string()).is_none());
+    assert_eq!(rx.pull().as_deref(), Some("b"));
+}
+
+#[test]
+fn raw_parts_configuration() {
+    static OVERRUNS: AtomicUsize = AtomicUsize::new(0);
+    static UNDERRUNS: AtomicUsize = AtomicUsize::new(0);
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    tx.set_soft_limit(4);
+    tx.set_nearly_full(2);
+    tx.set_overrun_callback(Some(|| {
+        OVERRUNS.fetch_add(1, Ordering::Relaxed);
+    }));
+    tx.set_watermark(0);
+    rx.set_nearly_empty(3);
+    rx.set_underrun_callback(Some(|| {
+        UNDERRUNS.fetch_add(1, Ordering::Relaxed);
+    }));
+
+    let (ptr, metadata) = tx.into_raw_parts();
+    let mut tx = unsafe { RingBufferWriter::from_raw_parts(ptr, metadata) };
+    let (ptr, metadata) = rx.into_raw_parts();
+    let mut rx = unsafe { RingBufferReader::from_raw_parts(ptr, metadata) };
+
+    assert_eq!(tx.soft_limit(), 4);
+    assert!(tx.watermark_reached());
+    assert_eq!(tx.push_hinted(1), Ok(Hint::Normal));
+    assert_eq!(tx.push_hinted(2), Ok(Hint::NearlyFull));
+    assert_eq!(tx.push_slice(&[3, 4, 5]), 2);
+    assert_eq!(tx.push(5), Some(5));
+    assert_eq!(OVERRUNS.load(Ordering::Relaxed), 1);
+    assert_eq!(rx.pull_hinted(), Some((1, Hint::NearlyEmpty)));
+    for _ in 0..3 {
+        assert!(rx.pull().is_some());
+    }
+    assert_eq!(rx.pull(), None);
+    assert_eq!(UNDERRUNS.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn raw_indices() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init_at(Index::MAX - 1);
+    assert_eq!(tx.raw_indices(), (Index::MAX - 1, Index::MAX - 1));
+
+    tx.push_slice(&[1, 2, 3]);
+    rx.pull();
+    let (r, w) = rx.raw_indices();
+    assert_eq!((r, w), (Index::MAX, 1));
+    assert_eq!(tx.raw_indices(), (r, w));
+    // Backlog computed by a monitor
+    assert_eq!(w.wrapping_sub(r), 2);
+}
+
+#[test]
+fn custom_protocol() {
+    // Records of a length followed by as many elements, published at once
+    let rb = RingBuffer::<u32, 8>::empty();
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            for len in 1..=5u32 {
+                let idx_w = rb.load_write_index();
+                let total = len as Index + 1;
+                while idx_w.wrapping_sub(rb.load_read_index()) + total > 8 {
+                    std::thread::yield_now();
+                }
+                unsafe {
+                    rb.slot_ptr(idx_w).write(len);
+                    for i in 0..len as Index {
+                        rb.slot_ptr(idx_w + 1 + i).write(len * 10 + i as u32);
+                    }
+                    rb.store_write_index(idx_w + total);
+                }
+            }
+        });
+
+        for len in 1..=5u32 {
+            let idx_r = rb.load_read_index();
+            while rb.load_write_index() == idx_r {
+                std::thread::yield_now();
+            }
+            // The whole record is visible once its header is
+            let header = unsafe { rb.slot_ptr(idx_r).read() };
+            assert_eq!(header, len);
+            for i in 0..len as Index {
+                assert_eq!(
+                    unsafe { rb.slot_ptr(idx_r + 1 + i).read() },
+                    len * 10 + i as u32
+                );
+            }
+            unsafe { rb.store_read_index(idx_r + 1 + len as Index) };
+        }
+    });
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "ringbuf")]
+use ::ringbuf::traits::{Consumer, Observer, Producer};
+use ringbuffer_spsc::{ringbuf, RingBuffer};
+
+// Written against the ringbuf traits only
+fn produce<P: Producer<Item = usize>>(p: &mut P, n: usize) {
+    let mut current: usize = 0;
+    while current < n {
+        if p.try_push(current).is_ok() {
+            current += 1;
+        } else {
+            std::thread::yield_now();
+        }
+    }
+}
+
+fn consume<C: Consumer<Item = usize>>(c: &mut C, n: usize) {
+    let mut current: usize = 0;
+    let mut buf = [0; 8];
+    while current < n {
+        let len = c.pop_slice(&mut buf);
+        for b in &buf[..len] {
+            assert_eq!(*b, current);
+            current += 1;
+        }
+        if len == 0 {
+            std::thread::yield_now();
+        }
+    }
+}
+
+#[test]
+fn traits_threads() {
+    const N: usize = 1_000_000;
+    let (tx, rx) = RingBuffer::<usize, 16>::init();
+    let (mut tx, mut rx) = (ringbuf::Producer::new(tx), ringbuf::Consumer::new(rx));
+    let p = std::thread::spawn(move || produce(&mut tx, N));
+    let c = std::thread::spawn(move || consume(&mut rx, N));
+    p.join().unwrap();
+    c.join().unwrap();
+}
+
+#[test]
+fn into_inner() {
+    let (tx, rx) = RingBuffer::<usize, 4>::init();
+    let (mut tx, mut rx) = (ringbuf::Producer::new(tx), ringbuf::Consumer::new(rx));
+    assert_eq!(tx.push_iter(0..3), 3);
+    assert_eq!(rx.occupied_len(), 3);
+    assert_eq!(rx.try_pop(), Some(0));
+    assert_eq!(tx.vacant_len(), 2);
+    assert!(tx.read_is_held());
+
+    let (mut tx, mut rx) = (tx.into_inner(), rx.into_inner());
+    assert!(tx.push(3).is_none());
+    assert!(tx.push(4).is_none());
+    assert_eq!(tx.push(5), Some(5));
+    assert_eq!(rx.pull(), Some(1));
+    drop(tx);
+
+    let rx = ringbuf::Consumer::new(rx);
+    assert!(!rx.write_is_held());
+    assert_eq!(rx.iter().copied().collect::<Vec<_>>(), [2, 3, 4]);
+}
+
+#[test]
+fn pending_published() {
+    let (mut tx, rx) = RingBuffer::<usize, 1>::init();
+    assert!(tx.push_or_merge(1, |_, _| unreachable!()).is_none());
+    let tx = ringbuf::Producer::new(tx);
+    let mut rx = ringbuf::Consumer::new(rx);
+    drop(tx);
+    assert_eq!(rx.try_pop(), Some(1));
+    assert_eq!(rx.try_pop(), None);
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "rt-assert")]
+use ringbuffer_spsc::{rt::RtAllocator, RingBuffer};
+use std::alloc::System;
+
+#[global_allocator]
+static ALLOC: RtAllocator<System> = RtAllocator::new(System);
+
+#[test]
+fn no_alloc() {
+    let (mut tx, mut rx) = RingBuffer::<u64, 4>::init();
+    for i in 0..16 {
+        tx.push(i);
+        assert_eq!(rx.pull(), Some(i));
+    }
+    // Allocating outside of the realtime sections is fine
+    let v: Vec<_> = (0..4).collect();
+    assert_eq!(tx.push_all(v).count(), 0);
+}
+
+// Aborts, only run as a child process by `alloc_in_callback`
+#[test]
+fn alloc_in_callback_child() {
+    if std::env::var_os("RT_ASSERT_CHILD").is_none() {
+        return;
+    }
+    let (mut tx, _rx) = RingBuffer::<u64, 1>::init();
+    tx.set_overrun_callback(Some(|| drop(vec![0u8; 16])));
+    tx.push(0);
+    tx.push(1);
+}
+
+#[test]
+fn alloc_in_callback() {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let out = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "alloc_in_callback_child"])
+        .env("RT_ASSERT_CHILD", "1")
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("allocation in a realtime section"));
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn latest_unread() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    // SAFETY: the reader never updates elements in place
+    assert_eq!(unsafe { tx.sample_latest() }, None);
+    assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+    assert_eq!(rx.sample_latest(), Some(3));
+    assert_eq!(rx.len(), 3);
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(unsafe { tx.sample_latest() }, Some(3));
+    assert_eq!(rx.pull_slice(&mut [0; 4]), 2);
+    assert_eq!(unsafe { tx.sample_latest() }, None);
+    assert_eq!(rx.sample_latest(), None);
+}
+
+#[test]
+fn shared_state() {
+    let rb = RingBuffer::<u32, 4>::empty();
+    let (mut tx, mut rx) = rb.split();
+    // SAFETY: the halves are not used during the calls
+    assert_eq!(unsafe { rb.sample_latest() }, None);
+    assert!(tx.push(1).is_none());
+    assert!(tx.push(2).is_none());
+    assert_eq!(unsafe { rb.sample_latest() }, Some(2));
+    assert_eq!(rx.pull_slice(&mut [0; 4]), 2);
+    assert_eq!(unsafe { rb.sample_latest() }, None);
+}
+
+#[test]
+fn concurrent_sampling() {
+    const N: u64 = 200_000;
+    let rb = RingBuffer::<[u64; 4], 8>::empty();
+    let (mut tx, mut rx) = rb.split();
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            for i in 0..N {
+                while tx.push([i; 4]).is_some() {
+                    std::thread::yield_now();
+                }
+                // SAFETY: the reader never updates elements in place
+                if let Some(v) = unsafe { tx.sample_latest() } {
+                    assert_eq!(v, [i; 4]);
+                }
+            }
+        });
+        // The samples of the reader are never torn, never go back in time
+        // and are never behind the elements pulled
+        let mut pulled = 0;
+        let mut last = 0;
+        while pulled < N {
+            if let Some(v) = rx.sample_latest() {
+                assert!(v.iter().all(|&x| x == v[0]), "torn sample {v:?}");
+                assert!(v[0] >= last && v[0] >= pulled);
+                last = v[0];
+            }
+            match rx.pull() {
+                Some(v) => {
+                    assert_eq!(v, [pulled; 4]);
+                    pulled += 1;
+                }
+                None => std::thread::yield_now(),
+            }
+        }
+        assert_eq!(rx.sample_latest(), None);
+    });
+}
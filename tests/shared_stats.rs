@@ -0,0 +1,61 @@
+#![cfg(feature = "shared-stats")]
+use ringbuffer_spsc::{Index, RingBuffer, StatsSnapshot};
+
+#[test]
+fn counts() {
+    let rb = RingBuffer::<u32, 4>::empty();
+    assert_eq!(rb.stats(), StatsSnapshot::default());
+    {
+        let (mut tx, mut rx) = rb.split();
+        assert_eq!(rx.pull(), None);
+        assert_eq!(tx.push_slice(&[1, 2, 3, 4, 5]), 4);
+        assert_eq!(tx.push(6), Some(6));
+        assert_eq!(rx.pull(), Some(1));
+        assert_eq!(
+            tx.stats(),
+            StatsSnapshot {
+                pushed: 4,
+                pulled: 1,
+                failed_pushes: 1,
+                failed_pulls: 1,
+                occupancy: 3,
+            }
+        );
+        assert_eq!(rx.stats(), tx.stats());
+    }
+    // The statistics survive the handles
+    assert_eq!(rb.stats().occupancy, 3);
+}
+
+#[test]
+fn snapshots_while_running() {
+    const N: u32 = 100_000;
+    let (mut tx, mut rx) = RingBuffer::<u32, 8>::init();
+    let p = std::thread::spawn(move || {
+        for i in 0..N {
+            while tx.push(i).is_some() {
+                std::thread::yield_now();
+            }
+        }
+        tx
+    });
+    let mut pulled = 0;
+    while pulled < N {
+        let stats = rx.stats();
+        assert!(stats.occupancy <= 8);
+        assert_eq!(
+            stats.pushed.wrapping_sub(stats.pulled),
+            stats.occupancy as Index
+        );
+        match rx.pull() {
+            Some(v) => {
+                assert_eq!(v, pulled);
+                pulled += 1;
+            }
+            None => std::thread::yield_now(),
+        }
+    }
+    let tx = p.join().unwrap();
+    let stats = tx.stats();
+    assert_eq!((stats.pushed, stats.pulled), (N as Index, N as Index));
+}
@@ -0,0 +1,113 @@
+//! Randomized concurrency tests, run with
+//! `RUSTFLAGS="--cfg shuttle" cargo test --test shuttle`.
+#![cfg(shuttle)]
+use ringbuffer_spsc::RingBuffer;
+use shuttle::thread;
+
+const ITERATIONS: usize = 10_000;
+
+#[test]
+fn push_pull() {
+    shuttle::check_random(
+        || {
+            let (mut tx, mut rx) = RingBuffer::<usize, 2>::init();
+            let p = thread::spawn(move || {
+                for i in 0..5 {
+                    while tx.push(i).is_some() {
+                        thread::yield_now();
+                    }
+                }
+            });
+            let mut current = 0;
+            while current < 5 {
+                match rx.pull() {
+                    Some(c) => {
+                        assert_eq!(c, current);
+                        current += 1;
+                    }
+                    None => thread::yield_now(),
+                }
+            }
+            p.join().unwrap();
+        },
+        ITERATIONS,
+    );
+}
+
+#[test]
+fn chunks() {
+    shuttle::check_random(
+        || {
+            let (mut tx, mut rx) = RingBuffer::<usize, 4>::init();
+            let p = thread::spawn(move || {
+                let mut current = 0;
+                while current < 10 {
+                    let n =
+                        tx.push_slice(&[current, current + 1, current + 2][..3.min(10 - current)]);
+                    current += n;
+                    thread::yield_now();
+                }
+            });
+            let mut current = 0;
+            let mut dst = [0; 3];
+            while current < 10 {
+                let n = rx.pull_slice(&mut dst);
+                for &c in &dst[..n] {
+                    assert_eq!(c, current);
+                    current += 1;
+                }
+                thread::yield_now();
+            }
+            p.join().unwrap();
+        },
+        ITERATIONS,
+    );
+}
+
+#[test]
+fn drop_handles() {
+    shuttle::check_random(
+        || {
+            let (mut tx, mut rx) = RingBuffer::<String, 2>::init();
+            let p = thread::spawn(move || {
+                let _ = tx.push("a".to_string());
+                let _ = tx.push("b".to_string());
+            });
+            let c = thread::spawn(move || {
+                let _ = rx.pull();
+            });
+            p.join().unwrap();
+            c.join().unwrap();
+        },
+        ITERATIONS,
+    );
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_wakeups() {
+    // A lost wakeup shows up as a deadlock
+    shuttle::check_random(
+        || {
+            let (mut tx, mut rx) = RingBuffer::<usize, 2>::init();
+            let p = thread::spawn(move || {
+                shuttle::future::block_on(async move {
+                    for i in 0..5 {
+                        tx.push_async(i).await.unwrap();
+                    }
+                })
+            });
+            let current = shuttle::future::block_on(async move {
+                let mut current = 0;
+                while let Some(c) = rx.pull_async().await {
+                    assert_eq!(c, current);
+                    current += 1;
+                }
+                current
+            });
+            assert_eq!(current, 5);
+            p.join().unwrap();
+        },
+        ITERATIONS,
+    );
+}
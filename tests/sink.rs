@@ -0,0 +1,60 @@
+#![cfg(feature = "futures-sink")]
+use futures::{task::noop_waker, Sink, SinkExt};
+use ringbuffer_spsc::{Disconnected, RingBuffer};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[test]
+fn send_all() {
+    const N: usize = 10_000;
+    let (mut tx, mut rx) = RingBuffer::<usize, 16>::init();
+    tx.set_sink_vacancy(8);
+
+    let p = async move {
+        let mut items = futures::stream::iter((0..N).map(Ok));
+        tx.send_all(&mut items).await.unwrap();
+    };
+    let c = async move {
+        let mut current = 0;
+        while let Some(c) = rx.pull_async().await {
+            assert_eq!(c, current);
+            current += 1;
+        }
+        current
+    };
+    let ((), n) = futures::executor::block_on(futures::future::join(p, c));
+    assert_eq!(n, N);
+}
+
+#[test]
+fn vacancy() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 8>::init();
+    tx.set_sink_vacancy(4);
+    assert_eq!(tx.sink_vacancy(), 4);
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    for i in 0..6 {
+        assert_eq!(tx.push(i), None);
+    }
+    assert_eq!(Pin::new(&mut tx).poll_ready(&mut cx), Poll::Pending);
+    rx.pull();
+    assert_eq!(Pin::new(&mut tx).poll_ready(&mut cx), Poll::Pending);
+    rx.pull();
+    assert_eq!(Pin::new(&mut tx).poll_ready(&mut cx), Poll::Ready(Ok(())));
+    assert_eq!(Pin::new(&mut tx).start_send(6), Ok(()));
+
+    // The vacancy is capped by the soft limit
+    tx.set_soft_limit(2);
+    assert_eq!(rx.pull_slice(&mut [0; 8]), 5);
+    assert_eq!(Pin::new(&mut tx).poll_ready(&mut cx), Poll::Ready(Ok(())));
+}
+
+#[test]
+fn disconnected() {
+    let (mut tx, rx) = RingBuffer::<usize, 4>::init();
+    drop(rx);
+    assert_eq!(futures::executor::block_on(tx.send(1)), Err(Disconnected));
+}
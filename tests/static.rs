@@ -0,0 +1,99 @@
+use ringbuffer_spsc::{static_ringbuffer, RingBuffer};
+
+static_ringbuffer!(RB: usize, 16);
+
+#[test]
+fn static_split() {
+    const N: usize = 1_000_000;
+    let (mut tx, mut rx) = RB.split();
+
+    let p = std::thread::spawn(move || {
+        let mut current: usize = 0;
+        while current < N {
+            if tx.push(current).is_none() {
+                current = current.wrapping_add(1);
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    });
+
+    let c = std::thread::spawn(move || {
+        let mut current: usize = 0;
+        while current < N {
+            if let Some(c) = rx.pull() {
+                assert_eq!(c, current);
+                current = current.wrapping_add(1);
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    });
+
+    p.join().unwrap();
+    c.join().unwrap();
+}
+
+#[test]
+fn split_again() {
+    let rb = RingBuffer::<usize, 4>::empty();
+    {
+        let (mut tx, _rx) = rb.split();
+        assert!(tx.push(1).is_none());
+        assert!(tx.push(2).is_none());
+    }
+    let (_tx, mut rx) = rb.split();
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(rx.pull(), Some(2));
+    assert_eq!(rx.pull(), None);
+}
+
+#[test]
+#[should_panic(expected = "already been split")]
+fn split_twice() {
+    let rb = RingBuffer::<usize, 4>::empty();
+    let _halves = rb.split();
+    let _ = rb.split();
+}
+
+#[test]
+fn embedded() {
+    #[derive(Default)]
+    struct Pipeline {
+        stage: RingBuffer<String, 4>,
+    }
+
+    let pipeline = Box::pin(Pipeline::default());
+    let (mut tx, mut rx) = pipeline.stage.split();
+    assert!(tx.push("a".to_string()).is_none());
+    drop(tx);
+    assert_eq!(rx.pull().as_deref(), Some("a"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn scoped() {
+    const N: usize = 100_000;
+    let last = RingBuffer::<usize, 16>::scoped(|mut tx, mut rx| {
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..N {
+                    while tx.push(i).is_some() {
+                        std::thread::yield_now();
+                    }
+                }
+            });
+            let mut current = 0;
+            while current < N {
+                if let Some(c) = rx.pull() {
+                    assert_eq!(c, current);
+                    current += 1;
+                } else {
+                    std::thread::yield_now();
+                }
+            }
+            current
+        })
+    });
+    assert_eq!(last, N);
+}
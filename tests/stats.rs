@@ -0,0 +1,21 @@
+#![cfg(feature = "stats")]
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn index_refreshes() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 8>::init();
+    assert_eq!((tx.index_refreshes(), rx.index_refreshes()), (0, 0));
+
+    // Pushing element by element only reloads the read index once full
+    for i in 0..8 {
+        assert!(tx.push(i).is_none());
+    }
+    assert!(tx.push(8).is_some());
+    assert_eq!(tx.index_refreshes(), 1);
+
+    // The reader reloads the write index once for all the elements in the ring buffer
+    assert_eq!(rx.pull_each(drop), 8);
+    assert_eq!(rx.index_refreshes(), 1);
+    assert!(rx.pull().is_none());
+    assert_eq!(rx.index_refreshes(), 2);
+}
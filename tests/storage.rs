@@ -0,0 +1,120 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{
+    storage::{Boxed, Static, Storage},
+    RingBuffer,
+};
+use std::mem::MaybeUninit;
+
+fn push_pull_threads<S>(rb: &RingBuffer<String, 64, S>)
+where
+    S: Storage<String> + Send + Sync,
+{
+    const N: usize = 10_000;
+    let (mut tx, mut rx) = rb.split();
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            for i in 0..N {
+                let mut t = i.to_string();
+                while let Some(back) = tx.push(t) {
+                    t = back;
+                    std::thread::yield_now();
+                }
+            }
+        });
+        let mut current = 0;
+        while current < N {
+            match rx.pull() {
+                Some(t) => {
+                    assert_eq!(t, current.to_string());
+                    current += 1;
+                }
+                None => std::thread::yield_now(),
+            }
+        }
+    });
+}
+
+#[test]
+fn boxed() {
+    push_pull_threads(&RingBuffer::with_storage(Boxed::new(64)));
+}
+
+#[test]
+fn boxed_from_slice() {
+    let slots = Box::<[u32]>::new_uninit_slice(8);
+    let (mut tx, mut rx) = RingBuffer::<u32, 8, _>::init_with_storage(Boxed::from(slots));
+    assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+    assert_eq!(rx.pull(), Some(1));
+    drop(tx);
+    assert_eq!(rx.pull(), Some(2));
+}
+
+#[test]
+fn static_slice() {
+    static mut SLOTS: [MaybeUninit<String>; 64] = [const { MaybeUninit::uninit() }; 64];
+    // SAFETY: the slots are only borrowed here
+    let slots: &'static mut [MaybeUninit<String>] = unsafe { &mut *std::ptr::addr_of_mut!(SLOTS) };
+    push_pull_threads(&RingBuffer::with_storage(Static::from(slots)));
+}
+
+#[test]
+fn extra_slots_unused() {
+    let rb = RingBuffer::<u8, 4, _>::with_storage(Boxed::new(6));
+    let (mut tx, mut rx) = rb.split();
+    for i in 0..10 {
+        assert!(tx.push(i).is_none());
+        assert_eq!(rx.pull(), Some(i));
+    }
+    assert_eq!(tx.push_slice(&[0; 6]), 4);
+}
+
+#[test]
+fn drops_left_elements() {
+    let (mut tx, rx) = RingBuffer::<String, 4, _>::init_with_storage(Boxed::new(4));
+    assert!(tx.push("a".into()).is_none());
+    assert!(tx.push("b".into()).is_none());
+    drop((tx, rx));
+
+    let rb = RingBuffer::<String, 4, _>::with_storage(Boxed::new(4));
+    let (mut tx, _rx) = rb.split();
+    assert!(tx.push("c".into()).is_none());
+}
+
+#[test]
+fn pump_across_storages() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8, _>::init_with_storage(Boxed::new(8));
+    let (mut relay_tx, mut relay_rx) = RingBuffer::<u8, 4>::init();
+    assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+    assert_eq!(ringbuffer_spsc::pump(&mut rx, &mut relay_tx, 8), 3);
+    assert_eq!(relay_rx.pull(), Some(1));
+}
+
+#[test]
+#[should_panic(expected = "RingBuffer requires a storage of at least 8 slots.")]
+fn storage_too_small() {
+    let _ = RingBuffer::<u8, 8, _>::with_storage(Boxed::new(4));
+}
+
+#[test]
+fn local() {
+    let (mut tx, mut rx) =
+        ringbuffer_spsc::local::RingBuffer::<u8, 4, _>::init_with_storage(Boxed::new(4));
+    assert!(tx.push(1).is_none());
+    assert_eq!(rx.peek(), Some(&1));
+    assert_eq!(rx.pull(), Some(1));
+}
+
+#[cfg(all(feature = "mmap", unix))]
+#[test]
+fn mmap() {
+    use ringbuffer_spsc::storage::Mmap;
+
+    push_pull_threads(&RingBuffer::with_storage(Mmap::new(64).unwrap()));
+
+    let (mut tx, mut rx) = RingBuffer::<u64, 1024, _>::init_with_storage(Mmap::new(1024).unwrap());
+    for i in 0..4096 {
+        assert!(tx.push(i).is_none());
+        assert_eq!(rx.pull(), Some(i));
+    }
+    assert_eq!(Mmap::<()>::new(8).unwrap().capacity(), 8);
+}
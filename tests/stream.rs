@@ -0,0 +1,65 @@
+#![cfg(feature = "futures-stream")]
+use futures::{task::noop_waker, StreamExt};
+use ringbuffer_spsc::RingBuffer;
+use std::task::{Context, Poll};
+
+#[test]
+fn stream_all() {
+    const N: usize = 10_000;
+    let (mut tx, rx) = RingBuffer::<usize, 16>::init();
+
+    let p = async move {
+        for i in 0..N {
+            tx.push_async(i).await.unwrap();
+        }
+    };
+    let c = rx.enumerate().for_each(|(i, c)| {
+        assert_eq!(i, c);
+        async {}
+    });
+    futures::executor::block_on(futures::future::join(p, c));
+}
+
+#[test]
+fn poll_next_many() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 8>::init();
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut dst = Vec::new();
+    assert_eq!(rx.poll_next_many(&mut cx, &mut dst, 4), Poll::Pending);
+
+    for i in 0..6 {
+        assert!(tx.push(i).is_none());
+    }
+    assert_eq!(rx.poll_next_many(&mut cx, &mut dst, 4), Poll::Ready(4));
+    assert_eq!(rx.poll_next_many(&mut cx, &mut dst, 4), Poll::Ready(2));
+    assert_eq!(dst, [0, 1, 2, 3, 4, 5]);
+    drop(tx);
+    assert_eq!(rx.poll_next_many(&mut cx, &mut dst, 4), Poll::Ready(0));
+}
+
+#[test]
+fn ready_chunks() {
+    const N: usize = 10_000;
+    let (mut tx, rx) = RingBuffer::<usize, 16>::init();
+
+    let p = async move {
+        for i in 0..N {
+            tx.push_async(i).await.unwrap();
+        }
+    };
+    let c = async move {
+        let mut chunks = rx.ready_chunks(8);
+        let mut current = 0;
+        while let Some(chunk) = chunks.next().await {
+            assert!(!chunk.is_empty() && chunk.len() <= 8);
+            for c in chunk {
+                assert_eq!(c, current);
+                current += 1;
+            }
+        }
+        current
+    };
+    let ((), n) = futures::executor::block_on(futures::future::join(p, c));
+    assert_eq!(n, N);
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "std")]
+use ringbuffer_spsc::{
+    timed::{TimedReader, TimedWriter},
+    RingBuffer,
+};
+use std::time::Duration;
+
+#[test]
+fn latency() {
+    let (tx, rx) = RingBuffer::init();
+    let (mut tx, mut rx) = (TimedWriter::<u8, 2>::new(tx), TimedReader::new(rx));
+
+    assert!(tx.push(1).is_none());
+    std::thread::sleep(Duration::from_millis(20));
+    assert!(tx.push(2).is_none());
+    assert_eq!(tx.push(3), Some(3));
+
+    let (first, t) = rx.pull_timed().unwrap();
+    assert_eq!(t, 1);
+    assert!(first >= Duration::from_millis(20));
+    let (second, t) = rx.pull_timed().unwrap();
+    assert_eq!(t, 2);
+    assert!(second < first);
+    assert_eq!(rx.pull(), None);
+}
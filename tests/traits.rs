@@ -0,0 +1,111 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{Consumer, Producer, RingBuffer};
+
+fn fill<P: Producer<usize> + ?Sized>(tx: &mut P) -> usize {
+    let mut n = 0;
+    while tx.push(n).is_none() {
+        n += 1;
+    }
+    assert!(tx.is_full());
+    n
+}
+
+fn drain<C: Consumer<usize> + ?Sized>(rx: &mut C) -> Vec<usize> {
+    let mut v = Vec::new();
+    while let Some(t) = rx.pull() {
+        v.push(t);
+    }
+    assert!(rx.is_empty());
+    v
+}
+
+#[test]
+fn arc() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 4>::init();
+    assert_eq!(Producer::capacity(&tx), 4);
+    assert_eq!(fill(&mut tx), 4);
+    assert_eq!(Consumer::slots(&rx), 4);
+    assert_eq!(Consumer::peek(&rx), Some(&0));
+    assert_eq!(drain(&mut rx), [0, 1, 2, 3]);
+}
+
+#[test]
+fn borrowed() {
+    let rb = RingBuffer::<usize, 8>::empty();
+    let (mut tx, mut rx) = rb.split();
+    assert_eq!(fill(&mut tx), 8);
+    assert_eq!(drain(&mut rx), (0..8).collect::<Vec<_>>());
+}
+
+#[test]
+fn boxed() {
+    let (tx, rx) = RingBuffer::<usize, 2>::init();
+    let mut tx: Box<dyn Producer<usize>> = Box::new(tx);
+    let mut rx: Box<dyn Consumer<usize>> = Box::new(rx);
+    assert_eq!(fill(&mut *tx), 2);
+    assert_eq!(drain(&mut *rx), [0, 1]);
+}
+
+#[test]
+fn soft_limit() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 8>::init();
+    tx.set_soft_limit(3);
+    assert_eq!(Producer::slots(&tx), 3);
+    assert_eq!(fill(&mut tx), 3);
+    assert_eq!(Producer::slots(&tx), 0);
+    // The headroom is left to priority pushes
+    assert!(tx.push_priority(3).is_none());
+    assert_eq!(drain(&mut rx), [0, 1, 2, 3]);
+}
+
+#[test]
+fn local() {
+    let (mut tx, mut rx) = ringbuffer_spsc::local::RingBuffer::<usize, 4>::init();
+    assert_eq!(Producer::capacity(&tx), 4);
+    assert_eq!(fill(&mut tx), 4);
+    assert_eq!(Consumer::slots(&rx), 4);
+    assert_eq!(Consumer::peek(&rx), Some(&0));
+    assert_eq!(drain(&mut rx), [0, 1, 2, 3]);
+}
+
+#[test]
+fn heapless() {
+    let mut queue = ringbuffer_spsc::heapless::Queue::<usize, 4>::new();
+    let (mut tx, mut rx) = queue.split();
+    assert_eq!(fill(&mut tx), 4);
+    assert_eq!(Consumer::slots(&rx), 4);
+    assert_eq!(Consumer::peek(&rx), Some(&0));
+    assert_eq!(drain(&mut rx), [0, 1, 2, 3]);
+}
+
+#[test]
+fn isr() {
+    let rb = RingBuffer::<usize, 4>::empty();
+    let (tx, mut rx) = rb.split();
+    let mut tx = ringbuffer_spsc::IsrWriter::new(tx);
+    assert_eq!(fill(&mut tx), 4);
+    // Handed back, not dropped
+    assert_eq!(tx.dropped(), 0);
+    assert_eq!(drain(&mut rx), [0, 1, 2, 3]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn shared() {
+    let (tx, mut rx) = RingBuffer::<usize, 4>::init();
+    let mut tx = ringbuffer_spsc::SharedWriter::new(tx);
+    tx.lock().set_soft_limit(2);
+    assert_eq!(fill(&mut tx), 2);
+    assert_eq!(drain(&mut rx), [0, 1]);
+}
+
+#[test]
+fn burst() {
+    let (tx, mut rx) = RingBuffer::<usize, 4>::init();
+    let mut tx = ringbuffer_spsc::BurstWriter::<_, 4, 2>::new(tx);
+    assert_eq!(Producer::slots(&tx), 6);
+    assert_eq!(fill(&mut tx), 6);
+    assert_eq!(drain(&mut rx), [0, 1, 2, 3]);
+    assert_eq!(tx.flush(), 2);
+    assert_eq!(drain(&mut rx), [4, 5]);
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "postcard")]
+use ringbuffer_spsc::{RingBuffer, TypedError, TypedReceiver, TypedSender};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Message {
+    Ping(u32),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+#[test]
+fn heterogeneous() {
+    let (tx, rx) = RingBuffer::<u8, 64>::init();
+    let (mut tx, mut rx) = (TypedSender::new(tx), TypedReceiver::new(rx));
+    let msgs = [
+        Message::Ping(u32::MAX),
+        Message::Text("abc".into()),
+        Message::Blob(vec![0; 10]),
+    ];
+    for m in &msgs {
+        tx.send(m).unwrap();
+    }
+    for m in msgs {
+        assert_eq!(rx.recv(), Ok(Some(m)));
+    }
+    assert_eq!(rx.recv(), Ok(None));
+    assert!(rx.is_empty());
+}
+
+#[test]
+fn wrap_around() {
+    let rb = RingBuffer::<u8, 32>::empty();
+    let (tx, rx) = rb.split();
+    let (mut tx, mut rx) = (TypedSender::new(tx), TypedReceiver::new(rx));
+    for i in 0..100u8 {
+        let m = Message::Blob(vec![i; i as usize % 20]);
+        tx.send(&m).unwrap();
+        assert_eq!(rx.recv(), Ok(Some(m)));
+    }
+}
+
+#[test]
+fn full_and_too_large() {
+    let (tx, rx) = RingBuffer::<u8, 16>::init();
+    let (mut tx, mut rx) = (TypedSender::new(tx), TypedReceiver::new(rx));
+    assert_eq!(
+        tx.send(&Message::Blob(vec![1; 12])),
+        Err(TypedError::TooLarge)
+    );
+    assert_eq!(tx.slots(), 16);
+
+    let m = Message::Blob(vec![1; 6]);
+    tx.send(&m).unwrap();
+    assert_eq!(tx.send(&m), Err(TypedError::Full));
+    assert_eq!(rx.recv(), Ok(Some(m.clone())));
+    tx.send(&m).unwrap();
+    assert_eq!(rx.recv(), Ok(Some(m)));
+}
+
+#[test]
+fn partial_and_corrupted() {
+    let (mut tx, rx) = RingBuffer::<u8, 16>::init();
+    let mut rx = TypedReceiver::<Message, 16>::new(rx);
+    assert_eq!(tx.push_slice(&[2, 0, 0]), 3);
+    assert_eq!(rx.recv(), Ok(None));
+    assert_eq!(tx.push_slice(&[0, 5]), 2);
+    assert_eq!(rx.recv(), Ok(None));
+    assert_eq!(tx.push_slice(&[7]), 1);
+    assert_eq!(rx.len(), 6);
+    assert!(matches!(rx.recv(), Err(TypedError::Postcard(_))));
+    assert!(rx.is_empty());
+
+    assert_eq!(tx.push_slice(&[0xFF; 4]), 4);
+    assert_eq!(rx.recv(), Err(TypedError::TooLarge));
+    assert!(rx.is_empty());
+}
+
+#[test]
+fn threads() {
+    let (tx, rx) = RingBuffer::<u8, 64>::init();
+    let (mut tx, mut rx) = (TypedSender::new(tx), TypedReceiver::<Message, 64>::new(rx));
+    let producer = std::thread::spawn(move || {
+        for i in 0..1000 {
+            let m = Message::Text(i.to_string());
+            while tx.send(&m) == Err(TypedError::Full) {
+                std::thread::yield_now();
+            }
+        }
+    });
+    for i in 0..1000 {
+        loop {
+            match rx.recv() {
+                Ok(Some(m)) => break assert_eq!(m, Message::Text(i.to_string())),
+                Ok(None) => std::thread::yield_now(),
+                Err(e) => panic!("{e}"),
+            }
+        }
+    }
+    producer.join().unwrap();
+}
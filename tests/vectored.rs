@@ -0,0 +1,102 @@
+#![cfg(feature = "std")]
+use ringbuffer_spsc::RingBuffer;
+use std::io::{ErrorKind, IoSlice, IoSliceMut, Read, Write};
+
+#[test]
+fn wrapping() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    assert_eq!(tx.write(&[0; 6]).unwrap(), 6);
+    assert_eq!(rx.read(&mut [0; 5]).unwrap(), 5);
+
+    // The slices are split across the end of the buffer
+    let bufs = [
+        IoSlice::new(&[1, 2, 3]),
+        IoSlice::new(&[]),
+        IoSlice::new(&[4, 5, 6, 7, 8]),
+    ];
+    assert_eq!(tx.write_vectored(&bufs).unwrap(), 7);
+    assert_eq!(tx.write(&[9]).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    let (mut a, mut b, mut c) = ([0; 2], [0; 3], [0; 4]);
+    let mut bufs = [
+        IoSliceMut::new(&mut a),
+        IoSliceMut::new(&mut b),
+        IoSliceMut::new(&mut c),
+    ];
+    assert_eq!(rx.read_vectored(&mut bufs).unwrap(), 8);
+    assert_eq!((a, b, c), ([0, 1], [2, 3, 4], [5, 6, 7, 0]));
+    assert_eq!(
+        rx.read(&mut [0; 4]).unwrap_err().kind(),
+        ErrorKind::WouldBlock
+    );
+}
+
+#[test]
+fn closed() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    tx.write_all(b"ab").unwrap();
+    drop(tx);
+    let mut out = Vec::new();
+    assert_eq!(rx.read_to_end(&mut out).unwrap(), 2);
+    assert_eq!(out, b"ab");
+
+    let (mut tx, rx) = RingBuffer::<u8, 4>::init();
+    drop(rx);
+    assert_eq!(tx.write(b"a").unwrap_err().kind(), ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn soft_limit() {
+    let (mut tx, _rx) = RingBuffer::<u8, 8>::init();
+    tx.set_soft_limit(3);
+    let bufs = [IoSlice::new(&[1, 2]), IoSlice::new(&[3, 4])];
+    assert_eq!(tx.write_vectored(&bufs).unwrap(), 3);
+}
+
+#[test]
+fn fill_drain() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    let mut src: &[u8] = b"hello world";
+    assert_eq!(tx.fill_from(&mut src).unwrap(), 8);
+    assert_eq!(tx.fill_from(&mut src).unwrap(), 0);
+    assert_eq!(src, b"rld");
+
+    let mut dst = Vec::new();
+    assert_eq!(rx.read(&mut [0; 5]).unwrap(), 5);
+    // Both regions are filled and drained with a single call
+    assert_eq!(tx.fill_from(&mut src).unwrap(), 3);
+    assert_eq!(rx.drain_to(&mut dst).unwrap(), 6);
+    assert_eq!(dst, b" world");
+    assert_eq!(rx.drain_to(&mut dst).unwrap(), 0);
+}
+
+/// Source recording the bytes of the buffers it is handed
+struct Recorder(Vec<u8>);
+
+impl Read for Recorder {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.read_vectored(&mut [IoSliceMut::new(buf)])
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> std::io::Result<usize> {
+        for buf in bufs {
+            self.0.extend_from_slice(buf);
+        }
+        Ok(0)
+    }
+}
+
+#[test]
+fn fill_zeroes_fresh_slots() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init();
+    let mut src = Recorder(Vec::new());
+    assert_eq!(tx.fill_from(&mut src).unwrap(), 0);
+    assert_eq!(src.0, [0; 8]);
+
+    assert_eq!(tx.write(b"abcdef").unwrap(), 6);
+    assert_eq!(rx.read(&mut [0; 4]).unwrap(), 4);
+    // The slots written on the first lap are handed over as they are
+    src.0.clear();
+    assert_eq!(tx.fill_from(&mut src).unwrap(), 0);
+    assert_eq!(src.0, b"\0\0abcd");
+}
@@ -1,4 +1,5 @@
-use ringbuffer_spsc::RingBuffer;
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{Index, RingBuffer};
 
 #[test]
 fn it_works() {
@@ -31,3 +32,318 @@ fn it_works() {
     p.join().unwrap();
     c.join().unwrap();
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn read_exact_into() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+
+    let p = std::thread::spawn(move || {
+        for i in 0..10 {
+            while tx.push(i).is_some() {
+                std::thread::yield_now();
+            }
+        }
+    });
+
+    let mut frame = [0; 6];
+    assert_eq!(rx.read_exact_into(&mut frame), Ok(()));
+    assert_eq!(frame, [0, 1, 2, 3, 4, 5]);
+    // Only 4 elements are left once the writer is dropped
+    assert_eq!(rx.read_exact_into(&mut frame), Err(4));
+    assert_eq!(frame[..4], [6, 7, 8, 9]);
+    p.join().unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn push_blocking_all() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    let c = std::thread::spawn(move || {
+        let mut pulled = Vec::new();
+        while pulled.len() < 20 {
+            match rx.pull() {
+                Some(v) => pulled.push(v),
+                None => std::thread::yield_now(),
+            }
+        }
+        pulled
+    });
+    let src: Vec<u32> = (0..10).collect();
+    assert_eq!(tx.push_blocking_all(&src), Ok(()));
+    assert_eq!(tx.push_blocking_all_cloned(&src), Ok(()));
+    let pulled = c.join().unwrap();
+    assert_eq!(pulled, [&src[..], &src[..]].concat());
+    // Nothing is pushed once the reader is gone
+    assert_eq!(tx.push_blocking_all(&src), Err(0));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wait_drained() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    assert!(tx.wait_drained());
+    for i in 0..3 {
+        assert!(tx.push(i).is_none());
+    }
+    let c = std::thread::spawn(move || {
+        let mut pulled = 0;
+        while pulled < 3 {
+            match rx.pull() {
+                Some(_) => pulled += 1,
+                None => std::thread::yield_now(),
+            }
+        }
+        rx
+    });
+    assert!(tx.wait_drained());
+    assert_eq!(tx.slots(), 4);
+    assert!(tx.push(3).is_none());
+    drop(c.join().unwrap());
+    assert!(!tx.wait_drained());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn spawn_pair() {
+    const N: usize = 1_000_000;
+    let (p, c) = ringbuffer_spsc::spawn_pair::<usize, 16, _, _, _, _>(
+        |mut tx| {
+            let mut current: usize = 0;
+            while current < N {
+                if tx.push(current).is_none() {
+                    current = current.wrapping_add(1);
+                } else {
+                    std::thread::yield_now();
+                }
+            }
+        },
+        |mut rx| {
+            let mut current: usize = 0;
+            while current < N {
+                if let Some(c) = rx.pull() {
+                    assert_eq!(c, current);
+                    current = current.wrapping_add(1);
+                } else {
+                    std::thread::yield_now();
+                }
+            }
+            current
+        },
+    );
+    p.join().unwrap();
+    assert_eq!(c.join().unwrap(), N);
+}
+
+#[test]
+fn soft_limit() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 4>::init();
+    assert_eq!(tx.soft_limit(), 4);
+    tx.set_soft_limit(3);
+
+    assert_eq!(tx.push_slice(&[1, 2, 3, 4]), 3);
+    assert_eq!(tx.push(5), Some(5));
+    // The headroom is reserved to priority elements
+    assert!(tx.push_priority(6).is_none());
+    assert_eq!(tx.push_priority(7), Some(7));
+
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(rx.pull(), Some(2));
+    assert!(tx.push(8).is_none());
+    assert_eq!(tx.push(9), Some(9));
+}
+
+#[test]
+fn watermark() {
+    let (mut tx, mut rx) = RingBuffer::<u32, 8>::init();
+    assert!(!tx.watermark_reached());
+    assert_eq!(tx.push_slice(&[1, 2, 3, 4, 5]), 5);
+    tx.set_watermark(2);
+    assert_eq!(tx.push_slice(&[6, 7]), 2);
+
+    // Elements pushed after arming the watermark are not accounted for
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(rx.pull(), Some(2));
+    assert!(!tx.watermark_reached());
+    assert_eq!(rx.pull(), Some(3));
+    assert!(tx.watermark_reached());
+    assert!(!tx.watermark_reached());
+
+    tx.set_watermark(10);
+    assert!(tx.watermark_reached());
+    tx.set_watermark(0);
+    tx.clear_watermark();
+    assert!(!tx.watermark_reached());
+}
+
+#[test]
+fn wrap_around() {
+    let (mut tx, mut rx) = RingBuffer::<usize, 4>::init_at(Index::MAX - 5);
+    for i in 0..64 {
+        assert!(tx.push(i).is_none());
+        assert!(tx.push(i + 1).is_none());
+        assert_eq!(rx.len(), 2);
+        assert_eq!(rx.pull(), Some(i));
+        assert_eq!(rx.len(), 1);
+        assert_eq!(rx.pull(), Some(i + 1));
+        assert!(rx.is_empty());
+    }
+    assert_eq!(tx.push_slice(&[1, 2, 3, 4, 5]), 4);
+    assert_eq!(tx.push(6), Some(6));
+    assert_eq!(rx.len(), 4);
+}
+
+#[test]
+fn init_with() {
+    let (mut tx, mut rx) = RingBuffer::<Vec<u8>, 4>::init_with(|i| Vec::with_capacity(i + 1));
+    assert_eq!(tx.slots(), 0);
+
+    // Take a buffer from the pool, use it and give it back
+    let mut buf = rx.pull().unwrap();
+    assert_eq!(buf.capacity(), 1);
+    buf.push(1);
+    buf.clear();
+    assert!(tx.push(buf).is_none());
+
+    let capacities: Vec<_> = std::iter::from_fn(|| rx.pull().map(|b| b.capacity())).collect();
+    assert_eq!(capacities[..3], [2, 3, 4]);
+    assert!(capacities[3] >= 1);
+}
+
+#[test]
+fn init_array() {
+    let (mut txs, mut rxs) = RingBuffer::<usize, 2>::init_array::<4>();
+    std::thread::scope(|s| {
+        for (shard, tx) in txs.iter_mut().enumerate() {
+            s.spawn(move || {
+                for i in 0..100 {
+                    while tx.push(shard * 100 + i).is_some() {
+                        std::thread::yield_now();
+                    }
+                }
+            });
+        }
+        // Each reader only sees the elements of its own writer
+        for (shard, rx) in rxs.iter_mut().enumerate() {
+            let mut current = shard * 100;
+            while current < (shard + 1) * 100 {
+                match rx.pull() {
+                    Some(c) => {
+                        assert_eq!(c, current);
+                        current += 1;
+                    }
+                    None => std::thread::yield_now(),
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn std_names() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 2>::init();
+    assert_eq!(tx.push_within_capacity(1), Ok(()));
+    assert_eq!(tx.push_within_capacity(2), Ok(()));
+    assert_eq!(tx.push_within_capacity(3), Err(3));
+    assert_eq!(rx.front(), Some(&1));
+    assert_eq!(rx.pop(), Some(1));
+    assert_eq!(rx.pop(), Some(2));
+    assert_eq!(rx.front(), None);
+    assert_eq!(rx.pop(), None);
+}
+
+#[test]
+fn pull_indexed() {
+    let (mut tx, mut rx) = RingBuffer::<char, 2>::init();
+    for (i, c) in ('a'..='e').enumerate() {
+        assert!(tx.push(c).is_none());
+        assert_eq!(rx.pull_indexed(), Some((i as u64, c)));
+    }
+    assert_eq!(rx.pull_indexed(), None);
+}
+
+#[test]
+fn refresh_indexes() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    assert_eq!(tx.refresh_read_index(), 4);
+    assert_eq!(rx.refresh_write_index(), 0);
+
+    tx.push_slice(&[1, 2, 3]);
+    assert_eq!(rx.refresh_write_index(), 3);
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(tx.refresh_read_index(), 2);
+}
+
+#[test]
+fn xrun_callbacks() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static OVERRUNS: AtomicUsize = AtomicUsize::new(0);
+    static UNDERRUNS: AtomicUsize = AtomicUsize::new(0);
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 2>::init();
+    tx.set_overrun_callback(Some(|| {
+        OVERRUNS.fetch_add(1, Ordering::Relaxed);
+    }));
+    rx.set_underrun_callback(Some(|| {
+        UNDERRUNS.fetch_add(1, Ordering::Relaxed);
+    }));
+
+    assert_eq!(rx.pull(), None);
+    for i in 0..4 {
+        tx.push(i);
+    }
+    assert_eq!(OVERRUNS.load(Ordering::Relaxed), 2);
+    assert_eq!(rx.pull(), Some(0));
+    assert_eq!(rx.pull(), Some(1));
+    assert_eq!(UNDERRUNS.load(Ordering::Relaxed), 1);
+
+    tx.set_overrun_callback(None);
+    tx.push_slice(&[2, 3, 4]);
+    assert!(tx.push(5).is_some());
+    assert_eq!(OVERRUNS.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn barrier_hooks() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static PUBLISHED: AtomicUsize = AtomicUsize::new(0);
+    static CONSUMED: AtomicUsize = AtomicUsize::new(0);
+
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    tx.set_pre_publish_hook(Some(|| {
+        PUBLISHED.fetch_add(1, Ordering::Relaxed);
+    }));
+    rx.set_post_consume_hook(Some(|| {
+        CONSUMED.fetch_add(1, Ordering::Relaxed);
+    }));
+
+    tx.push(0);
+    tx.push_slice(&[1, 2, 3]);
+    // Not called when nothing is published
+    tx.push(4);
+    assert_eq!(PUBLISHED.load(Ordering::Relaxed), 2);
+    assert_eq!(rx.pull(), Some(0));
+    assert_eq!(rx.pull_slice(&mut [0; 4]), 3);
+    assert_eq!(CONSUMED.load(Ordering::Relaxed), 2);
+
+    tx.set_pre_publish_hook(None);
+    rx.set_post_consume_hook(None);
+    tx.push(5);
+    rx.pull();
+    assert_eq!(PUBLISHED.load(Ordering::Relaxed), 2);
+    assert_eq!(CONSUMED.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn capacity() {
+    use ringbuffer_spsc::{RingBufferReader, RingBufferWriter};
+
+    const CAP: usize = RingBuffer::<u8, 16>::CAPACITY;
+    let companion = [0u32; RingBufferWriter::<u8, 16>::CAPACITY * 2];
+    assert_eq!(companion.len(), 2 * CAP);
+    assert_eq!(RingBufferReader::<u8, 16>::MASK, 15);
+
+    let (tx, rx) = RingBuffer::<u8, 16>::init();
+    assert_eq!((tx.capacity(), rx.capacity()), (16, 16));
+    assert_eq!(RingBuffer::<u8, 4>::empty().capacity(), 4);
+}
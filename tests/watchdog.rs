@@ -0,0 +1,20 @@
+#![cfg(feature = "watchdog")]
+use ringbuffer_spsc::RingBuffer;
+use std::time::Duration;
+
+#[test]
+fn stalled_reader() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 4>::init();
+    assert_eq!(tx.time_since_last_push(), None);
+    assert_eq!(rx.time_since_last_pull(), None);
+
+    assert_eq!(tx.push_slice(&[1, 2]), 2);
+    assert_eq!(rx.pull(), Some(1));
+    std::thread::sleep(Duration::from_millis(20));
+    let stalled = tx.time_since_last_pull().unwrap();
+    assert!(stalled >= Duration::from_millis(20));
+
+    assert_eq!(rx.pull(), Some(2));
+    assert!(tx.time_since_last_pull().unwrap() < stalled);
+    assert!(rx.time_since_last_push().unwrap() >= Duration::from_millis(20));
+}
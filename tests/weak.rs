@@ -0,0 +1,137 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::{
+    storage::{Boxed, Storage},
+    RingBuffer,
+};
+use std::{cell::Cell, mem::MaybeUninit, rc::Rc};
+
+/// Boxed slots recording when they are freed
+struct Tracked(Boxed<u32>, Rc<Cell<bool>>);
+
+unsafe impl Storage<u32> for Tracked {
+    fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    fn as_ptr(&self) -> *mut MaybeUninit<u32> {
+        self.0.as_ptr()
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.1.set(true);
+    }
+}
+
+#[test]
+fn liveness() {
+    let (mut tx, rx) = RingBuffer::<usize, 4>::init();
+    let weak = rx.downgrade();
+    assert_eq!(weak.capacity(), 4);
+    assert!(weak.is_empty());
+    assert!(!weak.is_writer_dropped() && !weak.is_reader_dropped());
+
+    assert!(tx.push(1).is_none());
+    assert!(tx.push(2).is_none());
+    assert_eq!(weak.len(), 2);
+    drop(rx);
+    assert!(!weak.is_writer_dropped() && weak.is_reader_dropped());
+    drop(tx);
+    assert!(weak.is_writer_dropped() && weak.is_reader_dropped());
+    // The state the ring buffer was left in
+    assert_eq!(weak.len(), 2);
+}
+
+#[test]
+fn elements_not_kept() {
+    let rc = Rc::new(());
+    let (mut tx, rx) = RingBuffer::<Rc<()>, 4>::init();
+    let weak = tx.downgrade();
+    let other = weak.clone();
+    assert!(tx.push(rc.clone()).is_none());
+    assert!(tx.push(rc.clone()).is_none());
+    assert_eq!(Rc::strong_count(&rc), 3);
+
+    drop((tx, rx));
+    assert_eq!(Rc::strong_count(&rc), 1);
+    drop(weak);
+    assert_eq!(other.len(), 2);
+    drop(other);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn weak_dropped_first() {
+    let rc = Rc::new(());
+    let (mut tx, rx) = RingBuffer::<Rc<()>, 4>::init();
+    drop(rx.downgrade());
+    assert!(tx.push(rc.clone()).is_none());
+    drop((tx, rx));
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn slots_freed() {
+    let freed = Rc::new(Cell::new(false));
+    let storage = Tracked(Boxed::new(4), freed.clone());
+    let (mut tx, rx) = RingBuffer::<u32, 4, _>::init_with_storage(storage);
+    let weak = tx.downgrade();
+    assert!(tx.push(1).is_none());
+    drop(tx);
+    assert!(!freed.get());
+    drop(rx);
+    assert!(freed.get());
+    assert!(weak.is_writer_dropped() && weak.is_reader_dropped());
+    assert_eq!(weak.len(), 1);
+}
+
+#[test]
+fn concurrent_release() {
+    for _ in 0..100 {
+        let (mut tx, rx) = RingBuffer::<Box<usize>, 8>::init();
+        assert!(tx.push(Box::new(1)).is_none());
+        let weaks: Vec<_> = (0..4).map(|_| tx.downgrade()).collect();
+        let threads: Vec<_> = weaks
+            .into_iter()
+            .map(|weak| {
+                std::thread::spawn(move || {
+                    while !weak.is_reader_dropped() {
+                        std::thread::yield_now();
+                    }
+                })
+            })
+            .collect();
+        std::thread::spawn(move || drop(rx)).join().unwrap();
+        drop(tx);
+        for t in threads {
+            t.join().unwrap();
+        }
+    }
+}
+
+#[cfg(feature = "shared-stats")]
+#[test]
+fn stats() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 2>::init();
+    let weak = tx.downgrade();
+    assert!(tx.push(1).is_none());
+    assert!(tx.push(2).is_none());
+    assert!(tx.push(3).is_some());
+    assert_eq!(rx.pull(), Some(1));
+    drop((tx, rx));
+
+    let stats = weak.stats();
+    assert_eq!((stats.pushed, stats.pulled, stats.occupancy), (2, 1, 1));
+    assert_eq!(stats.failed_pushes, 1);
+}
+
+#[cfg(all(feature = "guard-pages", unix))]
+#[test]
+fn guarded() {
+    let (mut tx, rx) = RingBuffer::<u8, 4096>::init_guarded();
+    let weak = rx.downgrade();
+    assert!(tx.push(1).is_none());
+    drop((tx, rx));
+    assert_eq!(weak.len(), 1);
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "alloc")]
+use ringbuffer_spsc::RingBuffer;
+
+#[test]
+fn free_bytes() {
+    let (mut tx, mut rx) = RingBuffer::<u8, 8>::init_zeroed();
+    assert_eq!(tx.free_bytes(), [0; 8]);
+    tx.free_bytes()[..6].copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+    tx.commit_bytes(6);
+
+    for i in 1..=4 {
+        assert_eq!(rx.pull(), Some(i));
+    }
+
+    // The pulled slots keep their bytes, the region wraps around like the raw one
+    assert_eq!(tx.free_bytes(), [0, 0]);
+    tx.commit_bytes(2);
+    assert_eq!(tx.free_bytes(), [1, 2, 3, 4]);
+    assert_eq!(tx.push_slice(&[9, 9, 9]), 3);
+    assert_eq!(tx.slots(), 1);
+    assert_eq!(rx.pull(), Some(5));
+}
+
+#[test]
+#[should_panic(expected = "only 2 slots are free")]
+fn commit_overflow() {
+    let (mut tx, _rx) = RingBuffer::<u8, 4>::init_zeroed();
+    assert_eq!(tx.push_slice(&[1, 2]), 2);
+    tx.commit_bytes(3);
+}
+
+#[test]
+fn split_zeroed() {
+    let rb = RingBuffer::<u8, 4>::empty();
+    {
+        let (mut tx, _rx) = rb.split();
+        assert!(tx.push(7).is_none());
+    }
+
+    // Elements left by the previous split are preserved
+    let (mut tx, mut rx) = rb.split_zeroed();
+    assert_eq!(tx.free_bytes(), [0, 0, 0]);
+    assert_eq!(rx.pull(), Some(7));
+    assert!(tx.into_inner().push(8).is_none());
+    assert_eq!(rx.pull(), Some(8));
+}